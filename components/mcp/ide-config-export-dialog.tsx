@@ -84,12 +84,13 @@ export function IDEConfigExportDialog({ preselectedServers }: IDEConfigExportDia
           return;
         }
 
-        await exportToIDEFormat(selectedServers, clientType, path);
+        const { warnings } = await exportToIDEFormat(selectedServers, clientType, path);
         toast.success(t('exportSuccess', `Exported ${selectedServers.length} server(s) to ${path}`));
+        warnings.forEach(warning => toast.warning(warning));
       } else {
         // Web mode: download as file
-        const json = await exportToIDEFormat(selectedServers, clientType);
-        const blob = new Blob([json], { type: 'application/json' });
+        const { config } = await exportToIDEFormat(selectedServers, clientType);
+        const blob = new Blob([config], { type: 'application/json' });
         const url = URL.createObjectURL(blob);
         const a = document.createElement('a');
         a.href = url;
@@ -92,6 +92,29 @@ export function ServerDetailView({ server, connectionState }: ServerDetailViewPr
                   </div>
                 </div>
               )}
+              {server.sandbox && (
+                <div>
+                  <span className="text-sm font-medium">{t('configuration.sandbox')}</span>
+                  {server.sandbox.block_network && (
+                    <Badge variant="secondary" className="ml-2">
+                      {t('configuration.sandboxNetworkBlocked')}
+                    </Badge>
+                  )}
+                  {server.sandbox.best_effort && (
+                    <Badge variant="outline" className="ml-2">
+                      {t('configuration.sandboxBestEffort')}
+                    </Badge>
+                  )}
+                  <div className="font-mono text-sm bg-muted p-2 rounded mt-1 space-y-1">
+                    <div>
+                      {t('configuration.sandboxRead')} {server.sandbox.allow_read?.join(', ') || '—'}
+                    </div>
+                    <div>
+                      {t('configuration.sandboxWrite')} {[server.sandbox.install_dir ?? server.cwd, ...(server.sandbox.allow_write ?? [])].filter(Boolean).join(', ') || '—'}
+                    </div>
+                  </div>
+                </div>
+              )}
             </>
           )}
 
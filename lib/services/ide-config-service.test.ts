@@ -130,10 +130,13 @@ describe('IDE Config Service', () => {
         },
       ];
 
-      const mockJson = '{"mcpServers":{"Test Server":{"command":"test","args":[]}}}';
+      const mockExport = {
+        config: '{"mcpServers":{"Test Server":{"command":"test","args":[]}}}',
+        warnings: [],
+      };
 
       mockIsTauri.mockReturnValue(true);
-      mockInvoke.mockResolvedValue(mockJson);
+      mockInvoke.mockResolvedValue(mockExport);
 
       const result = await ideConfigService.exportToIDEFormat(
         mockServers,
@@ -146,7 +149,7 @@ describe('IDE Config Service', () => {
         clientType: 'claude-desktop',
         outputPath: '/path/to/output.json',
       });
-      expect(result).toEqual(mockJson);
+      expect(result).toEqual(mockExport);
     });
 
     it('should generate JSON in web mode', async () => {
@@ -168,7 +171,8 @@ describe('IDE Config Service', () => {
 
       const result = await ideConfigService.exportToIDEFormat(mockServers, 'claude-desktop');
 
-      const parsed = JSON.parse(result);
+      expect(result.warnings).toEqual([]);
+      const parsed = JSON.parse(result.config);
       expect(parsed.mcpServers['Test Server']).toEqual({
         command: 'test',
         args: ['--arg'],
@@ -7,7 +7,7 @@
 
 import { isTauri, invoke } from './tauri-bridge';
 import { parseConfiguration, validateConfigFile } from '@/lib/utils/config-parser';
-import type { ConfigDiscovery, ConfigValidation } from '@/lib/types/tauri';
+import type { ConfigDiscovery, ConfigValidation, IDEExport } from '@/lib/types/tauri';
 import type { MCPServerConfig, MCPClientType } from '@/lib/types/mcp';
 
 /**
@@ -114,22 +114,25 @@ export async function importIDEConfig(
 
 /**
  * Export MCP Hub servers to IDE config format
- * Desktop mode: Uses Tauri command for export
+ * Desktop mode: Uses Tauri command for export; `gateway` exports a single
+ * MCP Hub gateway entry instead of every server
  * Web mode: Uses frontend serialization
  */
 export async function exportToIDEFormat(
   servers: MCPServerConfig[],
   clientType: MCPClientType,
-  outputPath?: string
-): Promise<string> {
+  outputPath?: string,
+  gateway?: 'stdio' | 'http'
+): Promise<IDEExport> {
   const serversJson = JSON.stringify(servers);
 
   if (isTauri()) {
     try {
-      return await invoke<string>('export_to_ide_format', {
+      return await invoke<IDEExport>('export_to_ide_format', {
         serversJson,
         clientType,
         outputPath,
+        gateway,
       });
     } catch (error) {
       console.error('Failed to export to IDE format:', error);
@@ -159,6 +162,6 @@ export async function exportToIDEFormat(
     mcpServers[server.name] = config;
   }
 
-  return JSON.stringify({ mcpServers }, null, 2);
+  return { config: JSON.stringify({ mcpServers }, null, 2), warnings: [] };
 }
 
@@ -272,6 +272,15 @@ export async function validateInstallation(
       result.estimatedTime = 1;
       break;
     }
+
+    case 'pypi':
+    case 'release':
+    case 'cargo': {
+      // Python venvs, release archives and cargo builds are handled by the desktop backend only
+      result.valid = false;
+      result.errors.push(`${config.source} installation is only supported in the desktop app`);
+      break;
+    }
   }
 
   return result;
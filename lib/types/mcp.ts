@@ -3,6 +3,8 @@
  * Defines types for all three MCP transport mechanisms: stdio, SSE, and HTTP
  */
 
+import type { SandboxPolicy } from './tauri';
+
 export type MCPTransportType = 'stdio' | 'sse' | 'http';
 
 export type ConnectionStatus =
@@ -56,6 +58,8 @@ export interface StdioMCPServerConfig extends BaseMCPServerConfig {
   args?: string[];
   env?: Record<string, string>;
   cwd?: string;
+  /** Filesystem/network sandbox applied by the desktop app (Linux only) */
+  sandbox?: SandboxPolicy;
 }
 
 /**
@@ -180,7 +184,7 @@ export interface MCPConnectionHistoryEntry {
 /**
  * Installation source types
  */
-export type InstallationSource = 'npm' | 'github' | 'local';
+export type InstallationSource = 'npm' | 'github' | 'local' | 'pypi' | 'release' | 'cargo';
 
 /**
  * Installation status
@@ -237,13 +241,52 @@ export interface LocalInstallConfig {
   validate?: boolean; // Validate path exists and is accessible
 }
 
+/**
+ * Python package installation configuration, installed into an isolated venv
+ */
+export interface PyPIInstallConfig {
+  source: 'pypi';
+  packageName: string;
+  version?: string; // Exact version or a specifier such as ">=1.0"
+  extras?: string[]; // Optional extras, e.g. ["http"]
+  indexUrl?: string; // Custom package index URL
+}
+
+/**
+ * Prebuilt release archive (tar.gz/zip) installation configuration
+ */
+export interface ReleaseInstallConfig {
+  source: 'release';
+  url: string; // May contain {target}, {os}, {arch} and {version} placeholders
+  version?: string;
+  name?: string; // Install directory name, derived from the archive name by default
+  sha256?: string; // Expected SHA-256 of the archive
+  checksumsUrl?: string; // sha256sum-style checksums file, used when sha256 is not given
+  entrypoint?: string; // Executable path inside the archive
+}
+
+/**
+ * Rust crate installation configuration, built with `cargo install`
+ */
+export interface CargoInstallConfig {
+  source: 'cargo';
+  crateName: string;
+  version?: string; // crates.io version; not combined with git
+  features?: string[];
+  git?: string; // Git repository URL to build from instead of crates.io
+  rev?: string; // Git revision, requires git
+}
+
 /**
  * Union type for all installation configurations
  */
 export type InstallConfig =
   | NPMInstallConfig
   | GitHubInstallConfig
-  | LocalInstallConfig;
+  | LocalInstallConfig
+  | PyPIInstallConfig
+  | ReleaseInstallConfig
+  | CargoInstallConfig;
 
 /**
  * Installation progress information
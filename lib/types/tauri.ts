@@ -77,6 +77,32 @@ export interface MCPServerProcess {
   cpuUsage?: number;
   uptime?: number;
   output?: string;
+  health?: MCPHealthStatus;
+  /** Survivor of a previous session, monitored only (no stdio, no restarts) */
+  adopted?: boolean;
+  /** Running under its Landlock/seccomp sandbox policy */
+  sandboxed?: boolean;
+  /** Hash of the launch config */
+  config_hash?: string;
+  /** True when servers.json now holds a different config for this server */
+  config_drift?: boolean;
+  /** Endpoint of a monitored remote (SSE / Streamable HTTP) server */
+  remote_url?: string;
+}
+
+/**
+ * MCP-level readiness and liveness of a stdio server
+ */
+export interface MCPHealthStatus {
+  ready: boolean;
+  protocol_version?: string;
+  server_name?: string;
+  server_version?: string;
+  capabilities?: Record<string, unknown>;
+  handshake_latency_ms?: number;
+  last_ping_at?: string;
+  last_ping_latency_ms?: number;
+  consecutive_ping_failures: number;
 }
 
 /**
@@ -85,8 +111,270 @@ export interface MCPServerProcess {
 export interface StdioConfig {
   command: string;
   args?: string[];
+  /** Values may reference stored `api_key_*` / `credential_*` secrets as `${keyring:<key>}`, resolved only when spawning */
   env?: Record<string, string>;
   cwd?: string;
+  restart?: RestartPolicy;
+  health?: HealthCheckConfig;
+  /** Milliseconds between SIGTERM and SIGKILL when stopping (default 5000) */
+  stop_grace_ms?: number;
+  limits?: ResourceLimits;
+  sandbox?: SandboxPolicy;
+}
+
+/**
+ * Sandbox for untrusted servers (Linux only): Landlock confines the filesystem to system
+ * directories, the install directory and the allowlists; seccomp optionally blocks IPv4/IPv6.
+ */
+export interface SandboxPolicy {
+  /** Readable and writable; defaults to cwd */
+  install_dir?: string;
+  /** Only the executable (and its version-manager runtime) is readable by default; grant the rest here */
+  allow_read?: string[];
+  allow_write?: string[];
+  block_network?: boolean;
+  /** Allow creating Unix sockets such as D-Bus (default false) */
+  allow_unix_sockets?: boolean;
+  /** Read /usr, /etc, ... and write /tmp, /dev/null and similar devices (default true) */
+  system_paths?: boolean;
+  /** Start unsandboxed when Landlock is unavailable instead of failing (default false) */
+  best_effort?: boolean;
+}
+
+/**
+ * Remote server probed periodically with `initialize` / `ping`
+ */
+export interface RemoteConfig {
+  url: string;
+  /** Legacy HTTP+SSE or Streamable HTTP (default 'sse') */
+  transport?: 'sse' | 'http';
+  /** Values may reference stored `api_key_*` / `credential_*` secrets as `${keyring:<key>}` */
+  headers?: Record<string, string>;
+  /** Handshake timeout, probe interval and failures before reporting an error */
+  health?: HealthCheckConfig;
+}
+
+/**
+ * A stdio server exposed as a Streamable HTTP endpoint on 127.0.0.1
+ */
+export interface BridgeInfo {
+  server_id: string;
+  /** e.g. http://127.0.0.1:4123/mcp */
+  url: string;
+  port: number;
+  /** Bearer token clients must send; kept in the system keyring */
+  token: string;
+  sessions: number;
+  started_at: string;
+}
+
+/**
+ * Effective sandbox of a server config on this machine
+ */
+export interface SandboxSummary {
+  landlock_abi?: number;
+  read_paths: string[];
+  write_paths: string[];
+  block_network: boolean;
+  block_unix_sockets: boolean;
+  enforced: boolean;
+}
+
+/**
+ * Per-server resource caps. Address space, CPU and open files are kernel limits (Linux);
+ * RSS and process count are enforced by the supervisor.
+ */
+export interface ResourceLimits {
+  max_address_space_mb?: number;
+  max_rss_mb?: number;
+  max_cpu_seconds?: number;
+  max_open_files?: number;
+  max_processes?: number;
+}
+
+/**
+ * Initialize handshake and ping settings for stdio servers
+ */
+export interface HealthCheckConfig {
+  handshake?: boolean;
+  startup_timeout_ms?: number;
+  ping_interval_ms?: number;
+  ping_timeout_ms?: number;
+  max_ping_failures?: number;
+}
+
+/**
+ * Automatic restart policy for crashed stdio servers
+ */
+export interface RestartPolicy {
+  mode?: 'never' | 'on-failure' | 'always';
+  max_retries?: number;
+  backoff_base_ms?: number;
+  backoff_max_ms?: number;
+  reset_after_secs?: number;
+}
+
+/**
+ * A captured line of MCP server output
+ */
+export interface MCPOutputLine {
+  seq: number;
+  timestamp: string;
+  stream: 'stdout' | 'stderr';
+  line: string;
+}
+
+/**
+ * Payload of the `mcp-server-output` event
+ */
+export interface MCPOutputEvent extends MCPOutputLine {
+  server_id: string;
+}
+
+/**
+ * Payload of the `mcp-install-output` event, one per line npm/git write during an installation.
+ * Progress changes are emitted as `mcp-install-progress` with the full installation progress.
+ */
+export interface InstallOutputEvent {
+  install_id: string;
+  stream: 'stdout' | 'stderr';
+  line: string;
+}
+
+/**
+ * A resource usage sample of an MCP server's process tree
+ */
+export interface MCPMetricSample {
+  timestamp: string;
+  memory_bytes: number;
+  cpu_percent: number;
+  process_count: number;
+}
+
+/**
+ * Resource usage history of an MCP server
+ */
+export interface MCPServerMetrics {
+  server_id: string;
+  pid?: number;
+  samples: MCPMetricSample[];
+  runaway: boolean;
+  runaway_reason?: string;
+}
+
+/**
+ * Lifecycle settings persisted by the desktop app
+ */
+export interface LifecycleSettings {
+  /** What to do with servers still running after a crash (detected on Linux, macOS and Windows): reattach as adopted, or stop them */
+  orphan_policy: 'adopt' | 'kill';
+  logs?: LogRotation;
+}
+
+/**
+ * Rotation and retention of the per-server log files under `logs/<server_id>/`
+ */
+export interface LogRotation {
+  enabled?: boolean;
+  max_file_bytes?: number;
+  rotate_after_hours?: number;
+  /** Rotated files kept per server */
+  max_files?: number;
+  max_age_days?: number;
+  /** Gzip rotated files */
+  compress?: boolean;
+}
+
+export interface LogFileInfo {
+  server_id: string;
+  /** File name to pass to `mcp_read_log` */
+  name: string;
+  size: number;
+  modified?: string;
+  compressed: boolean;
+  current: boolean;
+}
+
+/**
+ * One finished run of a managed server, from the persisted run history
+ */
+export interface MCPRunRecord {
+  server_id: string;
+  started_at?: string;
+  stopped_at?: string;
+  uptime_secs?: number;
+  exit_code?: number;
+  signal?: string;
+  /** Explicit start, or restart and what it recovered from */
+  start_cause: string;
+  end_reason: string;
+  /** Ended other than by a clean exit or an explicit stop */
+  crashed: boolean;
+  restart_count: number;
+  /** Last stderr lines at the time of a crash */
+  stderr_tail: string[];
+}
+
+/**
+ * Servers started and stopped together, in dependency order
+ */
+export interface ServerGroup {
+  id: string;
+  name: string;
+  members: GroupMember[];
+  /** Start the group when the app launches */
+  autostart?: boolean;
+}
+
+export interface GroupMember {
+  server_id: string;
+  /** Members that must be running before this one starts */
+  depends_on?: string[];
+  /** Launch config; defaults to the server's entry in servers.json */
+  config?: StdioConfig;
+}
+
+/**
+ * Outcome of starting or stopping one group member
+ */
+export interface GroupMemberResult {
+  server_id: string;
+  success: boolean;
+  state?: MCPServerProcess['state'];
+  error?: string;
+}
+
+/**
+ * Payload of the `mcp-server-state` event, emitted on every lifecycle transition
+ */
+export interface MCPStateChangeEvent {
+  server_id: string;
+  /** null when the server was not known before */
+  previous: MCPServerProcess['state'] | null;
+  state: MCPServerProcess['state'];
+  reason: string | null;
+  exit_code: number | null;
+  /** Signal that terminated the process, e.g. "SIGKILL" */
+  signal: string | null;
+  timestamp: string;
+}
+
+/**
+ * Result of stopping a server's process tree
+ */
+export interface MCPStopOutcome {
+  /** null when the process had already exited */
+  signal: 'SIGTERM' | 'SIGKILL' | null;
+  exit_code: number | null;
+  elapsed_ms: number;
+}
+
+/**
+ * Payload of the `mcp-server-notification` and `mcp-server-request` events
+ */
+export interface MCPServerMessageEvent {
+  server_id: string;
+  message: Record<string, unknown>;
 }
 
 /**
@@ -116,7 +404,7 @@ export interface RegistryServerEntry {
 export interface InstallationMetadata {
   serverId: string;
   installId: string;
-  sourceType: 'npm' | 'github' | 'local';
+  sourceType: 'npm' | 'github' | 'local' | 'pypi' | 'release' | 'cargo';
   installPath: string;
   packageName?: string;
   repository?: string;
@@ -125,6 +413,7 @@ export interface InstallationMetadata {
   clientType?: string;
   originalConfig?: string;
   configSourcePath?: string;
+  entrypoint?: string; // Executable to launch, for sources that install a binary
 }
 
 /**
@@ -150,6 +439,16 @@ export interface ConfigValidation {
   serverCount?: number;
 }
 
+/**
+ * IDE config export result
+ */
+export interface IDEExport {
+  /** The client config, as JSON */
+  config: string;
+  /** e.g. that the HTTP gateway token is written in plaintext for this client */
+  warnings: string[];
+}
+
 /**
  * Tauri Commands
  * All available Tauri commands that can be invoked from the frontend
@@ -214,10 +513,39 @@ export interface TauriCommands {
 
   // MCP lifecycle commands (stdio processes)
   mcp_start_server: (serverId: string, cfg: StdioConfig) => Promise<MCPServerProcess>;
-  mcp_stop_server: (serverId: string, force?: boolean) => Promise<void>;
+  mcp_stop_server: (serverId: string, force?: boolean) => Promise<MCPStopOutcome>;
   mcp_restart_server: (serverId: string, cfg?: StdioConfig) => Promise<MCPServerProcess>;
   mcp_get_status: (serverId: string) => Promise<MCPServerProcess>;
   mcp_list_running: () => Promise<MCPServerProcess[]>;
+  mcp_get_output: (serverId: string, tail?: number, fromSeq?: number, limit?: number) => Promise<MCPOutputLine[]>;
+  mcp_clear_output: (serverId: string) => Promise<void>;
+  mcp_get_metrics: (serverId: string) => Promise<MCPServerMetrics>;
+  mcp_send_request: (serverId: string, method: string, params?: unknown, timeoutMs?: number) => Promise<unknown>;
+  mcp_send_notification: (serverId: string, method: string, params?: unknown) => Promise<void>;
+  mcp_send_response: (serverId: string, id: string | number, result?: unknown, error?: unknown) => Promise<void>;
+  mcp_describe_sandbox: (cfg: StdioConfig) => Promise<SandboxSummary>;
+  /** Status is then reported by mcp_get_status / mcp_list_running; mcp_stop_server ends it */
+  mcp_start_remote_monitor: (serverId: string, cfg: RemoteConfig) => Promise<MCPServerProcess>;
+  /** Port 0 or omitted picks a free one */
+  mcp_start_bridge: (serverId: string, port?: number) => Promise<BridgeInfo>;
+  mcp_stop_bridge: (serverId: string) => Promise<void>;
+  mcp_list_bridges: () => Promise<BridgeInfo[]>;
+  /** Serve every enabled server as one MCP server over HTTP; names are namespaced `<server>__<name>` */
+  mcp_start_gateway: (port?: number) => Promise<BridgeInfo>;
+  mcp_stop_gateway: () => Promise<void>;
+  mcp_gateway_status: () => Promise<BridgeInfo | null>;
+  get_server_run_history: (serverId: string, limit?: number, crashesOnly?: boolean) => Promise<MCPRunRecord[]>;
+  mcp_list_logs: (serverId?: string) => Promise<LogFileInfo[]>;
+  mcp_read_log: (serverId: string, name?: string, tail?: number) => Promise<string>;
+  mcp_export_logs: (serverId: string, destination: string) => Promise<void>;
+  mcp_purge_logs: (serverId?: string, olderThanDays?: number) => Promise<number>;
+  mcp_list_groups: () => Promise<ServerGroup[]>;
+  mcp_save_group: (group: ServerGroup) => Promise<void>;
+  mcp_delete_group: (groupId: string) => Promise<void>;
+  mcp_start_group: (groupId: string) => Promise<GroupMemberResult[]>;
+  mcp_stop_group: (groupId: string) => Promise<GroupMemberResult[]>;
+  mcp_get_lifecycle_settings: () => Promise<LifecycleSettings>;
+  mcp_set_lifecycle_settings: (settings: LifecycleSettings) => Promise<void>;
 
   // MCP installer commands
   validate_install: (args: { config: Record<string, unknown> }) => Promise<unknown>;
@@ -238,7 +566,12 @@ export interface TauriCommands {
   discover_ide_configs: () => Promise<ConfigDiscovery[]>;
   validate_ide_config: (path: string, clientType?: string) => Promise<ConfigValidation>;
   import_ide_config: (path: string, clientType: string, mergeStrategy?: string) => Promise<string>;
-  export_to_ide_format: (serversJson: string, clientType: string, outputPath?: string) => Promise<string>;
+  /**
+   * `gateway` exports one MCP Hub gateway entry instead of every server. The HTTP entry
+   * references its token as `${env:MCP_HUB_GATEWAY_TOKEN}` for VS Code and Cursor and
+   * embeds it for other clients
+   */
+  export_to_ide_format: (serversJson: string, clientType: string, outputPath?: string, gateway?: 'stdio' | 'http') => Promise<IDEExport>;
   validate_config_path: (path: string, mustExist?: boolean) => Promise<string>;
 }
 
@@ -53,13 +53,22 @@ const next = require('next');
 const dir = process.cwd(); // set by Tauri to the resources directory
 const dev = false;
 const port = process.env.PORT || 3000;
+const host = process.env.HOST || '127.0.0.1';
 
 const app = next({ dev, dir });
 const handle = app.getRequestHandler();
 
 app.prepare().then(() => {
-  http.createServer((req, res) => handle(req, res)).listen(port, () => {
-    console.log('[tauri-server] Next app listening on port ' + port);
+  http.createServer((req, res) => {
+    // Polled by the desktop shell's supervisor (src-tauri/src/next_server.rs)
+    if (req.url === '/__tauri/health') {
+      res.writeHead(200, { 'Content-Type': 'application/json' });
+      res.end('{"status":"ok"}');
+      return;
+    }
+    handle(req, res);
+  }).listen(port, host, () => {
+    console.log('[tauri-server] Next app listening on ' + host + ':' + port);
   });
 }).catch((err) => {
   console.error('[tauri-server] Failed to start Next app', err);
@@ -33,7 +33,6 @@ impl ClientType {
         }
     }
 
-    #[allow(dead_code)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s {
             "mcp-hub" => Some(ClientType::McpHub),
@@ -97,6 +96,21 @@ pub struct ConfigValidation {
     pub server_count: Option<usize>,
 }
 
+/// Export result: the client config, plus anything the user should know before using it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IDEExport {
+    pub config: String,
+    pub warnings: Vec<String>,
+}
+
+/// Environment variable the exported HTTP gateway entry reads its bearer token from
+const GATEWAY_TOKEN_ENV: &str = "MCP_HUB_GATEWAY_TOKEN";
+
+/// Whether a client expands `${env:NAME}` in remote server headers
+fn expands_env_refs(client_type: &str) -> bool {
+    matches!(ClientType::from_str(client_type), Some(ClientType::Vscode | ClientType::Cursor))
+}
+
 /// Get the default config path for a given client type
 pub fn get_default_config_path(client_type: &ClientType) -> Result<PathBuf, String> {
     match client_type {
@@ -468,21 +482,78 @@ pub fn import_ide_config(
         .map_err(|e| format!("Failed to serialize servers: {}", e))
 }
 
-/// Export MCP Hub servers to IDE config format
+/// Single entry pointing a client at the MCP Hub gateway instead of at each server.
+/// `"stdio"` launches this executable with the gateway flag; `"http"` uses the running HTTP gateway.
+/// The HTTP entry references the bearer token through `GATEWAY_TOKEN_ENV` for clients that expand
+/// env references; other clients get the token itself, which is reported in `warnings`.
+fn gateway_entry(transport: &str, client_type: &str, warnings: &mut Vec<String>) -> Result<IDEServerConfig, String> {
+    match transport {
+        "stdio" => {
+            let exe = std::env::current_exe()
+                .map_err(|e| format!("Failed to locate the MCP Hub executable: {}", e))?;
+            Ok(IDEServerConfig {
+                command: Some(exe.to_string_lossy().to_string()),
+                args: vec![crate::mcp_gateway::GATEWAY_FLAG.to_string()],
+                env: HashMap::new(),
+                cwd: None,
+                url: None,
+                headers: HashMap::new(),
+                transport: None,
+            })
+        }
+        "http" => {
+            let info = crate::mcp_bridge::gateway_info()
+                .ok_or_else(|| "The HTTP gateway is not running".to_string())?;
+            let token = if expands_env_refs(client_type) {
+                warnings.push(format!(
+                    "Set {} to the HTTP gateway token in the environment {} is started from",
+                    GATEWAY_TOKEN_ENV, client_type
+                ));
+                format!("${{env:{}}}", GATEWAY_TOKEN_ENV)
+            } else {
+                warnings.push(format!(
+                    "{} can't read the token from the environment, so the HTTP gateway token is written in plaintext; anyone who can read the exported file can use the gateway",
+                    client_type
+                ));
+                info.token
+            };
+            Ok(IDEServerConfig {
+                command: None,
+                args: Vec::new(),
+                env: HashMap::new(),
+                cwd: None,
+                url: Some(info.url),
+                headers: HashMap::from([("Authorization".to_string(), format!("Bearer {}", token))]),
+                transport: Some("http".to_string()),
+            })
+        }
+        other => Err(format!("Unknown gateway transport: {}", other)),
+    }
+}
+
+/// Export MCP Hub servers to IDE config format.
+/// With `gateway` ("stdio" or "http"), export one gateway entry aggregating every enabled server.
 #[tauri::command]
 pub fn export_to_ide_format(
     servers_json: String,
-    _client_type: String,
+    client_type: String,
     output_path: Option<String>,
-) -> Result<String, String> {
+    gateway: Option<String>,
+) -> Result<IDEExport, String> {
     // Parse servers JSON
     let servers: Vec<serde_json::Value> = serde_json::from_str(&servers_json)
         .map_err(|e| format!("Failed to parse servers JSON: {}", e))?;
 
     // Convert to IDE format
     let mut mcp_servers = HashMap::new();
+    let mut warnings = Vec::new();
+
+    if let Some(transport) = &gateway {
+        mcp_servers.insert(crate::mcp_gateway::GATEWAY_NAME.to_string(), gateway_entry(transport, &client_type, &mut warnings)?);
+    }
 
-    for server in servers {
+    // The gateway entry stands in for the individual servers
+    for server in servers.into_iter().filter(|_| gateway.is_none()) {
         let name = server["name"]
             .as_str()
             .ok_or_else(|| "Server missing name field".to_string())?;
@@ -566,7 +637,7 @@ pub fn export_to_ide_format(
         log::info!("Exported IDE config to: {}", path);
     }
 
-    Ok(json_output)
+    Ok(IDEExport { config: json_output, warnings })
 }
 
 /// Cross-platform path validation and normalization
@@ -619,6 +690,18 @@ mod tests {
         assert_eq!(parsed, Some(ClientType::Vscode));
     }
 
+    #[test]
+    fn test_gateway_token_reference_support() {
+        assert!(expands_env_refs("vscode"));
+        assert!(expands_env_refs("cursor"));
+        assert!(!expands_env_refs("claude-desktop"));
+        assert!(!expands_env_refs("unknown"));
+
+        let exported = export_to_ide_format(r#"[{"name":"s","command":"node"}]"#.to_string(), "cline".to_string(), None, None).unwrap();
+        assert!(exported.config.contains("\"node\""));
+        assert!(exported.warnings.is_empty());
+    }
+
     #[test]
     fn test_ide_config_parsing() {
         let config_json = r#"{
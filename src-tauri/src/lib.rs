@@ -3,6 +3,21 @@ mod storage;
 mod file_dialogs;
 mod secure_storage;
 mod mcp_lifecycle;
+mod mcp_bridge;
+mod mcp_gateway;
+mod mcp_groups;
+mod mcp_limits;
+mod mcp_logs;
+mod mcp_metrics;
+mod mcp_output;
+mod mcp_remote;
+mod mcp_rpc;
+mod mcp_sandbox;
+mod process_tree;
+mod process_registry;
+mod release_archive;
+mod shutdown;
+mod next_server;
 mod mcp_installer;
 mod mcp_registry;
 mod ide_config;
@@ -11,11 +26,21 @@ use updates::UpdateState;
 
 use tauri::Manager;
 
+pub use mcp_gateway::GATEWAY_FLAG;
+
+/// Run as a headless MCP gateway over stdio (see `GATEWAY_FLAG`); returns the exit code
+pub fn run_gateway() -> i32 {
+  mcp_gateway::serve_stdio()
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
   tauri::Builder::default()
     .manage(UpdateState::default())
     .setup(|app| {
+      mcp_lifecycle::init(app.handle());
+      mcp_logs::init(app.handle());
+
       if cfg!(debug_assertions) {
         app.handle().plugin(
           tauri_plugin_log::Builder::default()
@@ -24,6 +49,11 @@ pub fn run() {
         )?;
       }
 
+      // Reattach to or clean up servers left running by a crashed session, then bring up
+      // server groups flagged for autostart, in dependency order
+      let autostart_groups = mcp_groups::autostart_groups(app.handle());
+      mcp_lifecycle::recover(app.handle(), move || mcp_groups::autostart(autostart_groups));
+
       #[cfg(desktop)]
       {
         app.handle().plugin(tauri_plugin_updater::Builder::new().build())?;
@@ -76,89 +106,13 @@ pub fn run() {
           }
         });
 
-        // Launch embedded Next.js server then open the window once it's ready
-        use std::{net::TcpStream, path::PathBuf, thread, time::Duration, process::Command};
-
-        let app_handle = app.handle().clone();
-        thread::spawn(move || {
-          let port = std::env::var("TAURI_NEXT_PORT").unwrap_or_else(|_| "34115".to_string());
-
-          // Resolve resource directory where we bundled .next and public
-          let resource_dir: PathBuf = match app_handle.path().resource_dir() {
-            Ok(p) => p,
-            Err(e) => {
-              log::error!("Failed to resolve resource_dir: {e}");
-              return;
-            }
-          };
-
-          // Node binary path (bundled), fallback to system 'node'
-          #[cfg(target_os = "windows")]
-          let node_name = "node.exe";
-          #[cfg(not(target_os = "windows"))]
-          let node_name = "node";
-          let bundled_node = resource_dir.join("resources").join("node").join(node_name);
-          let node_path = if bundled_node.exists() { bundled_node } else { PathBuf::from("node") };
-
-          // Server entry (our small launcher written by scripts/tauri-build.js)
-          let server_js = resource_dir.join("resources").join("server.js");
-          if !server_js.exists() {
-            log::error!("Server launcher not found at {:?}", server_js);
-            return;
-          }
-
-          // Spawn the Next server
-          let mut child = match Command::new(&node_path)
-            .arg(&server_js)
-            .env("PORT", &port)
-            .current_dir(&resource_dir)
-            .spawn()
-          {
-            Ok(c) => c,
-            Err(e) => {
-              log::error!("Failed to spawn Next server with {:?}: {}", node_path, e);
-              return;
-            }
-          };
-
-          // Wait for the port to be available
-          let addr = format!("127.0.0.1:{}", port);
-          let mut ready = false;
-          for _ in 0..200 { // ~20s
-            if TcpStream::connect(&addr).is_ok() { ready = true; break; }
-            thread::sleep(Duration::from_millis(100));
-          }
-          if !ready {
-            log::error!("Next server did not become ready at {}", addr);
-            let _ = child.kill();
-            return;
-          }
-
-          // Create the main window pointing to the local Next server
-          let url_str = format!("http://{}", addr);
-          // Use try! equivalent pattern to handle URL parsing gracefully
-          let webview_url: tauri::WebviewUrl = match url_str.as_str().try_into() {
-            Ok(url) => tauri::WebviewUrl::External(url),
-            Err(e) => {
-              log::error!("Failed to parse Next server URL '{}': {}", url_str, e);
-              return;
-            }
-          };
-
-          match tauri::WebviewWindowBuilder::new(&app_handle, "main", webview_url)
-            .title("mcp-hub-next")
-            .build() {
-            Ok(win) => { let _ = win.set_focus(); },
-            Err(e) => log::error!("Failed to create main window: {}", e),
-          }
-
-          // NOTE: We no longer hook an on-exit handler here (Tauri v2 has no AppHandle::on_exit).
-          // If needed, we could listen for window events and kill the child then.
-        });
+        // Launch the embedded Next.js server; the window shows a status page until it is healthy
+        next_server::start(app.handle().clone());
       }
 
       Ok(())
     })
+    .register_uri_scheme_protocol(next_server::STATUS_SCHEME, |_ctx, _request| next_server::status_response())
     .invoke_handler(tauri::generate_handler![
       // Update commands
       updates::get_app_version,
@@ -219,6 +173,32 @@ pub fn run() {
       mcp_lifecycle::mcp_restart_server,
       mcp_lifecycle::mcp_get_status,
       mcp_lifecycle::mcp_list_running,
+      mcp_output::mcp_get_output,
+      mcp_output::mcp_clear_output,
+      mcp_metrics::mcp_get_metrics,
+      mcp_rpc::mcp_send_request,
+      mcp_rpc::mcp_send_notification,
+      mcp_rpc::mcp_send_response,
+      mcp_sandbox::mcp_describe_sandbox,
+      mcp_remote::mcp_start_remote_monitor,
+      mcp_bridge::mcp_start_bridge,
+      mcp_bridge::mcp_stop_bridge,
+      mcp_bridge::mcp_list_bridges,
+      mcp_gateway::mcp_start_gateway,
+      mcp_gateway::mcp_stop_gateway,
+      mcp_gateway::mcp_gateway_status,
+      storage::get_server_run_history,
+      mcp_logs::mcp_list_logs,
+      mcp_logs::mcp_read_log,
+      mcp_logs::mcp_export_logs,
+      mcp_logs::mcp_purge_logs,
+      mcp_groups::mcp_list_groups,
+      mcp_groups::mcp_save_group,
+      mcp_groups::mcp_delete_group,
+      mcp_groups::mcp_start_group,
+      mcp_groups::mcp_stop_group,
+      process_registry::mcp_get_lifecycle_settings,
+      process_registry::mcp_set_lifecycle_settings,
       // MCP installer
       mcp_installer::validate_install,
       mcp_installer::install_server,
@@ -239,6 +219,12 @@ pub fn run() {
       ide_config::export_to_ide_format,
       ide_config::validate_config_path,
     ])
-    .run(tauri::generate_context!())
-    .expect("error while running tauri application");
+    .build(tauri::generate_context!())
+    .expect("error while building tauri application")
+    .run(|_app, event| {
+      // Stop MCP servers and the Next.js server before the process goes away
+      if let tauri::RunEvent::Exit = event {
+        shutdown::shutdown_all();
+      }
+    });
 }
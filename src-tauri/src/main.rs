@@ -2,5 +2,9 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+  // MCP clients launch the hub with this flag to use it as a single aggregated server
+  if std::env::args().any(|arg| arg == app_lib::GATEWAY_FLAG) {
+    std::process::exit(app_lib::run_gateway());
+  }
   app_lib::run();
 }
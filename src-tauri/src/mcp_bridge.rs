@@ -0,0 +1,609 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Read, Write},
+    net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc, Arc, Mutex, OnceLock,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{mcp_gateway, mcp_lifecycle};
+use crate::mcp_rpc::{self, RpcChannel};
+use crate::secure_storage;
+
+const MCP_PATH: &str = "/mcp";
+const SESSION_HEADER: &str = "mcp-session-id";
+/// Largest request body accepted from a client
+const MAX_BODY_BYTES: usize = 4 * 1024 * 1024;
+/// Longest request or header line accepted from a client
+const MAX_HEADER_LINE: usize = 8 * 1024;
+/// Largest request line plus headers accepted from a client
+const MAX_HEADER_BYTES: usize = 64 * 1024;
+/// Time allowed for a client to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+/// Comment sent on idle event streams so dead clients are noticed
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+/// Sessions without an open event stream are dropped after this long without requests
+const SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(3600);
+/// Connections served at once by one bridge (each holds a thread); further ones get a 503
+const MAX_CONNECTIONS: usize = 64;
+
+/// A running bridge as reported to the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct BridgeInfo {
+    pub server_id: String,
+    /// Streamable HTTP endpoint, e.g. `http://127.0.0.1:4123/mcp`
+    pub url: String,
+    pub port: u16,
+    /// Bearer token clients must send; stored in the system keyring
+    pub token: String,
+    pub sessions: usize,
+    pub started_at: String,
+}
+
+struct ClientSession {
+    last_seen: Instant,
+    /// Open GET event streams receiving the server's notifications
+    streams: Vec<mpsc::Sender<String>>,
+    /// Requests awaiting the server, from the client's id (as JSON) to the channel's id
+    in_flight: HashMap<String, u64>,
+}
+
+type Sessions = Arc<Mutex<HashMap<String, ClientSession>>>;
+
+/// What a bridge serves
+#[derive(Debug, Clone)]
+enum Target {
+    /// One managed stdio server, by id
+    Server(String),
+    /// The gateway aggregating every enabled server
+    Gateway,
+}
+
+struct Bridge {
+    target: Target,
+    port: u16,
+    token: String,
+    sessions: Sessions,
+    stop: Arc<AtomicBool>,
+    started_at: String,
+}
+
+impl Bridge {
+    fn info(&self, server_id: &str) -> BridgeInfo {
+        BridgeInfo {
+            server_id: server_id.to_string(),
+            url: format!("http://127.0.0.1:{}{MCP_PATH}", self.port),
+            port: self.port,
+            token: self.token.clone(),
+            sessions: self.sessions.lock().map(|s| s.len()).unwrap_or_default(),
+            started_at: self.started_at.clone(),
+        }
+    }
+}
+
+static BRIDGES: OnceLock<Mutex<HashMap<String, Bridge>>> = OnceLock::new();
+
+fn bridges() -> &'static Mutex<HashMap<String, Bridge>> {
+    BRIDGES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+struct HttpRequest {
+    method: String,
+    path: String,
+    /// Lowercased names
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+/// Read one line of at most `MAX_HEADER_LINE` bytes, counting it against the `budget` left
+/// for the request's head
+fn read_head_line(reader: &mut impl BufRead, budget: &mut usize) -> Result<String, String> {
+    let limit = MAX_HEADER_LINE.min(*budget);
+    let mut line = String::new();
+    reader.take(limit as u64 + 1).read_line(&mut line).map_err(|e| format!("Failed to read request head: {e}"))?;
+    if line.len() > limit {
+        return Err("Request head exceeds the limit".into());
+    }
+    *budget -= line.len();
+    Ok(line)
+}
+
+fn read_request(stream: &TcpStream) -> Result<HttpRequest, String> {
+    let mut reader = BufReader::new(stream);
+    let mut budget = MAX_HEADER_BYTES;
+    let line = read_head_line(&mut reader, &mut budget)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else { return Err("Malformed request line".into()) };
+    let path = target.split('?').next().unwrap_or_default().to_string();
+    let method = method.to_string();
+
+    let mut headers = HashMap::new();
+    loop {
+        let line = read_head_line(&mut reader, &mut budget)?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+    let len: usize = headers.get("content-length").and_then(|v| v.parse().ok()).unwrap_or(0);
+    if len > MAX_BODY_BYTES {
+        return Err(format!("Request body of {len} bytes exceeds the limit"));
+    }
+    let mut body = vec![0; len];
+    reader.read_exact(&mut body).map_err(|e| format!("Failed to read body: {e}"))?;
+    Ok(HttpRequest { method, path, headers, body })
+}
+
+fn write_response(mut stream: &TcpStream, status: &str, headers: &[(&str, &str)], body: &[u8]) {
+    let mut head = format!("HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n", body.len());
+    for (name, value) in headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    head.push_str("\r\n");
+    let _ = stream.write_all(head.as_bytes()).and_then(|_| stream.write_all(body)).and_then(|_| stream.flush());
+}
+
+fn write_json(stream: &TcpStream, status: &str, headers: &[(&str, &str)], body: &Value) {
+    let mut all = vec![("Content-Type", "application/json")];
+    all.extend_from_slice(headers);
+    write_response(stream, status, &all, body.to_string().as_bytes());
+}
+
+/// JSON-RPC error object not tied to a request, as sent for transport-level failures
+fn rpc_error(code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": null, "error": { "code": code, "message": message } })
+}
+
+/// Compare without short-circuiting so the token can't be guessed byte by byte
+fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len() && given.bytes().zip(expected.bytes()).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/// Browsers may only reach the bridge from local pages (DNS rebinding protection). The
+/// opaque `null` origin of sandboxed frames and `file:` pages could be anything, so it is refused.
+fn origin_allowed(origin: Option<&String>) -> bool {
+    let Some(origin) = origin else { return true };
+    let url = reqwest::Url::parse(origin);
+    matches!(url.as_ref().ok().and_then(|u| u.host_str()), Some("127.0.0.1" | "localhost" | "[::1]"))
+}
+
+/// `initialize` is answered by the bridge from the handshake the hub already performed,
+/// since the server process itself is initialized only once
+fn initialize_result(server_id: &str) -> Value {
+    let health = mcp_lifecycle::mcp_get_status(server_id.to_string()).ok().and_then(|s| s.health).unwrap_or_default();
+    json!({
+        "protocolVersion": health.protocol_version.unwrap_or_else(|| mcp_rpc::PROTOCOL_VERSION.to_string()),
+        "capabilities": health.capabilities.unwrap_or_else(|| json!({})),
+        "serverInfo": {
+            "name": health.server_name.unwrap_or_else(|| server_id.to_string()),
+            "version": health.server_version.unwrap_or_default(),
+        },
+    })
+}
+
+/// Forward one client message of `session_id` to the server; returns the response for requests
+fn forward(rpc: &RpcChannel, sessions: &Sessions, session_id: &str, message: &Value) -> Option<Value> {
+    let method = message.get("method").and_then(Value::as_str)?;
+    let mut params = message.get("params").cloned().unwrap_or(Value::Null);
+    let Some(id) = message.get("id").cloned() else {
+        // Each client's handshake is answered here; the server already got its own
+        if method == "notifications/initialized" {
+            return None;
+        }
+        if method == "notifications/cancelled" {
+            // The server knows the request by the channel's id; a request no longer in flight
+            // is not the client's to cancel
+            let request_id = params.get("requestId").map(Value::to_string).unwrap_or_default();
+            let channel_id = sessions.lock().ok().and_then(|map| map.get(session_id)?.in_flight.get(&request_id).copied())?;
+            params["requestId"] = json!(channel_id);
+        }
+        if let Err(e) = rpc.notify(method, params) {
+            log::debug!("Failed to forward {method}: {e}");
+        }
+        return None;
+    };
+    if method == "ping" {
+        return Some(json!({ "jsonrpc": "2.0", "id": id, "result": {} }));
+    }
+    // The channel assigns its own id so requests from different clients can't collide
+    let channel_id = rpc.next_request_id();
+    let key = id.to_string();
+    if let Ok(mut map) = sessions.lock() {
+        if let Some(session) = map.get_mut(session_id) {
+            session.in_flight.insert(key.clone(), channel_id);
+        }
+    }
+    let outcome = rpc.request_message_with_id(channel_id, method, params, Duration::from_millis(mcp_rpc::DEFAULT_REQUEST_TIMEOUT_MS));
+    if let Ok(mut map) = sessions.lock() {
+        if let Some(session) = map.get_mut(session_id) {
+            session.in_flight.remove(&key);
+        }
+    }
+    let mut response = outcome.unwrap_or_else(|e| rpc_error(-32603, &e));
+    response["id"] = id;
+    Some(response)
+}
+
+fn handle_post(target: &Target, sessions: &Sessions, request: &HttpRequest, stream: &TcpStream) {
+    let Ok(body) = serde_json::from_slice::<Value>(&request.body) else {
+        return write_json(stream, "400 Bad Request", &[], &rpc_error(-32700, "Parse error"));
+    };
+    let messages: Vec<Value> = match body {
+        Value::Array(batch) => batch,
+        single => vec![single],
+    };
+
+    if let Some(init) = messages.iter().find(|m| m.get("method").and_then(Value::as_str) == Some("initialize")) {
+        let session_id = nanoid::nanoid!();
+        if let Ok(mut map) = sessions.lock() {
+            map.retain(|_, s| !s.streams.is_empty() || s.last_seen.elapsed() < SESSION_IDLE_TIMEOUT);
+            map.insert(session_id.clone(), ClientSession { last_seen: Instant::now(), streams: vec![], in_flight: HashMap::new() });
+        }
+        log::info!("Bridge for {target:?}: new session {session_id}");
+        let result = match target {
+            Target::Server(server_id) => initialize_result(server_id),
+            Target::Gateway => mcp_gateway::initialize_result(),
+        };
+        let response = json!({ "jsonrpc": "2.0", "id": init.get("id").cloned().unwrap_or(Value::Null), "result": result });
+        return write_json(stream, "200 OK", &[("Mcp-Session-Id", &session_id)], &response);
+    }
+
+    let Some(session_id) = request.headers.get(SESSION_HEADER) else {
+        return write_json(stream, "400 Bad Request", &[], &rpc_error(-32000, "Missing Mcp-Session-Id header"));
+    };
+    let known = sessions.lock().map(|mut map| map.get_mut(session_id).map(|s| s.last_seen = Instant::now()).is_some()).unwrap_or(false);
+    if !known {
+        return write_json(stream, "404 Not Found", &[], &rpc_error(-32001, "Session not found"));
+    }
+    let mut responses: Vec<Value> = match target {
+        Target::Server(server_id) => match mcp_lifecycle::rpc_channel(server_id) {
+            Ok(rpc) => messages.iter().filter_map(|m| forward(&rpc, sessions, session_id, m)).collect(),
+            Err(e) => return write_json(stream, "503 Service Unavailable", &[], &rpc_error(-32603, &e)),
+        },
+        Target::Gateway => messages.iter().filter_map(mcp_gateway::handle).collect(),
+    };
+    let batch = messages.len() > 1;
+    match responses.len() {
+        0 => write_response(stream, "202 Accepted", &[], b""),
+        1 if !batch => write_json(stream, "200 OK", &[], &responses.remove(0)),
+        _ => write_json(stream, "200 OK", &[], &Value::Array(responses)),
+    }
+}
+
+/// Keep a GET connection open as an event stream of the server's notifications
+fn handle_get(target: &Target, sessions: &Sessions, request: &HttpRequest, mut stream: &TcpStream) {
+    let accepts_sse = request.headers.get("accept").is_some_and(|a| a.contains("text/event-stream"));
+    if !accepts_sse {
+        return write_response(stream, "405 Method Not Allowed", &[("Allow", "POST, DELETE")], b"");
+    }
+    let (tx, rx) = mpsc::channel();
+    let registered = match (request.headers.get(SESSION_HEADER), sessions.lock()) {
+        (Some(id), Ok(mut map)) => map.get_mut(id).map(|s| s.streams.push(tx)).is_some(),
+        _ => false,
+    };
+    if !registered {
+        return write_json(stream, "404 Not Found", &[], &rpc_error(-32001, "Session not found"));
+    }
+    let head = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n";
+    if stream.write_all(head.as_bytes()).and_then(|_| stream.flush()).is_err() {
+        return;
+    }
+    log::debug!("Bridge for {target:?}: event stream opened");
+    loop {
+        let chunk = match rx.recv_timeout(KEEPALIVE_INTERVAL) {
+            Ok(message) => format!("event: message\ndata: {message}\n\n"),
+            Err(mpsc::RecvTimeoutError::Timeout) => ": keepalive\n\n".to_string(),
+            // The session was closed or the bridge stopped
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        };
+        if stream.write_all(chunk.as_bytes()).and_then(|_| stream.flush()).is_err() {
+            break;
+        }
+    }
+}
+
+fn handle_connection(target: &Target, token: &str, sessions: &Sessions, stream: TcpStream) {
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+    let request = match read_request(&stream) {
+        Ok(request) => request,
+        Err(e) => {
+            log::debug!("Bridge for {target:?}: {e}");
+            return write_response(&stream, "400 Bad Request", &[], e.as_bytes());
+        }
+    };
+    if request.path != MCP_PATH {
+        return write_response(&stream, "404 Not Found", &[], b"");
+    }
+    if !origin_allowed(request.headers.get("origin")) {
+        return write_response(&stream, "403 Forbidden", &[], b"Origin not allowed");
+    }
+    let authorized = request.headers.get("authorization").and_then(|v| v.strip_prefix("Bearer ")).is_some_and(|given| tokens_match(given.trim(), token));
+    if !authorized {
+        return write_response(&stream, "401 Unauthorized", &[("WWW-Authenticate", "Bearer")], b"");
+    }
+    match request.method.as_str() {
+        "POST" => handle_post(target, sessions, &request, &stream),
+        "GET" => handle_get(target, sessions, &request, &stream),
+        "DELETE" => {
+            let removed = request.headers.get(SESSION_HEADER).and_then(|id| sessions.lock().ok()?.remove(id));
+            match removed {
+                Some(_) => write_response(&stream, "200 OK", &[], b""),
+                None => write_response(&stream, "404 Not Found", &[], b""),
+            }
+        }
+        _ => write_response(&stream, "405 Method Not Allowed", &[("Allow", "GET, POST, DELETE")], b""),
+    }
+}
+
+/// Push a notification from the server to every client with an open event stream
+/// (`mcp_gateway::GATEWAY_NAME` reaches the gateway's clients)
+pub(crate) fn broadcast(server_id: &str, message: &Value) {
+    let Some(sessions) = bridges().lock().ok().and_then(|map| map.get(server_id).map(|b| b.sessions.clone())) else { return };
+    let data = message.to_string();
+    let Ok(mut sessions) = sessions.lock() else { return };
+    for session in sessions.values_mut() {
+        session.streams.retain(|tx| tx.send(data.clone()).is_ok());
+    }
+}
+
+/// Serve `server_id` on 127.0.0.1:`port` (0 picks a free port), guarded by `token`
+fn start(server_id: &str, port: u16, token: String) -> Result<BridgeInfo, String> {
+    listen(server_id, Target::Server(server_id.to_string()), port, token)
+}
+
+/// Serve the gateway on 127.0.0.1:`port`; listed under `mcp_gateway::GATEWAY_NAME`
+pub(crate) fn start_gateway(port: u16, token: String) -> Result<BridgeInfo, String> {
+    listen(mcp_gateway::GATEWAY_NAME, Target::Gateway, port, token)
+}
+
+pub(crate) fn gateway_info() -> Option<BridgeInfo> {
+    let map = bridges().lock().ok()?;
+    map.get(mcp_gateway::GATEWAY_NAME).map(|bridge| bridge.info(mcp_gateway::GATEWAY_NAME))
+}
+
+/// One of a bridge's `MAX_CONNECTIONS`, released when dropped
+struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl ConnectionSlot {
+    fn acquire(active: &Arc<AtomicUsize>) -> Option<Self> {
+        let slot = Self(active.clone());
+        (active.fetch_add(1, Ordering::SeqCst) < MAX_CONNECTIONS).then_some(slot)
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn listen(server_id: &str, target: Target, port: u16, token: String) -> Result<BridgeInfo, String> {
+    let mut map = bridges().lock().map_err(|_| "Lock poisoned")?;
+    if let Some(bridge) = map.get(server_id) {
+        return Ok(bridge.info(server_id));
+    }
+    let listener = TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, port))).map_err(|e| format!("Failed to bind 127.0.0.1:{port}: {e}"))?;
+    let port = listener.local_addr().map_err(|e| format!("Failed to read bridge address: {e}"))?.port();
+    let bridge = Bridge { target, port, token, sessions: Arc::default(), stop: Arc::default(), started_at: mcp_lifecycle::now_iso() };
+
+    let (target, token, sessions, stop) = (bridge.target.clone(), bridge.token.clone(), bridge.sessions.clone(), bridge.stop.clone());
+    let active = Arc::new(AtomicUsize::new(0));
+    std::thread::Builder::new()
+        .name(format!("mcp-{server_id}-bridge"))
+        .spawn(move || {
+            for stream in listener.incoming() {
+                if stop.load(Ordering::SeqCst) {
+                    break;
+                }
+                let Ok(stream) = stream else { continue };
+                let Some(slot) = ConnectionSlot::acquire(&active) else {
+                    write_response(&stream, "503 Service Unavailable", &[("Retry-After", "1")], b"Too many connections");
+                    continue;
+                };
+                let (target, token, sessions) = (target.clone(), token.clone(), sessions.clone());
+                let _ = std::thread::Builder::new().name("mcp-bridge-conn".into()).spawn(move || {
+                    let _slot = slot;
+                    handle_connection(&target, &token, &sessions, stream);
+                });
+            }
+        })
+        .map_err(|e| format!("Failed to start bridge: {e}"))?;
+
+    log::info!("Bridge for {server_id} listening on 127.0.0.1:{port}");
+    let info = bridge.info(server_id);
+    map.insert(server_id.to_string(), bridge);
+    Ok(info)
+}
+
+pub(crate) fn stop(server_id: &str) -> Result<(), String> {
+    let bridge = bridges().lock().map_err(|_| "Lock poisoned")?.remove(server_id);
+    let bridge = bridge.ok_or_else(|| format!("No bridge for {server_id}"))?;
+    bridge.stop.store(true, Ordering::SeqCst);
+    // Wake the accept loop so it sees the flag; dropping the sessions ends their event streams
+    let _ = TcpStream::connect((Ipv4Addr::LOCALHOST, bridge.port));
+    if let Ok(mut sessions) = bridge.sessions.lock() {
+        sessions.clear();
+    }
+    log::info!("Bridge for {server_id} stopped");
+    Ok(())
+}
+
+/// Expose a running stdio server as a Streamable HTTP endpoint on 127.0.0.1, so several
+/// HTTP-only clients can share the one process. Clients authenticate with the returned token.
+#[tauri::command]
+pub fn mcp_start_bridge(server_id: String, port: Option<u16>) -> Result<BridgeInfo, String> {
+    mcp_lifecycle::rpc_channel(&server_id)?;
+    let token = secure_storage::get_or_create_bridge_token(&server_id)?;
+    start(&server_id, port.unwrap_or(0), token)
+}
+
+#[tauri::command]
+pub fn mcp_stop_bridge(server_id: String) -> Result<(), String> {
+    stop(&server_id)
+}
+
+#[tauri::command]
+pub fn mcp_list_bridges() -> Result<Vec<BridgeInfo>, String> {
+    let map = bridges().lock().map_err(|_| "Lock poisoned")?;
+    Ok(map.iter().filter(|(_, bridge)| matches!(bridge.target, Target::Server(_))).map(|(id, bridge)| bridge.info(id)).collect())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::mcp_lifecycle::{HealthCheckConfig, LifecycleState, StdioConfig};
+    use reqwest::blocking::Client;
+
+    /// Answers every request and announces it with a log notification first
+    const CHATTY_MCP_SERVER: &str = r#"while read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  if [ -n "$id" ]; then
+    printf '{"jsonrpc":"2.0","method":"notifications/message","params":{"level":"info","data":"request %s"}}\n' "$id"
+    printf '{"jsonrpc":"2.0","id":%s,"result":{"protocolVersion":"2025-06-18","serverInfo":{"name":"chatty","version":"2.0"},"capabilities":{"logging":{}}}}\n' "$id"
+  fi
+done"#;
+
+    fn start_server(server_id: &str) {
+        let cfg = StdioConfig {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), CHATTY_MCP_SERVER.to_string()],
+            health: HealthCheckConfig { ping_interval_ms: 0, ..Default::default() },
+            ..Default::default()
+        };
+        mcp_lifecycle::start_server(server_id.to_string(), cfg).unwrap();
+        for _ in 0..100 {
+            if mcp_lifecycle::mcp_get_status(server_id.to_string()).unwrap().state == LifecycleState::Running {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        panic!("{server_id} never became ready");
+    }
+
+    fn post(client: &Client, info: &BridgeInfo, session: Option<&str>, body: Value) -> reqwest::blocking::Response {
+        let mut request = client.post(&info.url).bearer_auth(&info.token).header("Accept", "application/json, text/event-stream").json(&body);
+        if let Some(session) = session {
+            request = request.header(SESSION_HEADER, session);
+        }
+        request.send().unwrap()
+    }
+
+    /// Test sessions, forwarding with client ids and notifications on the event stream
+    #[test]
+    fn test_bridge_serves_several_clients() {
+        start_server("http-bridge-test");
+        let info = start("http-bridge-test", 0, "secret-token".into()).unwrap();
+        assert_eq!(info.url, format!("http://127.0.0.1:{}/mcp", info.port));
+        let client = Client::new();
+
+        // Unauthenticated and session-less requests are refused
+        let anonymous = client.post(&info.url).json(&json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/list" })).send().unwrap();
+        assert_eq!(anonymous.status(), 401);
+        assert_eq!(post(&client, &info, None, json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/list" })).status(), 400);
+        assert_eq!(post(&client, &info, Some("bogus"), json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/list" })).status(), 404);
+
+        let mut sessions = vec![];
+        for _ in 0..2 {
+            let init = post(&client, &info, None, json!({ "jsonrpc": "2.0", "id": 0, "method": "initialize", "params": mcp_rpc::initialize_params() }));
+            assert_eq!(init.status(), 200);
+            let session = init.headers()[SESSION_HEADER].to_str().unwrap().to_string();
+            let result: Value = init.json().unwrap();
+            assert_eq!(result["result"]["serverInfo"]["name"], "chatty");
+            assert_eq!(post(&client, &info, Some(&session), json!({ "jsonrpc": "2.0", "method": "notifications/initialized" })).status(), 202);
+            sessions.push(session);
+        }
+        assert_ne!(sessions[0], sessions[1]);
+        assert_eq!(mcp_list_bridges().unwrap().iter().find(|b| b.server_id == "http-bridge-test").unwrap().sessions, 2);
+
+        let events = client.get(&info.url).bearer_auth(&info.token).header("Accept", "text/event-stream").header(SESSION_HEADER, &sessions[1]).send().unwrap();
+        assert_eq!(events.status(), 200);
+        let mut events = BufReader::new(events);
+
+        // Both clients use the same id; each gets its own answer back under it
+        for session in &sessions {
+            let response: Value = post(&client, &info, Some(session), json!({ "jsonrpc": "2.0", "id": 7, "method": "tools/list" })).json().unwrap();
+            assert_eq!(response["id"], 7);
+            assert_eq!(response["result"]["serverInfo"]["name"], "chatty");
+        }
+        let mut line = String::new();
+        while !line.starts_with("data:") {
+            line.clear();
+            events.read_line(&mut line).unwrap();
+        }
+        assert!(line.contains("notifications/message"));
+
+        let closed = client.delete(&info.url).bearer_auth(&info.token).header(SESSION_HEADER, &sessions[0]).send().unwrap();
+        assert_eq!(closed.status(), 200);
+        assert_eq!(post(&client, &info, Some(&sessions[0]), json!({ "jsonrpc": "2.0", "id": 8, "method": "ping" })).status(), 404);
+
+        stop("http-bridge-test").unwrap();
+        assert!(client.post(&info.url).send().is_err());
+        mcp_lifecycle::stop_server("http-bridge-test", true, None).unwrap();
+    }
+
+    /// Test a bridge serves at most `MAX_CONNECTIONS` at once
+    #[test]
+    fn test_connection_slots_capped() {
+        let active = Arc::new(AtomicUsize::new(0));
+        let slots: Vec<_> = (0..MAX_CONNECTIONS).map(|_| ConnectionSlot::acquire(&active).unwrap()).collect();
+        assert!(ConnectionSlot::acquire(&active).is_none());
+        drop(slots);
+        assert_eq!(active.load(Ordering::SeqCst), 0);
+        assert!(ConnectionSlot::acquire(&active).is_some());
+    }
+
+    /// Test a client's cancellation reaches the server under the channel's id for the request
+    #[test]
+    fn test_cancellation_maps_request_id() {
+        let path = std::env::temp_dir().join(format!("bridge-cancel-{}", std::process::id()));
+        let rpc = RpcChannel::new(std::fs::File::create(&path).unwrap());
+        let session = ClientSession { last_seen: Instant::now(), streams: vec![], in_flight: HashMap::from([("7".to_string(), 42)]) };
+        let sessions: Sessions = Arc::new(Mutex::new(HashMap::from([("s".to_string(), session)])));
+
+        for request_id in [7, 9] {
+            let cancel = json!({ "jsonrpc": "2.0", "method": "notifications/cancelled", "params": { "requestId": request_id } });
+            assert!(forward(&rpc, &sessions, "s", &cancel).is_none());
+        }
+        let written: Vec<Value> = std::fs::read_to_string(&path).unwrap().lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(written.len(), 1, "a request not in flight is not cancelled");
+        assert_eq!(written[0]["params"]["requestId"], 42);
+    }
+
+    /// Test request head lines and the head as a whole are bounded
+    #[test]
+    fn test_head_lines_capped() {
+        let mut budget = MAX_HEADER_BYTES;
+        assert_eq!(read_head_line(&mut &b"Host: x\r\nrest"[..], &mut budget).unwrap(), "Host: x\r\n");
+        assert_eq!(budget, MAX_HEADER_BYTES - 9);
+
+        let long = format!("X: {}\r\n", "a".repeat(MAX_HEADER_LINE));
+        assert!(read_head_line(&mut long.as_bytes(), &mut MAX_HEADER_BYTES.clone()).is_err());
+
+        let mut budget = 5;
+        assert!(read_head_line(&mut &b"Host: x\r\n"[..], &mut budget).is_err());
+    }
+
+    /// Test only local origins may reach the bridge
+    #[test]
+    fn test_origin_allowed() {
+        assert!(origin_allowed(None));
+        assert!(origin_allowed(Some(&"http://localhost:3000".to_string())));
+        assert!(origin_allowed(Some(&"http://127.0.0.1".to_string())));
+        assert!(!origin_allowed(Some(&"https://evil.example".to_string())));
+        assert!(!origin_allowed(Some(&"http://localhost.evil.example".to_string())));
+        assert!(origin_allowed(Some(&"http://[::1]:3000".to_string())));
+        assert!(!origin_allowed(Some(&"null".to_string())));
+        assert!(!origin_allowed(Some(&"http://[::2]:3000".to_string())));
+        assert!(!origin_allowed(Some(&"http://localhost@evil.example".to_string())));
+        assert!(!origin_allowed(Some(&"localhost".to_string())));
+    }
+}
@@ -0,0 +1,488 @@
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashSet,
+    io::{BufRead, Write},
+    sync::{Arc, Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+use crate::mcp_bridge::{self, BridgeInfo};
+use crate::mcp_lifecycle::{self, LifecycleState};
+use crate::mcp_rpc::{self, RpcChannel};
+use crate::{secure_storage, shutdown, storage};
+
+/// Server name announced to clients; also keys the gateway's HTTP bridge and token
+pub const GATEWAY_NAME: &str = "mcp-hub";
+/// Command-line flag that runs the app as a stdio gateway instead of opening a window
+pub const GATEWAY_FLAG: &str = "--mcp-gateway";
+/// Joins a server's namespace and the name of one of its tools or prompts
+const SEPARATOR: &str = "__";
+/// Resource URIs are wrapped as `mcp-hub://<namespace>/<original uri>`
+const URI_PREFIX: &str = "mcp-hub://";
+/// Longest namespace, keeping namespaced tool names within the usual 64-character limit
+const MAX_NAMESPACE_LEN: usize = 24;
+/// Pages fetched from one server per list request
+const MAX_PAGES: usize = 20;
+/// Time allowed for the stdio gateway's servers to finish their handshake
+const STARTUP_DEADLINE: Duration = Duration::from_secs(30);
+
+/// A running server the gateway routes to
+struct Backend {
+    namespace: String,
+    server_id: String,
+    rpc: Arc<RpcChannel>,
+}
+
+/// Stdout of the stdio gateway, shared by responses and forwarded notifications
+static STDOUT: OnceLock<Mutex<std::io::Stdout>> = OnceLock::new();
+
+/// Reduce a server name to `[a-z0-9_-]`, the characters allowed in tool names and URI hosts
+fn sanitize(name: &str) -> String {
+    let mut out: String = name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    while out.contains(SEPARATOR) {
+        out = out.replace(SEPARATOR, "_");
+    }
+    let out: String = out.trim_matches('_').chars().take(MAX_NAMESPACE_LEN).collect();
+    if out.is_empty() { "server".to_string() } else { out }
+}
+
+/// Unique namespaces for `(server_id, name)` pairs. The first server with a name keeps it;
+/// later duplicates get a suffix hashed from their server id, so a namespace doesn't depend
+/// on which other servers are running.
+fn namespaces(servers: &[(String, String)]) -> Vec<String> {
+    let mut taken = HashSet::new();
+    servers
+        .iter()
+        .map(|(server_id, name)| {
+            let base = sanitize(name);
+            if taken.insert(base.clone()) {
+                return base;
+            }
+            let digest = Sha256::digest(server_id.as_bytes());
+            let suffix: String = digest[..3].iter().map(|b| format!("{b:02x}")).collect();
+            let base: String = base.chars().take(MAX_NAMESPACE_LEN - suffix.len() - 1).collect();
+            let mut candidate = format!("{base}_{suffix}");
+            let mut n = 2;
+            while !taken.insert(candidate.clone()) {
+                candidate = format!("{base}_{suffix}_{n}");
+                n += 1;
+            }
+            candidate
+        })
+        .collect()
+}
+
+/// Running servers that are enabled in servers.json (or not saved there). Namespaces are
+/// assigned over every configured server in servers.json order, running or not, then over
+/// unsaved running servers by id, so they stay put as servers start and stop.
+fn backends() -> Vec<Backend> {
+    let mut servers = Vec::new();
+    let mut disabled = HashSet::new();
+    for server in mcp_lifecycle::saved_servers() {
+        let Some(id) = server["id"].as_str() else { continue };
+        if !server["enabled"].as_bool().unwrap_or(true) {
+            disabled.insert(id.to_string());
+        }
+        servers.push((id.to_string(), server["name"].as_str().unwrap_or(id).to_string()));
+    }
+    let known: HashSet<String> = servers.iter().map(|(id, _)| id.clone()).collect();
+    let mut unsaved: Vec<String> = mcp_lifecycle::server_ids().into_iter().filter(|id| !known.contains(id)).collect();
+    unsaved.sort();
+    unsaved.dedup();
+    servers.extend(unsaved.into_iter().map(|id| (id.clone(), id)));
+    let names = namespaces(&servers);
+    servers
+        .into_iter()
+        .zip(names)
+        .filter(|((server_id, _), _)| !disabled.contains(server_id))
+        .filter_map(|((server_id, _), namespace)| Some(Backend { rpc: mcp_lifecycle::rpc_channel(&server_id).ok()?, namespace, server_id }))
+        .collect()
+}
+
+fn namespaced(namespace: &str, name: &str) -> String {
+    format!("{namespace}{SEPARATOR}{name}")
+}
+
+fn wrap_uri(namespace: &str, uri: &str) -> String {
+    format!("{URI_PREFIX}{namespace}/{uri}")
+}
+
+/// Split a namespaced tool/prompt name into its backend and original name
+fn route_name<'a>(backends: &'a [Backend], name: &str) -> Option<(&'a Backend, String)> {
+    let (namespace, original) = name.split_once(SEPARATOR)?;
+    backends.iter().find(|b| b.namespace == namespace).map(|b| (b, original.to_string()))
+}
+
+/// Unwrap a gateway resource URI into its backend and original URI
+fn route_uri<'a>(backends: &'a [Backend], uri: &str) -> Option<(&'a Backend, String)> {
+    let (namespace, original) = uri.strip_prefix(URI_PREFIX)?.split_once('/')?;
+    backends.iter().find(|b| b.namespace == namespace).map(|b| (b, original.to_string()))
+}
+
+/// Result of the gateway's own `initialize`
+pub fn initialize_result() -> Value {
+    json!({
+        "protocolVersion": mcp_rpc::PROTOCOL_VERSION,
+        "capabilities": {
+            "tools": { "listChanged": true },
+            "resources": { "subscribe": true, "listChanged": true },
+            "prompts": { "listChanged": true },
+            "logging": {},
+            "completions": {},
+        },
+        "serverInfo": { "name": GATEWAY_NAME, "version": env!("CARGO_PKG_VERSION") },
+        "instructions": "Aggregates the servers managed by MCP Hub. Tool and prompt names are prefixed with their server's namespace and `__`.",
+    })
+}
+
+fn request_timeout() -> Duration {
+    Duration::from_millis(mcp_rpc::DEFAULT_REQUEST_TIMEOUT_MS)
+}
+
+/// Every item of a paginated list from one server
+fn list_all(backend: &Backend, method: &str, key: &str) -> Result<Vec<Value>, String> {
+    let mut items = Vec::new();
+    let mut cursor: Option<Value> = None;
+    for _ in 0..MAX_PAGES {
+        let params = cursor.take().map(|c| json!({ "cursor": c })).unwrap_or(Value::Null);
+        let result = backend.rpc.request(method, params, request_timeout())?;
+        items.extend(result.get(key).and_then(Value::as_array).cloned().unwrap_or_default());
+        match result.get("nextCursor") {
+            Some(next) if !next.is_null() => cursor = Some(next.clone()),
+            _ => break,
+        }
+    }
+    Ok(items)
+}
+
+/// Merge one list method across servers, renaming each item into its server's namespace
+fn aggregate(backends: &[Backend], method: &str, key: &str) -> Value {
+    let mut merged = Vec::new();
+    for backend in backends {
+        let items = match list_all(backend, method, key) {
+            Ok(items) => items,
+            Err(e) => {
+                log::debug!("Gateway: {method} failed for {}: {e}", backend.server_id);
+                continue;
+            }
+        };
+        for mut item in items {
+            if let Some(name) = item.get("name").and_then(Value::as_str) {
+                item["name"] = Value::String(namespaced(&backend.namespace, name));
+            }
+            for field in ["uri", "uriTemplate"] {
+                if let Some(uri) = item.get(field).and_then(Value::as_str) {
+                    item[field] = Value::String(wrap_uri(&backend.namespace, uri));
+                }
+            }
+            merged.push(item);
+        }
+    }
+    json!({ key: merged })
+}
+
+/// Send a request to one server and return its response message, JSON-RPC errors included
+fn call(backend: &Backend, method: &str, params: Value) -> Value {
+    match backend.rpc.request_message(method, params, request_timeout()) {
+        Ok(response) => response,
+        Err(e) => json!({ "jsonrpc": "2.0", "error": { "code": -32603, "message": e } }),
+    }
+}
+
+fn not_found(what: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "error": { "code": -32602, "message": format!("Unknown {what}") } })
+}
+
+/// Handle one message from a gateway client; returns the response for requests
+pub fn handle(message: &Value) -> Option<Value> {
+    let method = message.get("method").and_then(Value::as_str)?;
+    // Client notifications (initialized, cancelled...) concern the gateway session only
+    let id = message.get("id").cloned()?;
+    let mut params = message.get("params").cloned().unwrap_or_else(|| json!({}));
+
+    let local = |result: Value| json!({ "jsonrpc": "2.0", "result": result });
+    let mut response = match method {
+        "initialize" => local(initialize_result()),
+        "ping" => local(json!({})),
+        "tools/list" => local(aggregate(&backends(), "tools/list", "tools")),
+        "prompts/list" => local(aggregate(&backends(), "prompts/list", "prompts")),
+        "resources/list" => local(aggregate(&backends(), "resources/list", "resources")),
+        "resources/templates/list" => local(aggregate(&backends(), "resources/templates/list", "resourceTemplates")),
+        "tools/call" | "prompts/get" => {
+            let backends = backends();
+            let name = params.get("name").and_then(Value::as_str).unwrap_or_default().to_string();
+            match route_name(&backends, &name) {
+                Some((backend, original)) => {
+                    params["name"] = Value::String(original);
+                    call(backend, method, params)
+                }
+                None => not_found(&format!("name {name}")),
+            }
+        }
+        "resources/read" | "resources/subscribe" | "resources/unsubscribe" => {
+            let backends = backends();
+            let uri = params.get("uri").and_then(Value::as_str).unwrap_or_default().to_string();
+            match route_uri(&backends, &uri) {
+                Some((backend, original)) => {
+                    params["uri"] = Value::String(original);
+                    let mut response = call(backend, method, params);
+                    if let Some(contents) = response.pointer_mut("/result/contents").and_then(Value::as_array_mut) {
+                        for content in contents {
+                            if let Some(inner) = content.get("uri").and_then(Value::as_str) {
+                                content["uri"] = Value::String(wrap_uri(&backend.namespace, inner));
+                            }
+                        }
+                    }
+                    response
+                }
+                None => not_found(&format!("resource {uri}")),
+            }
+        }
+        "completion/complete" => {
+            let backends = backends();
+            let reference = params.get("ref").cloned().unwrap_or_default();
+            let routed = match reference.get("type").and_then(Value::as_str) {
+                Some("ref/prompt") => route_name(&backends, reference["name"].as_str().unwrap_or_default()).map(|(b, name)| (b, "name", name)),
+                Some("ref/resource") => route_uri(&backends, reference["uri"].as_str().unwrap_or_default()).map(|(b, uri)| (b, "uri", uri)),
+                _ => None,
+            };
+            match routed {
+                Some((backend, field, original)) => {
+                    params["ref"][field] = Value::String(original);
+                    call(backend, method, params)
+                }
+                None => not_found("completion reference"),
+            }
+        }
+        "logging/setLevel" => {
+            for backend in backends() {
+                if let Err(e) = backend.rpc.request(method, params.clone(), request_timeout()) {
+                    log::debug!("Gateway: logging/setLevel failed for {}: {e}", backend.server_id);
+                }
+            }
+            local(json!({}))
+        }
+        _ => json!({ "jsonrpc": "2.0", "error": { "code": -32601, "message": format!("Method not found: {method}") } }),
+    };
+    response["id"] = id;
+    Some(response)
+}
+
+/// Relay a server notification the gateway's clients care about, translated into its namespace
+pub(crate) fn forward_notification(server_id: &str, message: &Value) {
+    let method = message.get("method").and_then(Value::as_str).unwrap_or_default();
+    let relayed = matches!(
+        method,
+        "notifications/tools/list_changed" | "notifications/prompts/list_changed" | "notifications/resources/list_changed" | "notifications/resources/updated" | "notifications/message"
+    );
+    let stdio = STDOUT.get();
+    if !relayed || (stdio.is_none() && mcp_bridge::gateway_info().is_none()) {
+        return;
+    }
+    let Some(backend) = backends().into_iter().find(|b| b.server_id == server_id) else { return };
+    let mut message = message.clone();
+    if let Some(uri) = message.pointer("/params/uri").and_then(Value::as_str) {
+        message["params"]["uri"] = Value::String(wrap_uri(&backend.namespace, uri));
+    }
+    mcp_bridge::broadcast(GATEWAY_NAME, &message);
+    if let Some(stdout) = stdio {
+        write_line(stdout, &message);
+    }
+}
+
+fn write_line(stdout: &Mutex<std::io::Stdout>, message: &Value) {
+    let Ok(mut out) = stdout.lock() else { return };
+    let _ = writeln!(out, "{message}").and_then(|_| out.flush());
+}
+
+/// Serve the gateway over stdin/stdout: start every enabled stdio server saved in servers.json,
+/// answer requests until stdin closes, then stop the servers. Returns the process exit code.
+pub fn serve_stdio() -> i32 {
+    let dir = match storage::default_app_data_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            eprintln!("{GATEWAY_NAME}: {e}");
+            return 1;
+        }
+    };
+    mcp_lifecycle::init_headless(dir);
+    let stdout = STDOUT.get_or_init(|| Mutex::new(std::io::stdout()));
+
+    let enabled: HashSet<String> = mcp_lifecycle::saved_servers()
+        .iter()
+        .filter(|server| server["enabled"].as_bool().unwrap_or(true))
+        .filter_map(|server| server["id"].as_str().map(str::to_string))
+        .collect();
+    for (server_id, cfg) in mcp_lifecycle::saved_configs() {
+        if !enabled.contains(&server_id) {
+            continue;
+        }
+        if let Err(e) = mcp_lifecycle::start_server(server_id.clone(), cfg) {
+            eprintln!("{GATEWAY_NAME}: failed to start {server_id}: {e}");
+        }
+    }
+    // Answer tools/list with every server, not just the quick ones
+    let started = Instant::now();
+    while started.elapsed() < STARTUP_DEADLINE {
+        let starting = mcp_lifecycle::mcp_list_running().unwrap_or_default().iter().any(|s| s.state == LifecycleState::Starting);
+        if !starting {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+
+    for line in std::io::stdin().lock().lines() {
+        let Ok(line) = line else { break };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let message = match serde_json::from_str::<Value>(&line) {
+            Ok(message) => message,
+            Err(_) => {
+                write_line(stdout, &json!({ "jsonrpc": "2.0", "id": null, "error": { "code": -32700, "message": "Parse error" } }));
+                continue;
+            }
+        };
+        // Slow tool calls must not hold up other requests; responses may arrive out of order
+        let spawned = std::thread::Builder::new().name("mcp-gateway-request".into()).spawn(move || {
+            if let Some(response) = handle(&message) {
+                write_line(stdout, &response);
+            }
+        });
+        if let Err(e) = spawned {
+            eprintln!("{GATEWAY_NAME}: failed to handle request: {e}");
+        }
+    }
+    shutdown::shutdown_all();
+    0
+}
+
+/// Serve the gateway over Streamable HTTP on 127.0.0.1, guarded by a bearer token
+#[tauri::command]
+pub fn mcp_start_gateway(port: Option<u16>) -> Result<BridgeInfo, String> {
+    let token = secure_storage::get_or_create_bridge_token(GATEWAY_NAME)?;
+    mcp_bridge::start_gateway(port.unwrap_or(0), token)
+}
+
+#[tauri::command]
+pub fn mcp_stop_gateway() -> Result<(), String> {
+    mcp_bridge::stop(GATEWAY_NAME)
+}
+
+/// The HTTP gateway, if it is running
+#[tauri::command]
+pub fn mcp_gateway_status() -> Result<Option<BridgeInfo>, String> {
+    Ok(mcp_bridge::gateway_info())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// Test namespaces are sanitized, bounded and unique
+    #[test]
+    fn test_namespaces() {
+        let servers = vec![
+            ("a".to_string(), "GitHub Tools".to_string()),
+            ("b".to_string(), "github tools".to_string()),
+            ("c".to_string(), "my__server!".to_string()),
+            ("d".to_string(), "***".to_string()),
+            ("e".to_string(), "x".repeat(40)),
+        ];
+        let names = namespaces(&servers);
+        assert_eq!(names[0], "github_tools");
+        assert!(names[1].starts_with("github_tools_") && names[1].len() == "github_tools_".len() + 6, "{}", names[1]);
+        assert_eq!(names[2], "my_server");
+        assert_eq!(names[3], "server");
+        assert_eq!(names[4].len(), MAX_NAMESPACE_LEN);
+        assert!(names.iter().all(|n| !n.contains(SEPARATOR)));
+
+        // A duplicate's namespace depends only on its own id, not on which servers precede it
+        let alone = namespaces(&[("a".to_string(), "GitHub Tools".to_string()), servers[1].clone()]);
+        let with_others = namespaces(&[servers[0].clone(), servers[2].clone(), servers[1].clone()]);
+        assert_eq!(alone[1], names[1]);
+        assert_eq!(with_others[2], names[1]);
+        let long = namespaces(&[servers[4].clone(), ("f".to_string(), "x".repeat(40))]);
+        assert_eq!(long[1].len(), MAX_NAMESPACE_LEN);
+    }
+
+    /// Serves one tool and one resource, labelled with `$LABEL`
+    #[cfg(unix)]
+    const TOOL_SERVER: &str = r#"while read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  [ -n "$id" ] || continue
+  case "$line" in
+    *'"tools/list"'*) printf '{"jsonrpc":"2.0","id":%s,"result":{"tools":[{"name":"echo","inputSchema":{"type":"object"}}]}}\n' "$id" ;;
+    *'"tools/call"'*'"name":"echo"'*) printf '{"jsonrpc":"2.0","id":%s,"result":{"content":[{"type":"text","text":"%s"}]}}\n' "$id" "$LABEL" ;;
+    *'"tools/call"'*) printf '{"jsonrpc":"2.0","id":%s,"error":{"code":-32602,"message":"no such tool"}}\n' "$id" ;;
+    *'"resources/list"'*) printf '{"jsonrpc":"2.0","id":%s,"result":{"resources":[{"uri":"file:///notes.txt","name":"notes"}]}}\n' "$id" ;;
+    *'"resources/read"'*'"file:///notes.txt"'*) printf '{"jsonrpc":"2.0","id":%s,"result":{"contents":[{"uri":"file:///notes.txt","text":"%s notes"}]}}\n' "$id" "$LABEL" ;;
+    *) printf '{"jsonrpc":"2.0","id":%s,"result":{"protocolVersion":"2025-06-18","serverInfo":{"name":"%s","version":"1.0"},"capabilities":{"tools":{}}}}\n' "$id" "$LABEL" ;;
+  esac
+done"#;
+
+    #[cfg(unix)]
+    fn start_tool_server(server_id: &str, label: &str) {
+        let cfg = mcp_lifecycle::StdioConfig {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), TOOL_SERVER.to_string()],
+            env: HashMap::from([("LABEL".to_string(), label.to_string())]),
+            health: mcp_lifecycle::HealthCheckConfig { ping_interval_ms: 0, ..Default::default() },
+            ..Default::default()
+        };
+        mcp_lifecycle::start_server(server_id.to_string(), cfg).unwrap();
+        for _ in 0..100 {
+            if mcp_lifecycle::mcp_get_status(server_id.to_string()).unwrap().state == LifecycleState::Running {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        panic!("{server_id} never became ready");
+    }
+
+    fn request(id: u64, method: &str, params: Value) -> Value {
+        handle(&json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })).unwrap()
+    }
+
+    /// Test tools and resources of several servers are merged and routed back by namespace
+    #[cfg(unix)]
+    #[test]
+    fn test_gateway_routes_to_servers() {
+        start_tool_server("gw-alpha-test", "alpha");
+        start_tool_server("gw-beta-test", "beta");
+
+        let tools = request(1, "tools/list", json!({}));
+        assert_eq!(tools["id"], 1);
+        let names: Vec<&str> = tools["result"]["tools"].as_array().unwrap().iter().filter_map(|t| t["name"].as_str()).collect();
+        assert!(names.contains(&"gw-alpha-test__echo"), "{names:?}");
+        assert!(names.contains(&"gw-beta-test__echo"), "{names:?}");
+
+        let called = request(2, "tools/call", json!({ "name": "gw-beta-test__echo", "arguments": {} }));
+        assert_eq!(called["id"], 2);
+        assert_eq!(called["result"]["content"][0]["text"], "beta");
+        let failed = request(3, "tools/call", json!({ "name": "gw-alpha-test__missing" }));
+        assert_eq!(failed["error"]["message"], "no such tool");
+        assert_eq!(request(4, "tools/call", json!({ "name": "nowhere__echo" }))["error"]["code"], -32602);
+
+        let resources = request(5, "resources/list", json!({}));
+        let uri = format!("{URI_PREFIX}gw-alpha-test/file:///notes.txt");
+        assert!(resources["result"]["resources"].as_array().unwrap().iter().any(|r| r["uri"] == uri.as_str()));
+        let read = request(6, "resources/read", json!({ "uri": uri }));
+        assert_eq!(read["result"]["contents"][0]["text"], "alpha notes");
+        assert_eq!(read["result"]["contents"][0]["uri"], uri.as_str());
+
+        assert_eq!(request(7, "initialize", json!({}))["result"]["serverInfo"]["name"], GATEWAY_NAME);
+        assert_eq!(request(8, "sampling/createMessage", json!({}))["error"]["code"], -32601);
+        assert!(handle(&json!({ "jsonrpc": "2.0", "method": "notifications/initialized" })).is_none());
+
+        mcp_lifecycle::stop_server("gw-alpha-test", true, None).unwrap();
+        mcp_lifecycle::stop_server("gw-beta-test", true, None).unwrap();
+    }
+}
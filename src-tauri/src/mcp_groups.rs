@@ -0,0 +1,318 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+use tauri::{AppHandle, Manager};
+
+use crate::mcp_lifecycle::{self, LifecycleState, StdioConfig};
+
+const GROUPS_FILE: &str = "server_groups.json";
+/// Extra time allowed on top of a server's startup timeout before its dependents give up
+const READY_MARGIN: Duration = Duration::from_secs(2);
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A set of servers started and stopped together
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerGroup {
+    pub id: String,
+    pub name: String,
+    pub members: Vec<GroupMember>,
+    /// Start the group when the app launches
+    #[serde(default)]
+    pub autostart: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupMember {
+    pub server_id: String,
+    /// Members that must be running before this one starts (and stop after it)
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Launch config; defaults to the server's entry in servers.json
+    #[serde(default)]
+    pub config: Option<StdioConfig>,
+}
+
+/// Outcome of starting or stopping one member of a group
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupMemberResult {
+    pub server_id: String,
+    pub success: bool,
+    pub state: Option<LifecycleState>,
+    pub error: Option<String>,
+}
+
+impl GroupMemberResult {
+    fn ok(server_id: &str, state: Option<LifecycleState>) -> Self {
+        Self { server_id: server_id.to_string(), success: true, state, error: None }
+    }
+
+    fn failed(server_id: &str, error: String) -> Self {
+        Self { server_id: server_id.to_string(), success: false, state: None, error: Some(error) }
+    }
+}
+
+/// Members grouped into waves: every member's dependencies are in earlier waves,
+/// so each wave can be processed in parallel
+pub fn start_order(group: &ServerGroup) -> Result<Vec<Vec<String>>, String> {
+    let ids: HashSet<&str> = group.members.iter().map(|m| m.server_id.as_str()).collect();
+    if ids.len() != group.members.len() {
+        return Err(format!("Group {} lists a server more than once", group.id));
+    }
+    for member in &group.members {
+        if let Some(missing) = member.depends_on.iter().find(|dep| !ids.contains(dep.as_str())) {
+            return Err(format!("{} depends on {missing}, which is not in group {}", member.server_id, group.id));
+        }
+    }
+
+    let mut placed: HashSet<&str> = HashSet::new();
+    let mut waves = Vec::new();
+    while placed.len() < group.members.len() {
+        let wave: Vec<&str> = group
+            .members
+            .iter()
+            .filter(|m| !placed.contains(m.server_id.as_str()) && m.depends_on.iter().all(|dep| placed.contains(dep.as_str())))
+            .map(|m| m.server_id.as_str())
+            .collect();
+        if wave.is_empty() {
+            let mut cyclic: Vec<&str> = ids.difference(&placed).copied().collect();
+            cyclic.sort_unstable();
+            return Err(format!("Dependency cycle in group {} among: {}", group.id, cyclic.join(", ")));
+        }
+        placed.extend(&wave);
+        waves.push(wave.into_iter().map(String::from).collect());
+    }
+    Ok(waves)
+}
+
+/// Run `op` on every id of a wave in its own thread and collect the results in wave order
+fn run_wave(wave: &[String], op: impl Fn(&str) -> GroupMemberResult + Sync) -> Vec<GroupMemberResult> {
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = wave.iter().map(|id| (id, scope.spawn(|| op(id)))).collect();
+        handles
+            .into_iter()
+            .map(|(id, handle)| handle.join().unwrap_or_else(|_| GroupMemberResult::failed(id, "Worker thread panicked".into())))
+            .collect()
+    })
+}
+
+/// Start a server and wait until it is running (handshake included) or has failed
+fn start_member(server_id: &str, cfg: StdioConfig) -> GroupMemberResult {
+    let deadline = Instant::now() + Duration::from_millis(cfg.health.startup_timeout_ms) + READY_MARGIN;
+    if let Err(e) = mcp_lifecycle::start_server(server_id.to_string(), cfg) {
+        return GroupMemberResult::failed(server_id, e);
+    }
+    loop {
+        match mcp_lifecycle::mcp_get_status(server_id.to_string()) {
+            Ok(status) if status.state == LifecycleState::Running => return GroupMemberResult::ok(server_id, Some(status.state)),
+            Ok(status) if status.state != LifecycleState::Starting => {
+                let error = status.last_error.unwrap_or_else(|| format!("Server ended up {:?}", status.state));
+                return GroupMemberResult { state: Some(status.state), ..GroupMemberResult::failed(server_id, error) };
+            }
+            Ok(_) => {}
+            Err(e) => return GroupMemberResult::failed(server_id, e),
+        }
+        if Instant::now() >= deadline {
+            return GroupMemberResult { state: Some(LifecycleState::Starting), ..GroupMemberResult::failed(server_id, "Timed out waiting for the server to become ready".into()) };
+        }
+        std::thread::sleep(READY_POLL_INTERVAL);
+    }
+}
+
+/// Start every member in dependency order, waves in parallel. A member whose dependency
+/// failed is skipped; results follow the start order.
+pub fn start_group(group: &ServerGroup) -> Result<Vec<GroupMemberResult>, String> {
+    let waves = start_order(group)?;
+    let mut saved = mcp_lifecycle::saved_configs();
+    let mut configs: HashMap<&str, Option<StdioConfig>> =
+        group.members.iter().map(|m| (m.server_id.as_str(), m.config.clone().or_else(|| saved.remove(&m.server_id)))).collect();
+    let depends_on: HashMap<&str, &[String]> = group.members.iter().map(|m| (m.server_id.as_str(), m.depends_on.as_slice())).collect();
+
+    let mut results: Vec<GroupMemberResult> = Vec::new();
+    for wave in waves {
+        let mut runnable = Vec::new();
+        for id in wave {
+            let failed_dep = depends_on[id.as_str()].iter().find(|dep| results.iter().any(|r| r.server_id == **dep && !r.success));
+            match (failed_dep, configs.get_mut(id.as_str()).and_then(Option::take)) {
+                (Some(dep), _) => results.push(GroupMemberResult::failed(&id, format!("Skipped: dependency {dep} failed to start"))),
+                (None, None) => results.push(GroupMemberResult::failed(&id, "No configuration found for server".into())),
+                (None, Some(cfg)) => runnable.push((id, cfg)),
+            }
+        }
+        let ids: Vec<String> = runnable.iter().map(|(id, _)| id.clone()).collect();
+        let cfgs: HashMap<String, StdioConfig> = runnable.into_iter().collect();
+        results.extend(run_wave(&ids, |id| start_member(id, cfgs[id].clone())));
+    }
+    Ok(results)
+}
+
+/// Stop every member in reverse dependency order, waves in parallel
+pub fn stop_group(group: &ServerGroup) -> Result<Vec<GroupMemberResult>, String> {
+    let mut results = Vec::new();
+    for wave in start_order(group)?.into_iter().rev() {
+        results.extend(run_wave(&wave, |id| match mcp_lifecycle::stop_server(id, false, None) {
+            Ok(_) => GroupMemberResult::ok(id, Some(LifecycleState::Stopped)),
+            // Not running is the desired end state
+            Err(e) if e.starts_with("No running process") => GroupMemberResult::ok(id, Some(LifecycleState::Stopped)),
+            Err(e) => GroupMemberResult::failed(id, e),
+        }));
+    }
+    Ok(results)
+}
+
+fn groups_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    Ok(dir.join(GROUPS_FILE))
+}
+
+pub fn load_groups(path: &Path) -> Result<Vec<ServerGroup>, String> {
+    match fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content).map_err(|e| format!("Failed to parse server groups: {e}")),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(vec![]),
+        Err(e) => Err(format!("Failed to read server groups: {e}")),
+    }
+}
+
+fn save_groups(path: &Path, groups: &[ServerGroup]) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(groups).map_err(|e| format!("Failed to serialize server groups: {e}"))?;
+    fs::write(path, content).map_err(|e| format!("Failed to write server groups: {e}"))
+}
+
+fn find_group(app: &AppHandle, group_id: &str) -> Result<ServerGroup, String> {
+    load_groups(&groups_path(app)?)?.into_iter().find(|g| g.id == group_id).ok_or_else(|| format!("No group {group_id}"))
+}
+
+/// Groups flagged for autostart
+pub fn autostart_groups(app: &AppHandle) -> Vec<ServerGroup> {
+    match groups_path(app).and_then(|path| load_groups(&path)) {
+        Ok(groups) => groups.into_iter().filter(|g| g.autostart).collect(),
+        Err(e) => {
+            log::warn!("Skipping group autostart: {e}");
+            Vec::new()
+        }
+    }
+}
+
+/// Start `groups` one after another, blocking until done. Runs after orphan recovery so
+/// members that survived the last session are reused rather than started twice.
+pub fn autostart(groups: Vec<ServerGroup>) {
+    for group in groups {
+        match start_group(&group) {
+            Ok(results) => {
+                let failed: Vec<&str> = results.iter().filter(|r| !r.success).map(|r| r.server_id.as_str()).collect();
+                if failed.is_empty() {
+                    log::info!("Autostarted group {} ({} servers)", group.id, results.len());
+                } else {
+                    log::warn!("Autostarted group {} with failures: {}", group.id, failed.join(", "));
+                }
+            }
+            Err(e) => log::error!("Failed to autostart group {}: {e}", group.id),
+        }
+    }
+}
+
+#[tauri::command]
+pub fn mcp_list_groups(app: AppHandle) -> Result<Vec<ServerGroup>, String> {
+    load_groups(&groups_path(&app)?)
+}
+
+/// Create or replace a group; rejected if its dependencies are inconsistent
+#[tauri::command]
+pub fn mcp_save_group(app: AppHandle, group: ServerGroup) -> Result<(), String> {
+    start_order(&group)?;
+    let path = groups_path(&app)?;
+    let mut groups = load_groups(&path)?;
+    match groups.iter_mut().find(|g| g.id == group.id) {
+        Some(existing) => *existing = group,
+        None => groups.push(group),
+    }
+    save_groups(&path, &groups)
+}
+
+#[tauri::command]
+pub fn mcp_delete_group(app: AppHandle, group_id: String) -> Result<(), String> {
+    let path = groups_path(&app)?;
+    let mut groups = load_groups(&path)?;
+    groups.retain(|g| g.id != group_id);
+    save_groups(&path, &groups)
+}
+
+/// Start a group in dependency order; returns one result per member
+#[tauri::command]
+pub async fn mcp_start_group(app: AppHandle, group_id: String) -> Result<Vec<GroupMemberResult>, String> {
+    let group = find_group(&app, &group_id)?;
+    tauri::async_runtime::spawn_blocking(move || start_group(&group))
+        .await
+        .map_err(|e| format!("Group task failed: {e}"))?
+}
+
+/// Stop a group in reverse dependency order; returns one result per member
+#[tauri::command]
+pub async fn mcp_stop_group(app: AppHandle, group_id: String) -> Result<Vec<GroupMemberResult>, String> {
+    let group = find_group(&app, &group_id)?;
+    tauri::async_runtime::spawn_blocking(move || stop_group(&group))
+        .await
+        .map_err(|e| format!("Group task failed: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp_lifecycle::HealthCheckConfig;
+
+    fn member(id: &str, deps: &[&str], cfg: Option<StdioConfig>) -> GroupMember {
+        GroupMember { server_id: id.into(), depends_on: deps.iter().map(|d| d.to_string()).collect(), config: cfg }
+    }
+
+    fn group(members: Vec<GroupMember>) -> ServerGroup {
+        ServerGroup { id: "g".into(), name: "Group".into(), members, autostart: false }
+    }
+
+    /// Test waves respect dependencies and reject cycles and unknown members
+    #[test]
+    fn test_start_order() {
+        let g = group(vec![member("app", &["db", "fs"], None), member("db", &[], None), member("fs", &["db"], None), member("cache", &[], None)]);
+        assert_eq!(start_order(&g).unwrap(), vec![vec!["db".to_string(), "cache".to_string()], vec!["fs".to_string()], vec!["app".to_string()]]);
+
+        let cycle = group(vec![member("a", &["b"], None), member("b", &["a"], None), member("c", &[], None)]);
+        assert!(start_order(&cycle).unwrap_err().contains("a, b"));
+        assert!(start_order(&group(vec![member("a", &["x"], None)])).unwrap_err().contains("x"));
+        assert!(start_order(&group(vec![member("a", &[], None), member("a", &[], None)])).is_err());
+    }
+
+    /// Test a group starts in order, skips dependents of failures and stops again
+    #[cfg(unix)]
+    #[test]
+    fn test_start_and_stop_group() {
+        let cfg = |script: &str| StdioConfig {
+            command: "sh".into(),
+            args: vec!["-c".into(), script.into()],
+            health: HealthCheckConfig { handshake: false, ..Default::default() },
+            ..Default::default()
+        };
+        let g = group(vec![
+            member("group-test-app", &["group-test-db"], Some(cfg("sleep 60"))),
+            member("group-test-db", &[], Some(cfg("sleep 60"))),
+            member("group-test-broken", &[], None),
+            member("group-test-dependent", &["group-test-broken"], Some(cfg("sleep 60"))),
+        ]);
+        let results = start_group(&g).unwrap();
+        let by_id: HashMap<&str, &GroupMemberResult> = results.iter().map(|r| (r.server_id.as_str(), r)).collect();
+        assert!(by_id["group-test-db"].success && by_id["group-test-app"].success);
+        assert_eq!(by_id["group-test-db"].state, Some(LifecycleState::Running));
+        assert!(by_id["group-test-broken"].error.as_deref().unwrap().contains("No configuration"));
+        assert!(by_id["group-test-dependent"].error.as_deref().unwrap().contains("Skipped"));
+        assert!(mcp_lifecycle::mcp_get_status("group-test-dependent".into()).is_err());
+
+        let stopped = stop_group(&g).unwrap();
+        assert!(stopped.iter().all(|r| r.success), "{stopped:?}");
+        // Dependents stop before their dependencies
+        let pos = |id: &str| stopped.iter().position(|r| r.server_id == id).unwrap();
+        assert!(pos("group-test-app") < pos("group-test-db"));
+        assert!(mcp_lifecycle::mcp_get_status("group-test-db".into()).is_err());
+    }
+}
@@ -1,7 +1,32 @@
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, path::PathBuf, process::{Command, Stdio, Child}, sync::{Mutex, OnceLock}};
+use std::{collections::HashMap, io::Read, path::{Path, PathBuf}, process::{Command, ExitStatus, Stdio, Child}, sync::{Arc, Mutex, OnceLock}, thread::JoinHandle, time::{Duration, Instant}};
 use tauri::{AppHandle, Manager};
 
+use crate::mcp_lifecycle::emit_event;
+use crate::mcp_output::OutputStream;
+use crate::process_tree;
+use crate::release_archive;
+
+/// Event emitted whenever an installation's progress changes
+pub const INSTALL_PROGRESS_EVENT: &str = "mcp-install-progress";
+/// Event emitted for every line npm/git write during an installation
+pub const INSTALL_OUTPUT_EVENT: &str = "mcp-install-output";
+/// Lines kept in `InstallationProgress.logs`; older ones are dropped in batches of `LOG_TRIM_BATCH`
+const MAX_INSTALL_LOG_LINES: usize = 1000;
+const LOG_TRIM_BATCH: usize = 100;
+
+/// Time npm/git get to exit after SIGTERM when their installation is cancelled
+const CANCEL_GRACE: Duration = Duration::from_secs(2);
+/// How long `cancel_install` waits for the installation to roll back
+const ROLLBACK_WAIT: Duration = Duration::from_secs(10);
+const INSTALL_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// How long the output readers get after npm/git exit before descendants still holding
+/// the pipes open are killed
+const READER_DRAIN_WAIT: Duration = Duration::from_secs(2);
+/// Output without a line break is passed on in segments of this size
+const MAX_SEGMENT_BYTES: usize = 8 * 1024;
+const CANCELLED: &str = "Installation cancelled";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "source")]
 pub enum InstallConfig {
@@ -11,6 +36,15 @@ pub enum InstallConfig {
     GitHub { repository: String, branch: Option<String>, tag: Option<String>, commit: Option<String>, sub_path: Option<String> },
     #[serde(rename = "local")]
     Local { path: String, validate: Option<bool> },
+    /// Python package installed into its own venv, with uv when available
+    #[serde(rename = "pypi")]
+    PyPI { package_name: String, version: Option<String>, extras: Option<Vec<String>>, index_url: Option<String> },
+    /// Prebuilt release archive (tar.gz/zip); `url` may contain `{target}`, `{os}`, `{arch}` and `{version}`
+    #[serde(rename = "release")]
+    Release { url: String, version: Option<String>, name: Option<String>, sha256: Option<String>, checksums_url: Option<String>, entrypoint: Option<String> },
+    /// Rust crate built with `cargo install`, from crates.io or a git repository
+    #[serde(rename = "cargo")]
+    Cargo { crate_name: String, version: Option<String>, features: Option<Vec<String>>, git: Option<String>, rev: Option<String> },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +79,9 @@ pub struct InstallMetadata {
     /// Path to the IDE config file this was imported from
     #[serde(default)]
     pub config_source_path: Option<String>,
+    /// Executable to launch for sources that install a binary
+    #[serde(default)]
+    pub entrypoint: Option<String>,
 }
 
 static INSTALLS: OnceLock<Mutex<HashMap<String, InstallationProgress>>> = OnceLock::new();
@@ -59,6 +96,187 @@ fn now_iso() -> String { chrono::Utc::now().to_rfc3339() }
 
 fn npm_available() -> bool { Command::new("npm").arg("--version").stdout(Stdio::null()).stderr(Stdio::null()).status().map(|s| s.success()).unwrap_or(false) }
 fn git_available() -> bool { Command::new("git").arg("--version").stdout(Stdio::null()).stderr(Stdio::null()).status().map(|s| s.success()).unwrap_or(false) }
+fn uv_available() -> bool { Command::new("uv").arg("--version").stdout(Stdio::null()).stderr(Stdio::null()).status().map(|s| s.success()).unwrap_or(false) }
+
+/// Version reported by `<cmd> --version`, e.g. "1.80.0" from "cargo 1.80.0 (376290515 2024-07-16)"
+fn tool_version(cmd: &str) -> Option<String> {
+    let out = Command::new(cmd).arg("--version").stdin(Stdio::null()).output().ok()?;
+    if !out.status.success() { return None; }
+    String::from_utf8_lossy(&out.stdout).split_whitespace().nth(1).map(str::to_string)
+}
+
+/// Problems with a cargo config. Checked again at install time since the crate name ends up as
+/// a directory under `mcp_servers/cargo` and as a cargo argument.
+fn cargo_config_errors(crate_name: &str, version: Option<&str>, features: Option<&[String]>, git: Option<&str>, rev: Option<&str>) -> Vec<String> {
+    let name_re = regex::Regex::new(r"^[A-Za-z0-9][A-Za-z0-9_-]{0,63}$").unwrap();
+    let feature_re = regex::Regex::new(r"^[A-Za-z0-9_][A-Za-z0-9_+.-]*(/[A-Za-z0-9_][A-Za-z0-9_+.-]*)?$").unwrap();
+    let version_re = regex::Regex::new(r"^[A-Za-z0-9.*+<>=~^, -]+$").unwrap();
+    let rev_re = regex::Regex::new(r"^[A-Za-z0-9_][A-Za-z0-9._/-]*$").unwrap();
+    let mut errors = Vec::new();
+    if !name_re.is_match(crate_name) { errors.push("Invalid crate name".to_string()); }
+    if version.is_some_and(|v| !version_re.is_match(v) || v.trim().starts_with('-')) { errors.push("Invalid version requirement".into()); }
+    if features.into_iter().flatten().any(|f| !feature_re.is_match(f)) { errors.push("Invalid feature name".into()); }
+    match git {
+        Some(url) if !["https://", "http://", "ssh://", "git@", "file://"].iter().any(|p| url.starts_with(p)) => errors.push("Invalid git URL".into()),
+        Some(_) if version.is_some() => errors.push("version cannot be combined with git; use rev instead".into()),
+        None if rev.is_some() => errors.push("rev requires a git URL".into()),
+        _ => {}
+    }
+    if rev.is_some_and(|r| !rev_re.is_match(r)) { errors.push("Invalid git rev".into()); }
+    errors
+}
+
+/// `cargo install` into its own root so the crate's binaries stay out of `~/.cargo/bin`
+fn cargo_install_command(crate_name: &str, version: Option<&str>, features: Option<&[String]>, git: Option<&str>, rev: Option<&str>, root: &Path) -> Command {
+    let mut cmd = Command::new("cargo");
+    cmd.args(["install", "--root"]).arg(root).args(["--force", "--color", "never"]);
+    // `--opt=value` keeps values from being read as options
+    if let Some(git) = git {
+        cmd.arg(format!("--git={git}"));
+        if let Some(rev) = rev { cmd.arg(format!("--rev={rev}")); }
+    } else if let Some(v) = version {
+        cmd.arg(format!("--version={v}"));
+    }
+    if let Some(features) = features.filter(|f| !f.is_empty()) { cmd.arg(format!("--features={}", features.join(","))); }
+    cmd.args(["--", crate_name]);
+    cmd
+}
+
+/// Oldest Python the MCP Python SDK supports
+const MIN_PYTHON: (u32, u32) = (3, 10);
+
+/// First Python interpreter on PATH and its version
+fn find_python() -> Option<(&'static str, (u32, u32))> {
+    ["python3", "python"].into_iter().find_map(|cmd| {
+        let out = Command::new(cmd).arg("--version").stdin(Stdio::null()).output().ok()?;
+        if !out.status.success() { return None; }
+        // Python 2 printed its version to stderr
+        let text = format!("{}{}", String::from_utf8_lossy(&out.stdout), String::from_utf8_lossy(&out.stderr));
+        parse_python_version(&text).map(|v| (cmd, v))
+    })
+}
+
+/// "Python 3.11.4" -> (3, 11)
+fn parse_python_version(text: &str) -> Option<(u32, u32)> {
+    let mut parts = text.trim().strip_prefix("Python ")?.split('.');
+    Some((parts.next()?.parse().ok()?, parts.next()?.trim_end_matches(|c: char| !c.is_ascii_digit()).parse().ok()?))
+}
+
+/// Problems with a PyPI config. Checked again at install time since the names end up as a
+/// directory under `mcp_servers/pypi` and as pip arguments.
+fn pypi_config_errors(package_name: &str, version: Option<&str>, extras: Option<&[String]>, index_url: Option<&str>) -> Vec<String> {
+    // PEP 508 names, also used for extras
+    let name_re = regex::Regex::new(r"^[A-Za-z0-9]([A-Za-z0-9._-]*[A-Za-z0-9])?$").unwrap();
+    // Versions or PEP 440 specifiers such as "1.2.0" or ">=1.0,<2"; no markers or URLs
+    let version_re = regex::Regex::new(r"^[A-Za-z0-9.*+!<>=~, _-]*[A-Za-z0-9*]$").unwrap();
+    let mut errors = Vec::new();
+    if !name_re.is_match(package_name) || !is_dir_name(&package_name.to_lowercase()) { errors.push("Invalid Python package name".to_string()); }
+    if version.is_some_and(|v| !version_re.is_match(v.trim()) || v.trim().starts_with('-')) { errors.push("Invalid version specifier".into()); }
+    if extras.into_iter().flatten().any(|e| !name_re.is_match(e)) { errors.push("Invalid extra name".into()); }
+    if index_url.is_some_and(|u| !(u.starts_with("https://") || u.starts_with("http://"))) { errors.push("Index URL must be an http(s) URL".into()); }
+    errors
+}
+
+/// pip requirement for a PyPI install, e.g. `mcp-server-fetch[http]==1.2.0`
+fn pypi_requirement(package_name: &str, version: Option<&str>, extras: Option<&[String]>) -> String {
+    let mut spec = package_name.to_string();
+    if let Some(extras) = extras.filter(|e| !e.is_empty()) { spec.push_str(&format!("[{}]", extras.join(","))); }
+    match version.map(str::trim).filter(|v| !v.is_empty()) {
+        // Already a specifier such as ">=1.0" or "~=2.1"
+        Some(v) if v.starts_with(['<', '>', '=', '!', '~']) => spec.push_str(v),
+        Some(v) => { spec.push_str("=="); spec.push_str(v); }
+        None => {}
+    }
+    spec
+}
+
+/// Install directory name for a release: the configured name, else the archive name without
+/// extension, version and target suffixes
+fn release_name(name: Option<&str>, url: &str) -> String {
+    if let Some(n) = name.map(str::trim).filter(|n| !n.is_empty()) { return n.to_string(); }
+    let file = release_archive::file_name(url);
+    let stem = [".tar.gz", ".tgz", ".zip"].iter().find_map(|ext| file.to_ascii_lowercase().ends_with(ext).then(|| &file[..file.len() - ext.len()])).unwrap_or(file);
+    let triple = release_archive::target_triple();
+    let stem = stem.split(&*triple).next().unwrap_or(stem).trim_end_matches(['-', '_', '.']);
+    let stem = stem.split(['-', '_']).take_while(|part| !part.trim_start_matches('v').starts_with(|c: char| c.is_ascii_digit())).collect::<Vec<_>>().join("-");
+    if stem.is_empty() { "release".into() } else { stem }
+}
+
+/// A name usable as one directory below `mcp_servers/<source>`, never `.`, `..` or a path
+fn is_dir_name(name: &str) -> bool {
+    let mut components = Path::new(name).components();
+    matches!((components.next(), components.next()), (Some(std::path::Component::Normal(c)), None) if c == std::ffi::OsStr::new(name))
+        && !name.contains(['/', '\\'])
+}
+
+/// Download, verify and unpack a release archive into the freshly created `target`, returning
+/// its entrypoint; a cancellation removes `target`
+fn install_release(install_id: &str, url: &str, sha256: Option<&str>, checksums_url: Option<&str>, entrypoint: Option<&str>, name: &str, target: &Path) -> Result<PathBuf, String> {
+    let file = release_archive::file_name(url).to_string();
+    let format = release_archive::ArchiveFormat::from_name(&file).ok_or_else(|| format!("Unsupported archive type: {file} (expected .tar.gz, .tgz or .zip)"))?;
+    let expected = match (sha256, checksums_url) {
+        (Some(hash), _) => hash.trim().to_ascii_lowercase(),
+        (None, Some(list)) => release_archive::checksum_for(&release_archive::fetch_text(list)?, &file).ok_or_else(|| format!("No checksum for {file} in {list}"))?,
+        (None, None) => return Err("A sha256 checksum or checksums file is required".into()),
+    };
+
+    let archive = target.join(format!(".download-{file}"));
+    let mut last = 0;
+    let downloaded = release_archive::download(url, &archive, |done, total| {
+        if let Some(total) = total.filter(|t| *t > 0) {
+            let progress = 10 + (50 * done / total) as u8;
+            if progress != last {
+                last = progress;
+                update(install_id, |p| { p.progress = progress; p.message = format!("Downloading {file}... {} / {} KiB", done / 1024, total / 1024); });
+            }
+        }
+        !is_cancelled(install_id)
+    });
+    check_cancelled(install_id, Some(target))?;
+    let actual = downloaded.inspect_err(|_| { let _ = std::fs::remove_file(&archive); })?;
+    if actual != expected {
+        let _ = std::fs::remove_file(&archive);
+        return Err(format!("Checksum mismatch for {file}: expected {expected}, got {actual}"));
+    }
+    push_log(install_id, OutputStream::Stdout, format!("Verified {file} (sha256 {actual})"));
+
+    update(install_id, |p| { p.status=InstallationStatus::Installing; p.progress=65; p.message=format!("Extracting {file}..."); p.current_step=Some("Extracting".into()); p.current_step_number=Some(2); });
+    let extracted = release_archive::extract(&archive, format, target);
+    let _ = std::fs::remove_file(&archive);
+    extracted?;
+    check_cancelled(install_id, Some(target))?;
+
+    let entry = release_archive::find_entrypoint(target, entrypoint, name)?;
+    release_archive::make_executable(&entry)?;
+    push_log(install_id, OutputStream::Stdout, format!("Entrypoint: {}", entry.display()));
+    Ok(entry)
+}
+
+/// Move `staging` to `target`, replacing any previous install, and return where `entry`
+/// (inside `staging`) ends up
+fn replace_dir(staging: &Path, target: &Path, entry: &Path) -> Result<PathBuf, String> {
+    let rel = entry.strip_prefix(staging.canonicalize().map_err(|e| e.to_string())?).map_err(|_| "Entrypoint is outside the install directory".to_string())?.to_path_buf();
+    let mut backup = staging.as_os_str().to_owned();
+    backup.push(".old");
+    let backup = PathBuf::from(backup);
+    let had_previous = target.exists();
+    if had_previous {
+        std::fs::rename(target, &backup).map_err(|e| format!("Failed to replace {}: {e}", target.display()))?;
+    }
+    if let Err(e) = std::fs::rename(staging, target) {
+        if had_previous { let _ = std::fs::rename(&backup, target); }
+        return Err(format!("Failed to move release into {}: {e}", target.display()));
+    }
+    if had_previous {
+        if let Err(e) = std::fs::remove_dir_all(&backup) { log::warn!("Failed to remove previous release {}: {}", backup.display(), e); }
+    }
+    Ok(target.join(rel))
+}
+
+/// The interpreter inside a venv created at `venv`
+fn venv_python(venv: &Path) -> PathBuf {
+    if cfg!(windows) { venv.join("Scripts").join("python.exe") } else { venv.join("bin").join("python") }
+}
 
 #[tauri::command]
 pub fn validate_install(config: InstallConfig) -> Result<InstallationValidation, String> {
@@ -85,6 +303,56 @@ pub fn validate_install(config: InstallConfig) -> Result<InstallationValidation,
             if !pb.exists() || !pb.is_dir() { res.valid=false; res.errors.push("Path must exist and be a directory".into()); }
             res.estimated_size=Some(0); res.estimated_time=Some(1);
         }
+        InstallConfig::PyPI { package_name, version, extras, index_url } => {
+            let errors = pypi_config_errors(package_name, version.as_deref(), extras.as_deref(), index_url.as_deref());
+            if !errors.is_empty() { res.valid=false; res.errors.extend(errors); }
+
+            // uv can provision its own interpreter, so Python is only required without it
+            let uv = uv_available();
+            res.dependencies.push(DependencyInfo{ name:"uv".into(), required:false, installed:uv, install_path:None });
+            let python = find_python();
+            res.dependencies.push(DependencyInfo{ name:format!("python>={}.{}", MIN_PYTHON.0, MIN_PYTHON.1), required:!uv, installed:python.is_some_and(|(_, v)| v >= MIN_PYTHON), install_path:None });
+            match python {
+                Some((_, v)) if v >= MIN_PYTHON => {}
+                Some((_, (major, minor))) if uv => res.warnings.push(format!("Python {major}.{minor} on PATH is older than {}.{}; uv will pick a newer interpreter if it can", MIN_PYTHON.0, MIN_PYTHON.1)),
+                Some((_, (major, minor))) => { res.valid=false; res.errors.push(format!("Python {}.{} or newer is required, found {major}.{minor}", MIN_PYTHON.0, MIN_PYTHON.1)); }
+                None if uv => {}
+                None => { res.valid=false; res.errors.push("Neither uv nor python is available on PATH".into()); }
+            }
+            if !uv { res.warnings.push("uv not found; falling back to python -m venv and pip".into()); }
+            res.estimated_size=Some(30*1024*1024); res.estimated_time=Some(if uv { 15 } else { 60 });
+        }
+        InstallConfig::Release { url, version, name, sha256, checksums_url, .. } => {
+            let resolved = release_archive::expand_url(url, version.as_deref());
+            if !is_dir_name(&release_name(name.as_deref(), &resolved)) { res.valid=false; res.errors.push("Release name must be a single directory name".into()); }
+            if !(resolved.starts_with("https://") || resolved.starts_with("http://")) { res.valid=false; res.errors.push("Release URL must be an http(s) URL".into()); }
+            if resolved.contains("{version}") { res.valid=false; res.errors.push("Release URL uses {version} but no version was given".into()); }
+            if release_archive::ArchiveFormat::from_name(release_archive::file_name(&resolved)).is_none() { res.valid=false; res.errors.push("Release archive must be a .tar.gz, .tgz or .zip file".into()); }
+            match (sha256, checksums_url) {
+                (Some(hash), _) if !release_archive::is_sha256_hex(hash.trim()) => { res.valid=false; res.errors.push("sha256 must be 64 hex characters".into()); }
+                (None, None) => { res.valid=false; res.errors.push("A sha256 checksum or checksums file URL is required".into()); }
+                _ => {}
+            }
+            if !url.contains("{target}") { res.warnings.push(format!("Release URL has no {{target}} placeholder; make sure the archive is built for {}", release_archive::target_triple())); }
+            res.estimated_size=Some(20*1024*1024); res.estimated_time=Some(20);
+        }
+        InstallConfig::Cargo { crate_name, version, features, git, rev } => {
+            let errors = cargo_config_errors(crate_name, version.as_deref(), features.as_deref(), git.as_deref(), rev.as_deref());
+            if !errors.is_empty() { res.valid=false; res.errors.extend(errors); }
+
+            for tool in ["cargo", "rustc"] {
+                match tool_version(tool) {
+                    Some(v) => res.dependencies.push(DependencyInfo{ name:format!("{tool} {v}"), required:true, installed:true, install_path:None }),
+                    None => {
+                        res.valid=false;
+                        res.errors.push(format!("{tool} is not available on PATH"));
+                        res.dependencies.push(DependencyInfo{ name:tool.into(), required:true, installed:false, install_path:None });
+                    }
+                }
+            }
+            if git.is_some() && !git_available() { res.warnings.push("git is not on PATH; cargo will use its built-in git support".into()); }
+            res.estimated_size=Some(200*1024*1024); res.estimated_time=Some(300);
+        }
     }
     Ok(res)
 }
@@ -99,6 +367,10 @@ pub fn install_server(app: AppHandle, config: InstallConfig, _server_name: Strin
     let id_for_thread = install_id.clone();
     std::thread::spawn(move || {
         if let Err(e) = do_install(app, id_for_thread.clone(), config) {
+            // A cancelled installation already reported its own outcome
+            if is_cancelled(&id_for_thread) {
+                return;
+            }
             // Report error in progress tracking
             log::error!("Installation {} failed: {}", id_for_thread, e);
             update(&id_for_thread, |p| {
@@ -115,7 +387,278 @@ pub fn install_server(app: AppHandle, config: InstallConfig, _server_name: Strin
     Ok((install_id, current))
 }
 
-fn update(install_id: &str, patch: impl FnOnce(&mut InstallationProgress)) { if let Ok(mut map)=installs().lock(){ if let Some(p)=map.get_mut(install_id){ patch(p); } } }
+fn update(install_id: &str, patch: impl FnOnce(&mut InstallationProgress)) {
+    let snapshot = installs().lock().ok().and_then(|mut map| map.get_mut(install_id).map(|p| { patch(p); p.clone() }));
+    if let Some(p) = snapshot { emit_event(INSTALL_PROGRESS_EVENT, p); }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InstallOutputEvent { pub install_id: String, pub stream: OutputStream, pub line: String }
+
+/// Which tool a step runs, to know how to read progress out of its output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tool { Npm, Git, Pip, Cargo }
+
+/// One subprocess of an installation, covering `from..to` of the overall progress
+#[derive(Debug, Clone, Copy)]
+struct Step { tool: Tool, from: u8, to: u8 }
+
+/// Turns npm/git output into percent complete of the current step
+#[derive(Debug)]
+struct ProgressParser { tool: Tool, fetched: u32 }
+
+impl ProgressParser {
+    fn new(tool: Tool) -> Self { Self { tool, fetched: 0 } }
+
+    /// Percent of the step done and a status message, if the line tells
+    fn parse(&mut self, line: &str) -> Option<(u8, String)> {
+        match self.tool {
+            // git clone --progress: "Receiving objects:  45% (450/1000), 1.2 MiB | 2.0 MiB/s"
+            Tool::Git => {
+                let (phase, from, to) = [("Receiving objects:", 0u32, 70u32), ("Resolving deltas:", 70, 90), ("Updating files:", 90, 100)]
+                    .into_iter()
+                    .find(|(phase, _, _)| line.contains(phase))?;
+                let pct = percent_in(&line[line.find(phase)? + phase.len()..])?;
+                Some(((from + (to - from) * pct as u32 / 100) as u8, line.trim().to_string()))
+            }
+            // npm --loglevel=http: one "npm http fetch GET 200 <url>" line per package, then "added N packages"
+            Tool::Npm => {
+                if line.starts_with("added ") || line.starts_with("changed ") || line.starts_with("up to date") {
+                    return Some((100, line.trim().to_string()));
+                }
+                if !line.contains("http fetch") {
+                    return None;
+                }
+                self.fetched += 1;
+                // The package count is unknown up front, so approach 90% as fetches accumulate
+                let pct = 90 * self.fetched / (self.fetched + 25);
+                Some((pct as u8, format!("Fetched {} package{}", self.fetched, if self.fetched == 1 { "" } else { "s" })))
+            }
+            // pip: "Collecting x" per requirement, then "Installing collected packages" and "Successfully installed";
+            // uv: "Resolved N packages", "Prepared N packages", "Installed N packages"
+            Tool::Pip => {
+                let line = line.trim();
+                if line.starts_with("Successfully installed") || (line.starts_with("Installed ") && line.contains(" package")) {
+                    return Some((100, line.to_string()));
+                }
+                if line.starts_with("Installing collected packages") || (line.starts_with("Prepared ") && line.contains(" package")) {
+                    return Some((70, line.to_string()));
+                }
+                if line.starts_with("Resolved ") && line.contains(" package") {
+                    return Some((30, line.to_string()));
+                }
+                if !line.starts_with("Collecting ") {
+                    return None;
+                }
+                self.fetched += 1;
+                Some(((60 * self.fetched / (self.fetched + 10)) as u8, line.to_string()))
+            }
+            // cargo: "Downloaded"/"Compiling" per crate, then "Finished", "Installing" and "Installed package"
+            Tool::Cargo => {
+                let line = line.trim();
+                let word = line.split_whitespace().next()?;
+                match word {
+                    "Installed" | "Replaced" => Some((100, line.to_string())),
+                    // "Installing <crate> vX" opens the build; "Installing <path>" copies the binaries after it
+                    "Installing" | "Replacing" if line.split_whitespace().nth(1).is_some_and(|p| Path::new(p).is_absolute()) => Some((95, line.to_string())),
+                    "Finished" => Some((90, line.to_string())),
+                    "Compiling" => {
+                        self.fetched += 1;
+                        // The crate count is unknown up front, so approach 85% as crates build
+                        Some(((10 + 75 * self.fetched / (self.fetched + 40)) as u8, line.to_string()))
+                    }
+                    "Downloaded" | "Updating" | "Locking" => Some((5, line.to_string())),
+                    _ => None,
+                }
+            }
+        }
+    }
+}
+
+/// The first "NN%" in `s`, capped at 100
+fn percent_in(s: &str) -> Option<u8> {
+    let end = s.find('%')?;
+    let digits = s[..end].trim_end().rsplit(|c: char| !c.is_ascii_digit()).next()?;
+    digits.parse::<u8>().ok().map(|p| p.min(100))
+}
+
+fn push_log(install_id: &str, stream: OutputStream, line: String) {
+    if let Ok(mut map) = installs().lock() {
+        if let Some(p) = map.get_mut(install_id) {
+            let logs = p.logs.get_or_insert_with(Vec::new);
+            logs.push(line.clone());
+            if logs.len() >= MAX_INSTALL_LOG_LINES + LOG_TRIM_BATCH {
+                logs.drain(..logs.len() - MAX_INSTALL_LOG_LINES);
+            }
+        }
+    }
+    emit_event(INSTALL_OUTPUT_EVENT, InstallOutputEvent { install_id: install_id.to_string(), stream, line });
+}
+
+/// Split a pipe on both `\n` and `\r`; `\r`-terminated segments are progress redraws, so they
+/// only feed the parser while complete lines are also logged. A run of `MAX_SEGMENT_BYTES`
+/// without either is passed on as a complete segment so `pending` stays bounded.
+fn drain_output<R: Read>(mut reader: R, mut on_segment: impl FnMut(&str, bool)) {
+    let mut buf = [0u8; 4096];
+    let mut pending: Vec<u8> = Vec::new();
+    loop {
+        let n = match reader.read(&mut buf) { Ok(0) | Err(_) => break, Ok(n) => n };
+        for &b in &buf[..n] {
+            if b == b'\n' || b == b'\r' {
+                let segment = String::from_utf8_lossy(&pending).into_owned();
+                pending.clear();
+                if !segment.trim().is_empty() { on_segment(&segment, b == b'\n'); }
+            } else {
+                pending.push(b);
+                if pending.len() >= MAX_SEGMENT_BYTES {
+                    let segment = String::from_utf8_lossy(&pending).into_owned();
+                    pending.clear();
+                    on_segment(&segment, true);
+                }
+            }
+        }
+    }
+    let segment = String::from_utf8_lossy(&pending).into_owned();
+    if !segment.trim().is_empty() { on_segment(&segment, true); }
+}
+
+/// Stream one pipe of an installer step into the installation's logs and progress
+fn attach_output<R: Read + Send + 'static>(install_id: &str, stream: OutputStream, reader: R, step: Step, parser: Arc<Mutex<ProgressParser>>) -> JoinHandle<()> {
+    let install_id = install_id.to_string();
+    std::thread::spawn(move || {
+        drain_output(reader, |segment, complete| {
+            let segment = segment.trim_end();
+            if complete {
+                push_log(&install_id, stream, segment.to_string());
+            }
+            let Some((pct, message)) = parser.lock().ok().and_then(|mut p| p.parse(segment)) else { return };
+            let progress = step.from + ((step.to - step.from) as u32 * pct as u32 / 100) as u8;
+            let snapshot = installs().lock().ok().and_then(|mut map| {
+                let p = map.get_mut(&install_id)?;
+                // Never move backwards, and only emit when something visible changed
+                if matches!(p.status, InstallationStatus::Cancelled) || (progress <= p.progress && p.message == message) {
+                    return None;
+                }
+                p.progress = p.progress.max(progress);
+                p.message = message;
+                Some(p.clone())
+            });
+            if let Some(p) = snapshot {
+                emit_event(INSTALL_PROGRESS_EVENT, p);
+            }
+        });
+    })
+}
+
+fn is_cancelled(install_id: &str) -> bool {
+    installs().lock().ok().and_then(|map| map.get(install_id).map(|p| matches!(p.status, InstallationStatus::Cancelled))).unwrap_or(false)
+}
+
+/// Create the target directory; returns it if it did not exist before, so a cancelled
+/// installation knows what to roll back
+fn create_target(target: &Path) -> Result<Option<PathBuf>, String> {
+    let created = !target.exists();
+    std::fs::create_dir_all(target).map_err(|e| e.to_string())?;
+    Ok(created.then(|| target.to_path_buf()))
+}
+
+/// If the installation was cancelled, remove the directory it created and report it finished
+fn check_cancelled(install_id: &str, created: Option<&Path>) -> Result<(), String> {
+    if !is_cancelled(install_id) {
+        return Ok(());
+    }
+    let mut message = CANCELLED.to_string();
+    if let Some(dir) = created {
+        match std::fs::remove_dir_all(dir) {
+            Ok(()) => message = format!("{CANCELLED}; removed {}", dir.display()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                log::warn!("Failed to roll back {}: {}", dir.display(), e);
+                message = format!("{CANCELLED}; failed to remove {}: {}", dir.display(), e);
+            }
+        }
+    }
+    log::info!("Installation {}: {}", install_id, message);
+    update(install_id, |p| { p.message = message; p.completed_at = Some(now_iso()); });
+    Err(CANCELLED.into())
+}
+
+/// Run an installer subprocess in its own process group, registered in `INSTALL_PROCESSES`
+/// so `cancel_install` can kill the whole tree
+fn run_tracked(install_id: &str, mut cmd: Command, step: Step) -> Result<ExitStatus, String> {
+    if is_cancelled(install_id) {
+        return Err(CANCELLED.into());
+    }
+    process_tree::isolate(&mut cmd);
+    let mut child = cmd.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn().map_err(|e| e.to_string())?;
+    let parser = Arc::new(Mutex::new(ProgressParser::new(step.tool)));
+    let mut readers = Vec::new();
+    if let Some(out) = child.stdout.take() { readers.push(attach_output(install_id, OutputStream::Stdout, out, step, parser.clone())); }
+    if let Some(err) = child.stderr.take() { readers.push(attach_output(install_id, OutputStream::Stderr, err, step, parser)); }
+    let pid = child.id();
+    install_processes().lock().map_err(|_| "Lock poisoned")?.insert(install_id.to_string(), child);
+    loop {
+        let polled = {
+            let mut procs = install_processes().lock().map_err(|_| "Lock poisoned")?;
+            // Gone from the registry: `cancel_install` took and killed it
+            let Some(child) = procs.get_mut(install_id) else { return Err(CANCELLED.into()) };
+            match child.try_wait() {
+                Ok(None) if is_cancelled(install_id) => {
+                    // Cancelled between the check above and registration; like `cancel`, take
+                    // the child out of the registry and stop it once the lock is released
+                    let child = procs.remove(install_id);
+                    drop(procs);
+                    if let Some(mut child) = child {
+                        process_tree::terminate(&mut child, CANCEL_GRACE, false);
+                    }
+                    return Err(CANCELLED.into());
+                }
+                Ok(None) => None,
+                done => {
+                    procs.remove(install_id);
+                    Some(done.map_err(|e| e.to_string())?)
+                }
+            }
+        };
+        if let Some(Some(status)) = polled {
+            drain_readers(readers, pid);
+            return Ok(status);
+        }
+        std::thread::sleep(INSTALL_POLL_INTERVAL);
+    }
+}
+
+/// Let the readers flush the tail of the output before the step is reported done. A descendant
+/// that outlives npm/git keeps the pipes open, so after `READER_DRAIN_WAIT` the rest of the
+/// process group is killed; readers still blocked after that are left behind.
+fn drain_readers(readers: Vec<JoinHandle<()>>, pgid: u32) {
+    let wait = |readers: &[JoinHandle<()>]| {
+        let deadline = Instant::now() + READER_DRAIN_WAIT;
+        while readers.iter().any(|r| !r.is_finished()) && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    };
+    wait(&readers);
+    if readers.iter().any(|r| !r.is_finished()) {
+        log::warn!("Process group {pgid} still holds the installer output open; killing it");
+        // The group id stays reserved while any member lives, so this can't hit another process
+        #[cfg(unix)]
+        process_tree::terminate_pid(pgid, Duration::ZERO, true);
+        #[cfg(not(unix))]
+        let _ = pgid;
+        wait(&readers);
+    }
+    for reader in readers.into_iter().filter(|r| r.is_finished()) {
+        let _ = reader.join();
+    }
+}
+
+/// `run_tracked`, rolling back `created` if the step ends because of a cancellation
+fn run_step(install_id: &str, cmd: Command, step: Step, created: Option<&Path>) -> Result<ExitStatus, String> {
+    let result = run_tracked(install_id, cmd, step);
+    check_cancelled(install_id, created)?;
+    result
+}
 
 /// Helper function to persist metadata to disk
 fn persist_metadata(app: &AppHandle) {
@@ -141,15 +684,18 @@ fn do_install(app: AppHandle, install_id: String, config: InstallConfig) -> Resu
             // target dir under app data
             let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
             let target = if global.unwrap_or(false) { dir.clone() } else { dir.join("mcp_servers").join("npm").join(package_name.replace("/","-")) };
-            std::fs::create_dir_all(&target).map_err(|e| e.to_string())?;
+            let created = create_target(&target)?;
 
-            let mut args: Vec<String> = vec!["install".into(), if let Some(ref v)=version { format!("{}@{}", package_name, v) } else { package_name.clone() } ];
+            let mut args: Vec<String> = vec!["install".into(), if let Some(ref v)=version { format!("{}@{}", package_name, v) } else { package_name.clone() }, "--loglevel=http".into() ];
             if !global.unwrap_or(false) { args.push("--prefix".into()); args.push(target.to_string_lossy().to_string()); }
             if let Some(reg) = registry { args.push("--registry".into()); args.push(reg); }
 
-            let status = Command::new("npm").args(&args).stdout(Stdio::piped()).stderr(Stdio::piped()).status().map_err(|e| e.to_string())?;
+            let mut cmd = Command::new("npm");
+            cmd.args(&args);
+            let status = run_step(&install_id, cmd, Step { tool: Tool::Npm, from: 10, to: 80 }, created.as_deref())?;
             if !status.success() { update(&install_id, |p| { p.status=InstallationStatus::Failed; p.progress=0; p.message="Installation failed".into(); p.error=Some(format!("npm exited with status {:?}", status.code())); p.completed_at=Some(now_iso()); }); return Err("npm install failed".into()); }
 
+            check_cancelled(&install_id, created.as_deref())?;
             update(&install_id, |p| { p.status=InstallationStatus::Configuring; p.progress=80; p.message="Configuring server...".into(); p.current_step=Some("Configuring".into()); p.current_step_number=Some(2); });
 
             // Save installation metadata
@@ -165,6 +711,7 @@ fn do_install(app: AppHandle, install_id: String, config: InstallConfig) -> Resu
                 client_type: Some("mcp-hub".to_string()),
                 original_config: None,
                 config_source_path: None,
+                entrypoint: None,
             };
             if let Ok(mut meta) = install_metadata().lock() {
                 meta.insert(install_id.clone(), metadata);
@@ -180,19 +727,26 @@ fn do_install(app: AppHandle, install_id: String, config: InstallConfig) -> Resu
             update(&install_id, |p| { p.status=InstallationStatus::Downloading; p.progress=10; p.message=format!("Cloning {}...", repository); p.current_step=Some("Cloning".into()); p.total_steps=Some(4); p.current_step_number=Some(1); });
             let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
             let target = dir.join("mcp_servers").join("github").join(repository.split('/').next_back().unwrap_or("repo"));
-            std::fs::create_dir_all(&target).map_err(|e| e.to_string())?;
+            let created = create_target(&target)?;
 
-            let mut args = vec!["clone".to_string(), format!("https://github.com/{}.git", repository), target.to_string_lossy().to_string(), "--depth".into(), "1".into()];
+            let mut args = vec!["clone".to_string(), format!("https://github.com/{}.git", repository), target.to_string_lossy().to_string(), "--depth".into(), "1".into(), "--progress".into()];
             if let Some(ref b) = branch { args.push("--branch".into()); args.push(b.clone()); }
             if let Some(ref t) = tag { args.push("--branch".into()); args.push(t.clone()); }
 
-            let status = Command::new("git").args(&args).stdout(Stdio::piped()).stderr(Stdio::piped()).status().map_err(|e| e.to_string())?;
+            let mut cmd = Command::new("git");
+            cmd.args(&args);
+            let status = run_step(&install_id, cmd, Step { tool: Tool::Git, from: 10, to: 60 }, created.as_deref())?;
             if !status.success() { update(&install_id, |p| { p.status=InstallationStatus::Failed; p.progress=0; p.message="Clone failed".into(); p.error=Some(format!("git exited with status {:?}", status.code())); p.completed_at=Some(now_iso()); }); return Err("git clone failed".into()); }
 
             update(&install_id, |p| { p.status=InstallationStatus::Installing; p.progress=60; p.message="Installing dependencies...".into(); p.current_step=Some("Installing deps".into()); p.current_step_number=Some(3); });
             // Best-effort npm install if package.json exists
             let pkg = target.join("package.json");
-            if pkg.exists() { let _ = Command::new("npm").arg("install").current_dir(&target).status(); }
+            if pkg.exists() {
+                let mut cmd = Command::new("npm");
+                cmd.args(["install", "--loglevel=http"]).current_dir(&target);
+                let _ = run_step(&install_id, cmd, Step { tool: Tool::Npm, from: 60, to: 90 }, created.as_deref())?;
+            }
+            check_cancelled(&install_id, created.as_deref())?;
 
             // Save installation metadata
             let metadata = InstallMetadata {
@@ -207,6 +761,89 @@ fn do_install(app: AppHandle, install_id: String, config: InstallConfig) -> Resu
                 client_type: Some("mcp-hub".to_string()),
                 original_config: None,
                 config_source_path: None,
+                entrypoint: None,
+            };
+            if let Ok(mut meta) = install_metadata().lock() {
+                meta.insert(install_id.clone(), metadata);
+            }
+
+            // Persist metadata to disk
+            persist_metadata(&app);
+
+            update(&install_id, |p| { p.status=InstallationStatus::Completed; p.progress=100; p.message="Installation completed successfully".into(); p.current_step=Some("Completed".into()); p.current_step_number=Some(4); p.completed_at=Some(now_iso()); });
+            Ok(())
+        }
+        InstallConfig::PyPI { package_name, version, extras, index_url } => {
+            let errors = pypi_config_errors(&package_name, version.as_deref(), extras.as_deref(), index_url.as_deref());
+            if !errors.is_empty() {
+                let error = errors.join("; ");
+                update(&install_id, |p| { p.status=InstallationStatus::Failed; p.progress=0; p.message="Invalid PyPI configuration".into(); p.error=Some(error.clone()); p.completed_at=Some(now_iso()); });
+                return Err(error);
+            }
+            update(&install_id, |p| { p.status=InstallationStatus::Downloading; p.progress=10; p.message="Creating virtual environment...".into(); p.current_step=Some("Creating venv".into()); p.total_steps=Some(4); p.current_step_number=Some(1); });
+            let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+            let target = dir.join("mcp_servers").join("pypi").join(package_name.to_lowercase());
+            // The venv tool creates the directory itself
+            let created = (!target.exists()).then(|| target.clone());
+            std::fs::create_dir_all(dir.join("mcp_servers").join("pypi")).map_err(|e| e.to_string())?;
+            let venv_step = Step { tool: Tool::Pip, from: 10, to: 20 };
+            let install_step = Step { tool: Tool::Pip, from: 20, to: 85 };
+            let requirement = pypi_requirement(&package_name, version.as_deref(), extras.as_deref());
+
+            let (venv_cmd, mut install_cmd) = if uv_available() {
+                let mut venv = Command::new("uv");
+                venv.arg("venv").arg(&target).args(["--allow-existing", "--python", &format!(">={}.{}", MIN_PYTHON.0, MIN_PYTHON.1)]);
+                let mut install = Command::new("uv");
+                install.args(["pip", "install", "--python"]).arg(venv_python(&target));
+                (venv, install)
+            } else {
+                let (python, (major, minor)) = find_python().ok_or("Neither uv nor python is available on PATH")?;
+                if (major, minor) < MIN_PYTHON {
+                    let error = format!("Python {}.{} or newer is required, found {major}.{minor}", MIN_PYTHON.0, MIN_PYTHON.1);
+                    update(&install_id, |p| { p.status=InstallationStatus::Failed; p.progress=0; p.message="Python is too old".into(); p.error=Some(error.clone()); p.completed_at=Some(now_iso()); });
+                    return Err(error);
+                }
+                let mut venv = Command::new(python);
+                venv.args(["-m", "venv"]).arg(&target);
+                let mut install = Command::new(venv_python(&target));
+                install.args(["-m", "pip", "install", "--progress-bar", "off", "--disable-pip-version-check"]);
+                (venv, install)
+            };
+            let status = run_step(&install_id, venv_cmd, venv_step, created.as_deref())?;
+            if !status.success() {
+                if let Some(dir) = created.as_deref() { let _ = std::fs::remove_dir_all(dir); }
+                update(&install_id, |p| { p.status=InstallationStatus::Failed; p.progress=0; p.message="Failed to create virtual environment".into(); p.error=Some(format!("venv creation exited with status {:?}", status.code())); p.completed_at=Some(now_iso()); });
+                return Err("venv creation failed".into());
+            }
+
+            update(&install_id, |p| { p.status=InstallationStatus::Installing; p.message=format!("Installing {}...", requirement); p.current_step=Some("Installing".into()); p.current_step_number=Some(2); });
+            if let Some(url) = index_url { install_cmd.args(["--index-url", &url]); }
+            // After `--` so the requirement can never be read as an option
+            install_cmd.arg("--").arg(&requirement);
+            let status = run_step(&install_id, install_cmd, install_step, created.as_deref())?;
+            if !status.success() {
+                if let Some(dir) = created.as_deref() { let _ = std::fs::remove_dir_all(dir); }
+                update(&install_id, |p| { p.status=InstallationStatus::Failed; p.progress=0; p.message="Installation failed".into(); p.error=Some(format!("pip exited with status {:?}", status.code())); p.completed_at=Some(now_iso()); });
+                return Err("pip install failed".into());
+            }
+
+            check_cancelled(&install_id, created.as_deref())?;
+            update(&install_id, |p| { p.status=InstallationStatus::Configuring; p.progress=90; p.message="Configuring server...".into(); p.current_step=Some("Configuring".into()); p.current_step_number=Some(3); });
+
+            // Save installation metadata
+            let metadata = InstallMetadata {
+                server_id: install_id.clone(),
+                install_id: install_id.clone(),
+                source_type: "pypi".to_string(),
+                install_path: target.to_string_lossy().to_string(),
+                package_name: Some(package_name),
+                repository: None,
+                version,
+                installed_at: now_iso(),
+                client_type: Some("mcp-hub".to_string()),
+                original_config: None,
+                config_source_path: None,
+                entrypoint: None,
             };
             if let Ok(mut meta) = install_metadata().lock() {
                 meta.insert(install_id.clone(), metadata);
@@ -218,9 +855,117 @@ fn do_install(app: AppHandle, install_id: String, config: InstallConfig) -> Resu
             update(&install_id, |p| { p.status=InstallationStatus::Completed; p.progress=100; p.message="Installation completed successfully".into(); p.current_step=Some("Completed".into()); p.current_step_number=Some(4); p.completed_at=Some(now_iso()); });
             Ok(())
         }
+        InstallConfig::Release { url, version, name, sha256, checksums_url, entrypoint } => {
+            let url = release_archive::expand_url(&url, version.as_deref());
+            let name = release_name(name.as_deref(), &url);
+            if !is_dir_name(&name) {
+                update(&install_id, |p| { p.status=InstallationStatus::Failed; p.progress=0; p.message="Invalid release name".into(); p.error=Some(format!("{name:?} is not a valid directory name")); p.completed_at=Some(now_iso()); });
+                return Err("invalid release name".into());
+            }
+            update(&install_id, |p| { p.status=InstallationStatus::Downloading; p.progress=10; p.message=format!("Downloading {}...", release_archive::file_name(&url)); p.current_step=Some("Downloading".into()); p.total_steps=Some(4); p.current_step_number=Some(1); });
+            let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+            let releases = dir.join("mcp_servers").join("release");
+            let target = releases.join(&name);
+            // Unpack next to the target and swap it in only once verified, so a failed or
+            // cancelled reinstall leaves the working release untouched
+            let staging = releases.join(format!(".{name}.staging-{install_id}"));
+            std::fs::create_dir_all(&staging).map_err(|e| e.to_string())?;
+
+            let entry = match install_release(&install_id, &url, sha256.as_deref(), checksums_url.as_deref(), entrypoint.as_deref(), &name, &staging).and_then(|entry| replace_dir(&staging, &target, &entry)) {
+                Ok(entry) => entry,
+                Err(e) if e == CANCELLED => return Err(e),
+                Err(e) => {
+                    let _ = std::fs::remove_dir_all(&staging);
+                    update(&install_id, |p| { p.status=InstallationStatus::Failed; p.progress=0; p.message="Installation failed".into(); p.error=Some(e.clone()); p.completed_at=Some(now_iso()); });
+                    return Err(e);
+                }
+            };
+
+            update(&install_id, |p| { p.status=InstallationStatus::Configuring; p.progress=90; p.message="Configuring server...".into(); p.current_step=Some("Configuring".into()); p.current_step_number=Some(3); });
+
+            // Save installation metadata
+            let metadata = InstallMetadata {
+                server_id: install_id.clone(),
+                install_id: install_id.clone(),
+                source_type: "release".to_string(),
+                install_path: target.to_string_lossy().to_string(),
+                package_name: Some(name),
+                repository: Some(url),
+                version,
+                installed_at: now_iso(),
+                client_type: Some("mcp-hub".to_string()),
+                original_config: None,
+                config_source_path: None,
+                entrypoint: Some(entry.to_string_lossy().to_string()),
+            };
+            if let Ok(mut meta) = install_metadata().lock() {
+                meta.insert(install_id.clone(), metadata);
+            }
+
+            // Persist metadata to disk
+            persist_metadata(&app);
+
+            update(&install_id, |p| { p.status=InstallationStatus::Completed; p.progress=100; p.message="Installation completed successfully".into(); p.current_step=Some("Completed".into()); p.current_step_number=Some(4); p.completed_at=Some(now_iso()); });
+            Ok(())
+        }
+        InstallConfig::Cargo { crate_name, version, features, git, rev } => {
+            let errors = cargo_config_errors(&crate_name, version.as_deref(), features.as_deref(), git.as_deref(), rev.as_deref());
+            if !errors.is_empty() {
+                let error = errors.join("; ");
+                update(&install_id, |p| { p.status=InstallationStatus::Failed; p.progress=0; p.message="Invalid cargo configuration".into(); p.error=Some(error.clone()); p.completed_at=Some(now_iso()); });
+                return Err(error);
+            }
+            update(&install_id, |p| { p.status=InstallationStatus::Installing; p.progress=5; p.message=format!("Building {}...", crate_name); p.current_step=Some("Building".into()); p.total_steps=Some(3); p.current_step_number=Some(1); });
+            let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+            let target = dir.join("mcp_servers").join("cargo").join(&crate_name);
+            let created = create_target(&target)?;
+
+            let cmd = cargo_install_command(&crate_name, version.as_deref(), features.as_deref(), git.as_deref(), rev.as_deref(), &target);
+            let status = run_step(&install_id, cmd, Step { tool: Tool::Cargo, from: 5, to: 85 }, created.as_deref())?;
+            if !status.success() {
+                if let Some(dir) = created.as_deref() { let _ = std::fs::remove_dir_all(dir); }
+                update(&install_id, |p| { p.status=InstallationStatus::Failed; p.progress=0; p.message="Installation failed".into(); p.error=Some(format!("cargo exited with status {:?}", status.code())); p.completed_at=Some(now_iso()); });
+                return Err("cargo install failed".into());
+            }
+
+            check_cancelled(&install_id, created.as_deref())?;
+            update(&install_id, |p| { p.status=InstallationStatus::Configuring; p.progress=90; p.message="Configuring server...".into(); p.current_step=Some("Configuring".into()); p.current_step_number=Some(2); });
+
+            // The binary named after the crate, or the only one it installed
+            let entry = release_archive::find_entrypoint(&target.join("bin"), None, &crate_name)
+                .inspect_err(|e| log::warn!("Installation {}: {}", install_id, e))
+                .ok();
+            if let Some(entry) = &entry { push_log(&install_id, OutputStream::Stdout, format!("Entrypoint: {}", entry.display())); }
+
+            // Save installation metadata
+            let metadata = InstallMetadata {
+                server_id: install_id.clone(),
+                install_id: install_id.clone(),
+                source_type: "cargo".to_string(),
+                install_path: target.to_string_lossy().to_string(),
+                package_name: Some(crate_name),
+                repository: git,
+                version: version.or(rev),
+                installed_at: now_iso(),
+                client_type: Some("mcp-hub".to_string()),
+                original_config: None,
+                config_source_path: None,
+                entrypoint: entry.map(|e| e.to_string_lossy().to_string()),
+            };
+            if let Ok(mut meta) = install_metadata().lock() {
+                meta.insert(install_id.clone(), metadata);
+            }
+
+            // Persist metadata to disk
+            persist_metadata(&app);
+
+            update(&install_id, |p| { p.status=InstallationStatus::Completed; p.progress=100; p.message="Installation completed successfully".into(); p.current_step=Some("Completed".into()); p.current_step_number=Some(3); p.completed_at=Some(now_iso()); });
+            Ok(())
+        }
         InstallConfig::Local { path, .. } => {
             let pb = PathBuf::from(&path);
             if !pb.exists() || !pb.is_dir() { update(&install_id, |p| { p.status=InstallationStatus::Failed; p.progress=0; p.message="Invalid local path".into(); p.error=Some("Path must exist and be directory".into()); p.completed_at=Some(now_iso()); }); return Err("invalid path".into()); }
+            check_cancelled(&install_id, None)?;
             update(&install_id, |p| { p.status=InstallationStatus::Configuring; p.progress=50; p.message="Configuring local server...".into(); p.current_step=Some("Configuring".into()); p.total_steps=Some(2); p.current_step_number=Some(1); });
 
             // Save installation metadata
@@ -236,6 +981,7 @@ fn do_install(app: AppHandle, install_id: String, config: InstallConfig) -> Resu
                 client_type: Some("mcp-hub".to_string()),
                 original_config: None,
                 config_source_path: None,
+                entrypoint: None,
             };
             if let Ok(mut meta) = install_metadata().lock() {
                 meta.insert(install_id.clone(), metadata);
@@ -255,9 +1001,42 @@ pub fn get_install_progress(install_id: String) -> Result<InstallationProgress,
     installs().lock().map_err(|_|"Lock poisoned")?.get(&install_id).cloned().ok_or_else(||"Installation not found".into())
 }
 
+/// Cancel a running installation: kill its npm/git process tree, then wait for the
+/// partially created target directory to be rolled back. Finished installations are left alone.
 #[tauri::command]
-pub fn cancel_install(install_id: String) -> Result<(), String> {
-    update(&install_id, |p| { p.status=InstallationStatus::Cancelled; p.message="Installation cancelled".into(); p.completed_at=Some(now_iso()); });
+pub async fn cancel_install(install_id: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || cancel(&install_id))
+        .await
+        .map_err(|e| format!("Cancel task failed: {e}"))?
+}
+
+/// Blocking part of `cancel_install`; waits up to the kill grace period plus `ROLLBACK_WAIT`
+fn cancel(install_id: &str) -> Result<(), String> {
+    {
+        let mut map = installs().lock().map_err(|_| "Lock poisoned")?;
+        let p = map.get_mut(install_id).ok_or("Installation not found")?;
+        if matches!(p.status, InstallationStatus::Completed | InstallationStatus::Failed | InstallationStatus::Cancelled) {
+            return Ok(());
+        }
+        p.status = InstallationStatus::Cancelled;
+        p.message = "Cancelling installation".into();
+    }
+
+    let child = install_processes().lock().map_err(|_| "Lock poisoned")?.remove(install_id);
+    if let Some(mut child) = child {
+        let outcome = process_tree::terminate(&mut child, CANCEL_GRACE, false);
+        log::info!("Installation {}: stopped installer process ({:?})", install_id, outcome.signal);
+    }
+
+    // The installation thread rolls back and marks completion once it notices
+    let started = Instant::now();
+    while started.elapsed() < ROLLBACK_WAIT {
+        let finished = installs().lock().ok().and_then(|map| map.get(install_id).map(|p| p.completed_at.is_some())).unwrap_or(true);
+        if finished {
+            break;
+        }
+        std::thread::sleep(INSTALL_POLL_INTERVAL);
+    }
     Ok(())
 }
 
@@ -302,7 +1081,7 @@ pub fn uninstall_server(
         if let Some(sid) = server_id.as_ref() {
             log::info!("Stopping server {} before uninstall", sid);
             // Try to stop, but don't fail uninstall if stop fails
-            let _ = crate::mcp_lifecycle::mcp_stop_server(sid.clone(), Some(false));
+            let _ = crate::mcp_lifecycle::stop_server(sid, false, None);
         }
     }
 
@@ -355,6 +1134,47 @@ pub fn uninstall_server(
             }
         }
 
+        "pypi" => {
+            log::info!("Uninstalling Python package venv at: {}", metadata.install_path);
+
+            // The package lives alone in its venv, so removing the venv uninstalls it
+            let path = PathBuf::from(&metadata.install_path);
+            if path.exists() {
+                std::fs::remove_dir_all(&path)
+                    .map_err(|e| format!("Failed to delete Python virtual environment: {}", e))?;
+                log::info!("Deleted Python virtual environment: {}", metadata.install_path);
+            } else {
+                log::warn!("Python virtual environment does not exist: {}", metadata.install_path);
+            }
+        }
+
+        "release" => {
+            log::info!("Uninstalling release binaries at: {}", metadata.install_path);
+
+            let path = PathBuf::from(&metadata.install_path);
+            if path.exists() {
+                std::fs::remove_dir_all(&path)
+                    .map_err(|e| format!("Failed to delete release installation directory: {}", e))?;
+                log::info!("Deleted release installation directory: {}", metadata.install_path);
+            } else {
+                log::warn!("Release installation directory does not exist: {}", metadata.install_path);
+            }
+        }
+
+        "cargo" => {
+            log::info!("Uninstalling cargo install root at: {}", metadata.install_path);
+
+            // Everything `cargo install --root` wrote lives under the root
+            let path = PathBuf::from(&metadata.install_path);
+            if path.exists() {
+                std::fs::remove_dir_all(&path)
+                    .map_err(|e| format!("Failed to delete cargo installation directory: {}", e))?;
+                log::info!("Deleted cargo installation directory: {}", metadata.install_path);
+            } else {
+                log::warn!("Cargo installation directory does not exist: {}", metadata.install_path);
+            }
+        }
+
         "local" => {
             log::info!("Removing local server reference (not deleting user files): {}", metadata.install_path);
             // For local installations, we don't delete the actual files
@@ -409,6 +1229,7 @@ mod tests {
             client_type: Some("mcp-hub".to_string()),
             original_config: None,
             config_source_path: None,
+            entrypoint: None,
         };
 
         // Serialize to JSON
@@ -443,6 +1264,7 @@ mod tests {
             client_type: Some("mcp-hub".to_string()),
             original_config: None,
             config_source_path: None,
+            entrypoint: None,
         };
 
         let json = serde_json::to_string(&metadata).unwrap();
@@ -468,6 +1290,7 @@ mod tests {
             client_type: Some("mcp-hub".to_string()),
             original_config: None,
             config_source_path: None,
+            entrypoint: None,
         };
 
         let json = serde_json::to_string(&metadata).unwrap();
@@ -495,6 +1318,7 @@ mod tests {
             client_type: Some("mcp-hub".to_string()),
             original_config: None,
             config_source_path: None,
+            entrypoint: None,
         };
 
         // Store metadata
@@ -860,6 +1684,7 @@ mod tests {
             client_type: Some("mcp-hub".to_string()),
             original_config: None,
             config_source_path: None,
+            entrypoint: None,
         };
 
         let metadata2 = InstallMetadata {
@@ -874,6 +1699,7 @@ mod tests {
             client_type: Some("mcp-hub".to_string()),
             original_config: None,
             config_source_path: None,
+            entrypoint: None,
         };
 
         let metadata_vec = vec![metadata1, metadata2];
@@ -888,4 +1714,310 @@ mod tests {
         assert_eq!(deserialized[0].source_type, "npm");
         assert_eq!(deserialized[1].source_type, "github");
     }
+
+    /// Test cancelling kills the installer's whole process tree and rolls back the target dir
+    #[cfg(unix)]
+    #[test]
+    fn test_cancel_install_kills_tree_and_rolls_back() {
+        let install_id = format!("cancel-test-{}", nanoid::nanoid!(8));
+        let target = std::env::temp_dir().join(&install_id);
+        installs().lock().unwrap().insert(install_id.clone(), InstallationProgress {
+            install_id: install_id.clone(),
+            status: InstallationStatus::Installing,
+            progress: 10,
+            message: "Installing".into(),
+            current_step: None,
+            total_steps: None,
+            current_step_number: None,
+            started_at: now_iso(),
+            completed_at: None,
+            error: None,
+            logs: None,
+        });
+
+        let created = create_target(&target).unwrap();
+        assert_eq!(created.as_deref(), Some(target.as_path()));
+        let pidfile = target.join("pid");
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", &format!("sleep 30 & echo $! > '{}'; wait", pidfile.display())]);
+        let worker = {
+            let install_id = install_id.clone();
+            std::thread::spawn(move || run_step(&install_id, cmd, Step { tool: Tool::Git, from: 10, to: 60 }, created.as_deref()))
+        };
+
+        let started = Instant::now();
+        while !pidfile.exists() || std::fs::read_to_string(&pidfile).unwrap().trim().is_empty() {
+            assert!(started.elapsed() < Duration::from_secs(5), "installer step never started");
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        let grandchild: u32 = std::fs::read_to_string(&pidfile).unwrap().trim().parse().unwrap();
+
+        cancel(&install_id).unwrap();
+        assert_eq!(worker.join().unwrap().unwrap_err(), CANCELLED);
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(!target.exists());
+
+        let stat = std::fs::read_to_string(format!("/proc/{grandchild}/stat")).unwrap_or_default();
+        assert!(stat.is_empty() || stat.rsplit_once(") ").is_some_and(|(_, rest)| rest.starts_with('Z')));
+
+        let progress = installs().lock().unwrap().get(&install_id).cloned().unwrap();
+        assert!(matches!(progress.status, InstallationStatus::Cancelled));
+        assert!(progress.completed_at.is_some());
+
+        // Cancelling again is a no-op, unknown ids are an error
+        cancel(&install_id).unwrap();
+        assert!(cancel("missing").is_err());
+    }
+
+    /// Test git and npm output are turned into monotonic step progress
+    #[test]
+    fn test_progress_parser() {
+        let mut git = ProgressParser::new(Tool::Git);
+        assert_eq!(git.parse("Cloning into 'repo'..."), None);
+        assert_eq!(git.parse("remote: Counting objects: 100% (12/12), done."), None);
+        assert_eq!(git.parse("Receiving objects:  50% (6/12), 1.00 KiB | 1.00 MiB/s").map(|p| p.0), Some(35));
+        assert_eq!(git.parse("Resolving deltas: 100% (3/3), done.").map(|p| p.0), Some(90));
+        assert_eq!(git.parse("Updating files:  10% (1/10)").map(|p| p.0), Some(91));
+
+        let mut npm = ProgressParser::new(Tool::Npm);
+        assert_eq!(npm.parse("npm notice New minor version of npm available"), None);
+        let first = npm.parse("npm http fetch GET 200 https://registry.npmjs.org/express 50ms (cache miss)").unwrap();
+        assert_eq!(first.1, "Fetched 1 package");
+        let second = npm.parse("npm http fetch GET 200 https://registry.npmjs.org/qs 12ms (cache miss)").unwrap();
+        assert!(second.0 >= first.0 && second.0 < 90);
+        assert_eq!(npm.parse("added 64 packages in 2s").map(|p| p.0), Some(100));
+    }
+
+    /// Test output without line breaks is split into bounded segments
+    #[test]
+    fn test_drain_output_bounds_segments() {
+        let data = format!("{}\rdone\n", "x".repeat(MAX_SEGMENT_BYTES * 2 + 10));
+        let mut segments = Vec::new();
+        drain_output(data.as_bytes(), |segment, complete| segments.push((segment.len(), complete)));
+        assert_eq!(segments, vec![(MAX_SEGMENT_BYTES, true), (MAX_SEGMENT_BYTES, true), (10, false), (4, true)]);
+    }
+
+    /// Test a step's stdout/stderr are streamed into the logs, with `\r` redraws only driving progress
+    #[cfg(unix)]
+    #[test]
+    fn test_run_tracked_streams_output() {
+        let install_id = format!("stream-test-{}", nanoid::nanoid!(8));
+        installs().lock().unwrap().insert(install_id.clone(), InstallationProgress {
+            install_id: install_id.clone(),
+            status: InstallationStatus::Downloading,
+            progress: 10,
+            message: "Cloning".into(),
+            current_step: None,
+            total_steps: None,
+            current_step_number: None,
+            started_at: now_iso(),
+            completed_at: None,
+            error: None,
+            logs: Some(vec![]),
+        });
+
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "echo \"Cloning into 'repo'...\"; printf 'Receiving objects:  40%% (4/10)\\rReceiving objects: 100%% (10/10), done.\\n' >&2; exit 3"]);
+        let status = run_tracked(&install_id, cmd, Step { tool: Tool::Git, from: 10, to: 60 }).unwrap();
+        assert_eq!(status.code(), Some(3));
+
+        let progress = installs().lock().unwrap().remove(&install_id).unwrap();
+        let logs = progress.logs.unwrap();
+        assert!(logs.contains(&"Cloning into 'repo'...".to_string()));
+        assert!(logs.contains(&"Receiving objects: 100% (10/10), done.".to_string()));
+        assert!(!logs.iter().any(|l| l.contains("40%")));
+        assert_eq!(progress.progress, 45);
+        assert!(progress.message.starts_with("Receiving objects: 100%"));
+    }
+
+    /// Test a descendant holding the output pipes open can't hang a step
+    #[cfg(unix)]
+    #[test]
+    fn test_run_tracked_kills_lingering_descendants() {
+        let install_id = format!("linger-test-{}", nanoid::nanoid!(8));
+        installs().lock().unwrap().insert(install_id.clone(), InstallationProgress {
+            install_id: install_id.clone(),
+            status: InstallationStatus::Downloading,
+            progress: 10,
+            message: String::new(),
+            current_step: None,
+            total_steps: None,
+            current_step_number: None,
+            started_at: now_iso(),
+            completed_at: None,
+            error: None,
+            logs: Some(vec![]),
+        });
+
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "sleep 30 & echo started"]);
+        let started = Instant::now();
+        let status = run_tracked(&install_id, cmd, Step { tool: Tool::Npm, from: 10, to: 60 }).unwrap();
+        assert!(status.success());
+        assert!(started.elapsed() < READER_DRAIN_WAIT * 3);
+
+        let logs = installs().lock().unwrap().remove(&install_id).unwrap().logs.unwrap();
+        assert!(logs.contains(&"started".to_string()));
+    }
+
+    /// Test PyPI config deserialization and requirement building
+    #[test]
+    fn test_pypi_config_and_requirement() {
+        let config: InstallConfig = serde_json::from_str(r#"{"source":"pypi","package_name":"mcp-server-fetch","version":"1.2.0","extras":["http"],"index_url":null}"#).unwrap();
+        let InstallConfig::PyPI { package_name, version, extras, index_url } = config else { panic!("expected PyPI config") };
+        assert_eq!(pypi_requirement(&package_name, version.as_deref(), extras.as_deref()), "mcp-server-fetch[http]==1.2.0");
+        assert!(index_url.is_none());
+
+        assert_eq!(pypi_requirement("mcp-server-git", Some(">=0.6"), None), "mcp-server-git>=0.6");
+        assert_eq!(pypi_requirement("mcp-server-git", None, Some(&[])), "mcp-server-git");
+
+        assert_eq!(parse_python_version("Python 3.11.4\n"), Some((3, 11)));
+        assert_eq!(parse_python_version("Python 3.13.0rc1"), Some((3, 13)));
+        assert_eq!(parse_python_version("not python"), None);
+    }
+
+    /// Test validate_install rejects malformed PyPI names, extras and index URLs
+    #[test]
+    fn test_validate_install_pypi_invalid() {
+        let config = InstallConfig::PyPI {
+            package_name: "bad name!".to_string(),
+            version: None,
+            extras: Some(vec!["ok".to_string(), "not ok".to_string()]),
+            index_url: Some("ftp://example.com/simple".to_string()),
+        };
+        let res = validate_install(config).unwrap();
+        assert!(!res.valid);
+        assert!(res.errors.iter().any(|e| e.contains("package name")));
+        for name in ["..", "-r/etc/passwd", "--index-url=http://evil", "a/b"] {
+            assert!(!pypi_config_errors(name, None, None, None).is_empty(), "{name}");
+        }
+        assert!(!pypi_config_errors("ok", Some("1.0 @ file:///x"), None, None).is_empty());
+        assert!(pypi_config_errors("mcp-server-fetch", Some(">=1.0, <2"), Some(&["http".into()]), Some("https://pypi.org/simple")).is_empty());
+        assert!(res.errors.iter().any(|e| e.contains("extra")));
+        assert!(res.errors.iter().any(|e| e.contains("Index URL")));
+        assert!(res.dependencies.iter().any(|d| d.name.starts_with("python")));
+    }
+
+    /// Serve fixed bodies by path over HTTP on localhost, returning the base URL
+    fn fixture_server(files: HashMap<String, Vec<u8>>) -> String {
+        use std::io::{BufRead, BufReader, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                let _ = reader.read_line(&mut request_line);
+                let mut line = String::new();
+                while reader.read_line(&mut line).is_ok_and(|n| n > 2) { line.clear(); }
+                let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+                match files.get(path) {
+                    Some(body) => {
+                        let _ = write!(stream, "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", body.len());
+                        let _ = stream.write_all(body);
+                    }
+                    None => { let _ = write!(stream, "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"); }
+                }
+            }
+        });
+        base
+    }
+
+    /// Test a release archive is downloaded, verified against a checksums file and unpacked
+    /// with an executable entrypoint, and that a checksum mismatch is rejected
+    #[test]
+    fn test_install_release_from_fixture() {
+        use sha2::{Digest, Sha256};
+
+        let triple = release_archive::target_triple();
+        let file = format!("my-server-1.2.0-{triple}.tar.gz");
+        let archive = {
+            let mut tar = tar::Builder::new(flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default()));
+            let script = b"#!/bin/sh\necho ok\n";
+            let mut header = tar::Header::new_gnu();
+            header.set_size(script.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            tar.append_data(&mut header, "my-server-1.2.0/my-server", &script[..]).unwrap();
+            tar.into_inner().unwrap().finish().unwrap()
+        };
+        let digest: String = Sha256::digest(&archive).iter().map(|b| format!("{b:02x}")).collect();
+        let base = fixture_server(HashMap::from([
+            (format!("/v1.2.0/{file}"), archive),
+            ("/v1.2.0/SHA256SUMS".to_string(), format!("{digest}  {file}\n").into_bytes()),
+        ]));
+        let url = release_archive::expand_url(&format!("{base}/v{{version}}/my-server-{{version}}-{{target}}.tar.gz"), Some("1.2.0"));
+        assert_eq!(release_name(None, &url), "my-server");
+
+        let tmp = tempfile::tempdir().unwrap();
+        let target = tmp.path().join("my-server");
+        std::fs::create_dir_all(&target).unwrap();
+        let checksums = format!("{base}/v1.2.0/SHA256SUMS");
+        let entry = install_release("release-test", &url, None, Some(&checksums), None, "my-server", &target).unwrap();
+        assert_eq!(entry, target.join("my-server-1.2.0").join("my-server"));
+        assert!(!target.join(format!(".download-{file}")).exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(entry.metadata().unwrap().permissions().mode() & 0o111, 0o111);
+        }
+
+        // A verified release replaces the previous one; names must be single directories
+        let previous = tmp.path().join("installed");
+        std::fs::create_dir_all(&previous).unwrap();
+        std::fs::write(previous.join("stale"), "old").unwrap();
+        let moved = replace_dir(&target, &previous, &entry).unwrap();
+        assert_eq!(moved, previous.join("my-server-1.2.0").join("my-server"));
+        assert!(moved.is_file() && !previous.join("stale").exists() && !target.exists());
+        for bad in ["..", ".", "../..", "a/b", "/abs", ""] {
+            assert!(!is_dir_name(bad), "{bad}");
+        }
+        assert!(is_dir_name("my-server") && is_dir_name("my.server"));
+
+        let other = tmp.path().join("bad");
+        std::fs::create_dir_all(&other).unwrap();
+        let err = install_release("release-test", &url, Some(&"0".repeat(64)), None, None, "my-server", &other).unwrap_err();
+        assert!(err.contains("Checksum mismatch"));
+        assert!(std::fs::read_dir(&other).unwrap().next().is_none());
+    }
+
+    /// Test the cargo install command line and progress read from cargo's output
+    #[test]
+    fn test_cargo_install_command_and_progress() {
+        let root = PathBuf::from("/data/mcp_servers/cargo/hello-mcp");
+        let args = |cmd: Command| cmd.get_args().map(|a| a.to_string_lossy().into_owned()).collect::<Vec<_>>().join(" ");
+        let features = vec!["http".to_string(), "tls/rustls".to_string()];
+        assert_eq!(
+            args(cargo_install_command("hello-mcp", Some("1.0.0"), Some(&features), None, None, &root)),
+            "install --root /data/mcp_servers/cargo/hello-mcp --force --color never --version=1.0.0 --features=http,tls/rustls -- hello-mcp"
+        );
+        assert_eq!(
+            args(cargo_install_command("hello-mcp", None, None, Some("https://github.com/o/r"), Some("abc123"), &root)),
+            "install --root /data/mcp_servers/cargo/hello-mcp --force --color never --git=https://github.com/o/r --rev=abc123 -- hello-mcp"
+        );
+
+        let mut cargo = ProgressParser::new(Tool::Cargo);
+        assert_eq!(cargo.parse("    Updating git repository `https://github.com/o/r`").map(|p| p.0), Some(5));
+        assert_eq!(cargo.parse("  Installing hello-mcp v0.1.0 (https://github.com/o/r?rev=abc123#abc123)"), None);
+        let first = cargo.parse("   Compiling serde v1.0.0").unwrap().0;
+        let second = cargo.parse("   Compiling hello-mcp v0.1.0").unwrap().0;
+        assert!(first > 5 && second > first && second < 85);
+        assert_eq!(cargo.parse("    Finished `release` profile [optimized] target(s) in 0.32s").map(|p| p.0), Some(90));
+        #[cfg(unix)]
+        assert_eq!(cargo.parse("  Installing /data/mcp_servers/cargo/hello-mcp/bin/hello-mcp").map(|p| p.0), Some(95));
+        assert_eq!(cargo.parse("   Installed package `hello-mcp v0.1.0` (executable `hello-mcp`)").map(|p| p.0), Some(100));
+        assert_eq!(cargo.parse("warning: be sure to add `/data/bin` to your PATH"), None);
+
+        let res = validate_install(InstallConfig::Cargo { crate_name: "hello mcp".into(), version: Some("1.0".into()), features: Some(vec!["bad feature".into()]), git: Some("https://github.com/o/r".into()), rev: None }).unwrap();
+        assert!(!res.valid);
+        assert!(res.errors.iter().any(|e| e.contains("crate name")));
+        assert!(res.errors.iter().any(|e| e.contains("feature")));
+        assert!(res.errors.iter().any(|e| e.contains("rev instead")));
+        assert!(res.dependencies.iter().any(|d| d.name.starts_with("cargo")));
+        for name in ["..", "-Zunstable-options", "--git=https://evil", "a/b"] {
+            assert!(!cargo_config_errors(name, None, None, None, None).is_empty(), "{name}");
+        }
+        assert!(!cargo_config_errors("ok", None, None, Some("https://github.com/o/r"), Some("--upload-pack=x")).is_empty());
+        assert!(cargo_config_errors("hello-mcp", Some("^1.2"), Some(&features), None, None).is_empty());
+    }
 }
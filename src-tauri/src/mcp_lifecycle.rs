@@ -1,9 +1,27 @@
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, process::{Child, Command, Stdio}, sync::{Mutex, OnceLock}, time::{Duration, SystemTime}};
+use serde_json::Value;
+use std::{collections::{HashMap, HashSet}, path::PathBuf, process::{Child, Command, ExitStatus, Stdio}, sync::{Arc, Condvar, Mutex, OnceLock}, time::{Duration, Instant, SystemTime}};
+use tauri::{AppHandle, Emitter, Manager};
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+use crate::mcp_limits::{self, ResourceLimits};
+use crate::mcp_metrics;
+use crate::mcp_output::{self, OutputStream};
+use crate::mcp_remote;
+use crate::mcp_rpc::{self, RpcChannel};
+use crate::mcp_sandbox::{self, SandboxPolicy};
+use crate::process_registry::{self, OrphanPolicy, RegistryRecord};
+use crate::process_tree::{self, StopOutcome};
+use crate::{secure_storage, storage};
+
+/// Number of trailing output lines reported in `MCPServerProcess.output`
+const STATUS_OUTPUT_LINES: usize = 20;
+/// Number of trailing stderr lines kept in the run history when a server crashes
+const CRASH_STDERR_LINES: usize = 50;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum LifecycleState {
+    #[default]
     Stopped,
     Starting,
     Running,
@@ -12,7 +30,7 @@ pub enum LifecycleState {
     Error,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MCPServerProcess {
     pub server_id: String,
     pub pid: Option<u32>,
@@ -25,16 +43,197 @@ pub struct MCPServerProcess {
     pub cpu_usage: Option<f32>,
     pub uptime: Option<u64>,
     pub output: Option<String>,
+    /// MCP-level readiness and liveness, filled once the handshake has been attempted
+    #[serde(default)]
+    pub health: Option<HealthStatus>,
+    /// Survivor of a previous session: monitored only, no stdio and no restarts
+    #[serde(default)]
+    pub adopted: bool,
+    /// Running under its Landlock/seccomp sandbox policy
+    #[serde(default)]
+    pub sandboxed: bool,
+    /// Hash of the launch config (see `process_registry::config_hash`)
+    #[serde(default)]
+    pub config_hash: Option<String>,
+    /// Whether servers.json now holds a different config for this id; `None` if it has none
+    #[serde(default)]
+    pub config_drift: Option<bool>,
+    /// Endpoint of a monitored remote (SSE / Streamable HTTP) server; `None` for local processes
+    #[serde(default)]
+    pub remote_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct HealthStatus {
+    /// The `initialize` handshake completed
+    pub ready: bool,
+    pub protocol_version: Option<String>,
+    pub server_name: Option<String>,
+    pub server_version: Option<String>,
+    pub capabilities: Option<Value>,
+    pub handshake_latency_ms: Option<u64>,
+    pub last_ping_at: Option<String>,
+    pub last_ping_latency_ms: Option<u64>,
+    pub consecutive_ping_failures: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl HealthStatus {
+    /// Readiness as reported by a successful `initialize` result
+    pub(crate) fn from_initialize(result: &Value, latency_ms: u64) -> Self {
+        Self {
+            ready: true,
+            protocol_version: result.get("protocolVersion").and_then(Value::as_str).map(str::to_string),
+            server_name: result.pointer("/serverInfo/name").and_then(Value::as_str).map(str::to_string),
+            server_version: result.pointer("/serverInfo/version").and_then(Value::as_str).map(str::to_string),
+            capabilities: result.get("capabilities").cloned(),
+            handshake_latency_ms: Some(latency_ms),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct StdioConfig {
     pub command: String,
     #[serde(default)]
     pub args: Vec<String>,
+    /// Values may reference stored secrets as `${keyring:<key>}`; resolved only when spawning
     #[serde(default)]
     pub env: HashMap<String, String>,
     pub cwd: Option<String>,
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    #[serde(default)]
+    pub health: HealthCheckConfig,
+    /// Time between SIGTERM and SIGKILL when stopping (defaults to 5s)
+    #[serde(default)]
+    pub stop_grace_ms: Option<u64>,
+    #[serde(default)]
+    pub limits: ResourceLimits,
+    /// Filesystem and network restrictions for untrusted servers (Linux only)
+    #[serde(default)]
+    pub sandbox: Option<SandboxPolicy>,
+}
+
+/// One finished run of a managed server, persisted by `storage` for post-mortem diagnosis
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub server_id: String,
+    pub started_at: Option<String>,
+    pub stopped_at: Option<String>,
+    pub uptime_secs: Option<u64>,
+    pub exit_code: Option<i32>,
+    pub signal: Option<String>,
+    /// Why the run was started: an explicit start, or a restart and what it recovered from
+    pub start_cause: String,
+    /// Why the run ended
+    pub end_reason: String,
+    /// Ended other than by a clean exit or an explicit stop
+    pub crashed: bool,
+    pub restart_count: u32,
+    /// Last stderr lines at the time of a crash
+    #[serde(default)]
+    pub stderr_tail: Vec<String>,
+}
+
+/// MCP-level readiness and liveness checks performed over the server's stdio
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HealthCheckConfig {
+    /// Perform the `initialize` handshake before reporting the server as running
+    #[serde(default = "default_true")]
+    pub handshake: bool,
+    /// Time allowed for the handshake before the server is considered failed
+    #[serde(default = "default_startup_timeout_ms")]
+    pub startup_timeout_ms: u64,
+    /// Interval between `ping` requests once ready; 0 disables pinging
+    #[serde(default = "default_ping_interval_ms")]
+    pub ping_interval_ms: u64,
+    #[serde(default = "default_ping_timeout_ms")]
+    pub ping_timeout_ms: u64,
+    /// Consecutive failed pings after which the server is treated as hung and killed
+    #[serde(default = "default_max_ping_failures")]
+    pub max_ping_failures: u32,
+}
+
+fn default_true() -> bool { true }
+fn default_startup_timeout_ms() -> u64 { 30_000 }
+fn default_ping_interval_ms() -> u64 { 30_000 }
+fn default_ping_timeout_ms() -> u64 { 10_000 }
+fn default_max_ping_failures() -> u32 { 3 }
+
+impl Default for HealthCheckConfig {
+    fn default() -> Self {
+        Self {
+            handshake: true,
+            startup_timeout_ms: default_startup_timeout_ms(),
+            ping_interval_ms: default_ping_interval_ms(),
+            ping_timeout_ms: default_ping_timeout_ms(),
+            max_ping_failures: default_max_ping_failures(),
+        }
+    }
+}
+
+/// When the supervisor should bring a server back after it exits
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum RestartMode {
+    #[default]
+    Never,
+    OnFailure,
+    Always,
+}
+
+/// Automatic restart policy with exponential backoff
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RestartPolicy {
+    #[serde(default)]
+    pub mode: RestartMode,
+    /// Consecutive restarts attempted before giving up
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// Delay before the first restart; doubled for each consecutive failure
+    #[serde(default = "default_backoff_base_ms")]
+    pub backoff_base_ms: u64,
+    /// Upper bound for the backoff delay
+    #[serde(default = "default_backoff_max_ms")]
+    pub backoff_max_ms: u64,
+    /// A server that stays up this long has its consecutive failure count reset
+    #[serde(default = "default_reset_after_secs")]
+    pub reset_after_secs: u64,
+}
+
+fn default_max_retries() -> u32 { 5 }
+fn default_backoff_base_ms() -> u64 { 1000 }
+fn default_backoff_max_ms() -> u64 { 60_000 }
+fn default_reset_after_secs() -> u64 { 300 }
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            mode: RestartMode::Never,
+            max_retries: default_max_retries(),
+            backoff_base_ms: default_backoff_base_ms(),
+            backoff_max_ms: default_backoff_max_ms(),
+            reset_after_secs: default_reset_after_secs(),
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// Whether a server that exited (cleanly or not) should be restarted
+    pub fn wants_restart(&self, success: bool) -> bool {
+        match self.mode {
+            RestartMode::Never => false,
+            RestartMode::OnFailure => !success,
+            RestartMode::Always => true,
+        }
+    }
+
+    /// Backoff before restart number `attempt` (0-based): base * 2^attempt, capped
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1u64.checked_shl(attempt.min(32)).unwrap_or(u64::MAX);
+        Duration::from_millis(self.backoff_base_ms.saturating_mul(factor).min(self.backoff_max_ms))
+    }
 }
 
 #[derive(Debug)]
@@ -42,15 +241,104 @@ struct ProcEntry {
     child: Child,
     state: MCPServerProcess,
     started: SystemTime,
+    /// Config the server was launched with, reused by id-only restarts
+    cfg: StdioConfig,
+    rpc: Arc<RpcChannel>,
+    /// Consecutive failed runs, reset once the server stays up for `reset_after_secs`
+    failures: u32,
+    next_restart: Option<Instant>,
+    /// Why the hub killed the child, reported instead of the raw exit status
+    exit_reason: Option<String>,
+    /// Why the current run was started, for the run history
+    start_cause: String,
+    /// Kernel start time of the child, read once at spawn for the process registry
+    start_ticks: Option<u64>,
+}
+
+/// A server left running by a previous session and reattached from the process registry
+#[derive(Debug)]
+struct AdoptedEntry {
+    state: MCPServerProcess,
+    started: SystemTime,
+    record: RegistryRecord,
 }
 
 static PROCESSES: OnceLock<Mutex<HashMap<String, ProcEntry>>> = OnceLock::new();
+static ADOPTED: OnceLock<Mutex<HashMap<String, AdoptedEntry>>> = OnceLock::new();
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+/// App data directory holding servers.json
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+static SUPERVISOR: OnceLock<()> = OnceLock::new();
+
+/// How often the supervisor checks children for exits and due restarts
+const SUPERVISOR_INTERVAL: Duration = Duration::from_millis(500);
 
 fn processes() -> &'static Mutex<HashMap<String, ProcEntry>> {
     PROCESSES.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
-fn now_iso() -> String {
+fn adopted() -> &'static Mutex<HashMap<String, AdoptedEntry>> {
+    ADOPTED.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Ids being started, and the signal raised when one is released
+static STARTING: OnceLock<(Mutex<HashSet<String>>, Condvar)> = OnceLock::new();
+
+fn starting() -> &'static (Mutex<HashSet<String>>, Condvar) {
+    STARTING.get_or_init(|| (Mutex::new(HashSet::new()), Condvar::new()))
+}
+
+/// Exclusive right to start a server id, held from the status check until the new entry is
+/// inserted so concurrent starts can't both spawn; released when dropped
+struct StartReservation(String);
+
+impl StartReservation {
+    /// Waits while another start of the same id is in progress
+    fn claim(server_id: &str) -> Result<Self, String> {
+        let (lock, released) = starting();
+        let mut ids = lock.lock().map_err(|_| "Lock poisoned")?;
+        while ids.contains(server_id) {
+            ids = released.wait(ids).map_err(|_| "Lock poisoned")?;
+        }
+        ids.insert(server_id.to_string());
+        Ok(Self(server_id.to_string()))
+    }
+}
+
+impl Drop for StartReservation {
+    fn drop(&mut self) {
+        let (lock, released) = starting();
+        if let Ok(mut ids) = lock.lock() {
+            ids.remove(&self.0);
+        }
+        released.notify_all();
+    }
+}
+
+/// Register the app handle used to push lifecycle events to the frontend
+pub fn init(app: &AppHandle) {
+    let _ = APP_HANDLE.set(app.clone());
+    match app.path().app_data_dir() {
+        Ok(dir) => init_headless(dir),
+        Err(e) => log::warn!("Failed to get app data directory: {e}"),
+    }
+}
+
+/// Read saved server configs from `dir` when running without a Tauri app
+pub fn init_headless(dir: PathBuf) {
+    let _ = DATA_DIR.set(dir);
+}
+
+/// Emit an event to the frontend; a no-op until `init` has been called
+pub(crate) fn emit_event<S: Serialize + Clone>(event: &str, payload: S) {
+    if let Some(app) = APP_HANDLE.get() {
+        if let Err(e) = app.emit(event, payload) {
+            log::debug!("Failed to emit {event}: {e}");
+        }
+    }
+}
+
+pub(crate) fn now_iso() -> String {
     chrono::Utc::now().to_rfc3339()
 }
 
@@ -60,16 +348,117 @@ fn update_uptime(entry: &mut ProcEntry) {
     }
 }
 
-#[tauri::command]
-pub fn mcp_start_server(server_id: String, cfg: StdioConfig) -> Result<MCPServerProcess, String> {
-    // If already running, return current state
-    if let Ok(state) = mcp_get_status(server_id.clone()) {
-        if state.state == LifecycleState::Running {
-            return Ok(state);
+fn refresh_output(entry: &mut ProcEntry) {
+    entry.state.output = mcp_output::tail_text(&entry.state.server_id, STATUS_OUTPUT_LINES);
+}
+
+/// Fill `memory_usage`/`cpu_usage` from the supervisor's latest sample; status queries never
+/// sample themselves, so polling them doesn't add to the history
+fn refresh_metrics(state: &mut MCPServerProcess) {
+    let sample = match (&state.state, state.pid) {
+        (LifecycleState::Running | LifecycleState::Starting, Some(pid)) => mcp_metrics::latest(&state.server_id, pid),
+        _ => None,
+    };
+    state.memory_usage = sample.as_ref().map(|s| s.memory_bytes);
+    state.cpu_usage = sample.as_ref().map(|s| s.cpu_percent);
+}
+
+/// The pid the supervisor should sample for `state`, or `None` after clearing the figures of
+/// a server that isn't running
+fn metrics_target(state: &mut MCPServerProcess) -> Option<(String, u32)> {
+    match (&state.state, state.pid) {
+        (LifecycleState::Running | LifecycleState::Starting, Some(pid)) => Some((state.server_id.clone(), pid)),
+        _ => {
+            state.memory_usage = None;
+            state.cpu_usage = None;
+            None
+        }
+    }
+}
+
+/// Event pushed on every `LifecycleState` transition
+pub const STATE_EVENT: &str = "mcp-server-state";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StateChangeEvent {
+    pub server_id: String,
+    /// `None` for a server that was not known before
+    pub previous: Option<LifecycleState>,
+    pub state: LifecycleState,
+    pub reason: Option<String>,
+    pub exit_code: Option<i32>,
+    /// Signal that terminated the process, e.g. "SIGKILL"
+    pub signal: Option<String>,
+    pub timestamp: String,
+}
+
+/// How a process ended, attached to the transition it caused
+#[derive(Debug, Default)]
+pub(crate) struct ExitInfo {
+    code: Option<i32>,
+    signal: Option<String>,
+}
+
+impl ExitInfo {
+    fn from_status(status: &ExitStatus) -> Self {
+        #[cfg(unix)]
+        let signal = {
+            use std::os::unix::process::ExitStatusExt;
+            status.signal().map(|sig| nix::sys::signal::Signal::try_from(sig).map(|s| s.as_str().to_string()).unwrap_or_else(|_| sig.to_string()))
+        };
+        #[cfg(not(unix))]
+        let signal = None;
+        Self { code: status.code(), signal }
+    }
+
+    fn from_stop(outcome: &StopOutcome) -> Self {
+        Self { code: outcome.exit_code, signal: outcome.signal.map(|s| s.as_str().to_string()) }
+    }
+}
+
+/// Announce a transition to the frontend; returns the event that was emitted
+fn emit_state_change(process: &MCPServerProcess, previous: Option<LifecycleState>, reason: Option<String>, exit: ExitInfo) -> StateChangeEvent {
+    let event = StateChangeEvent {
+        server_id: process.server_id.clone(),
+        previous,
+        state: process.state.clone(),
+        reason,
+        exit_code: exit.code,
+        signal: exit.signal,
+        timestamp: now_iso(),
+    };
+    emit_event(STATE_EVENT, event.clone());
+    event
+}
+
+/// Move a server to `state`, emitting a state-change event if it actually changed
+pub(crate) fn set_state(process: &mut MCPServerProcess, state: LifecycleState, reason: Option<String>, exit: ExitInfo) -> Option<StateChangeEvent> {
+    if process.state == state {
+        return None;
+    }
+    let previous = std::mem::replace(&mut process.state, state);
+    Some(emit_state_change(process, Some(previous), reason, exit))
+}
+
+fn describe_exit(status: &ExitStatus) -> String {
+    if let Some(code) = status.code() {
+        return format!("Exited with code {code}");
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(sig) = status.signal() {
+            return format!("Killed by signal {sig}");
         }
     }
+    "Exited".to_string()
+}
 
-    // Spawn process
+/// Spawn the server process, start draining its output and open a JSON-RPC channel on its stdio.
+/// `env` is `cfg.env` with secret references already resolved; resolving may wait on the
+/// keyring, so callers do it before taking the registry lock. The flag reports whether the
+/// sandbox policy was applied.
+fn spawn_child(server_id: &str, cfg: &StdioConfig, env: HashMap<String, String>) -> Result<(Child, Arc<RpcChannel>, bool), String> {
     let mut cmd = Command::new(&cfg.command);
     if !cfg.args.is_empty() {
         cmd.args(&cfg.args);
@@ -77,18 +466,509 @@ pub fn mcp_start_server(server_id: String, cfg: StdioConfig) -> Result<MCPServer
     if let Some(cwd) = &cfg.cwd {
         cmd.current_dir(cwd);
     }
-    if !cfg.env.is_empty() {
-        cmd.envs(&cfg.env);
-    }
+    cmd.envs(env);
     cmd.stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped());
+    process_tree::isolate(&mut cmd);
+    // Each run starts with an empty buffer so a crash report holds only its own output;
+    // earlier runs remain in the server's log files
+    let _ = mcp_output::mcp_clear_output(server_id.to_string());
+    mcp_limits::apply(&mut cmd, &cfg.limits);
+    let sandboxed = mcp_sandbox::apply(&mut cmd, cfg)?;
+
+    let mut child = cmd.spawn().map_err(|e| format!("Failed to start process: {e}"))?;
+    let stdin = child.stdin.take().ok_or("Failed to open server stdin")?;
+    let rpc = Arc::new(RpcChannel::new(stdin));
+
+    // Drain stdout/stderr so chatty servers never block on a full pipe
+    if let Some(stdout) = child.stdout.take() {
+        let rpc_reader = rpc.clone();
+        let reader_id = server_id.to_string();
+        let routed = mcp_output::attach_with(server_id.to_string(), OutputStream::Stdout, stdout, move |line| match line {
+            Some(line) => rpc_reader.dispatch(&reader_id, line),
+            None => rpc_reader.close(),
+        });
+        if let Err(e) = routed {
+            log::warn!("Failed to capture stdout for {server_id}: {e}");
+        }
+    }
+    if let Some(stderr) = child.stderr.take() {
+        if let Err(e) = mcp_output::attach(server_id.to_string(), OutputStream::Stderr, stderr) {
+            log::warn!("Failed to capture stderr for {server_id}: {e}");
+        }
+    }
+    Ok((child, rpc, sandboxed))
+}
+
+fn initial_state(cfg: &StdioConfig) -> LifecycleState {
+    if cfg.health.handshake { LifecycleState::Starting } else { LifecycleState::Running }
+}
+
+/// Run `f` on the entry for `server_id` if it still belongs to the process `pid`
+fn with_entry(server_id: &str, pid: u32, f: impl FnOnce(&mut ProcEntry)) -> bool {
+    let Ok(mut map) = processes().lock() else { return false };
+    match map.get_mut(server_id) {
+        Some(entry) if entry.state.pid == Some(pid) && matches!(entry.state.state, LifecycleState::Starting | LifecycleState::Running) => {
+            f(entry);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Kill a child the hub considers broken; the supervisor then applies the restart policy
+fn fail_entry(entry: &mut ProcEntry, reason: String) {
+    if let Ok(Some(_)) = entry.child.try_wait() {
+        // Already exited on its own; report the real exit status instead
+        return;
+    }
+    log::warn!("Server {}: {reason}", entry.state.server_id);
+    entry.exit_reason = Some(reason);
+    // Only signalled here, under the registry lock; the supervisor reaps the child
+    process_tree::kill_group(entry.child.id());
+}
+
+/// Run history entry for the run of `entry` that just ended
+fn run_record(entry: &ProcEntry, end_reason: &str, crashed: bool, exit: &ExitInfo) -> RunRecord {
+    let server_id = &entry.state.server_id;
+    RunRecord {
+        server_id: server_id.clone(),
+        started_at: entry.state.started_at.clone(),
+        stopped_at: entry.state.stopped_at.clone(),
+        uptime_secs: entry.started.elapsed().ok().map(|d| d.as_secs()),
+        exit_code: exit.code,
+        signal: exit.signal.clone(),
+        start_cause: entry.start_cause.clone(),
+        end_reason: end_reason.to_string(),
+        crashed,
+        restart_count: entry.state.restart_count,
+        stderr_tail: if crashed { mcp_output::tail_lines(server_id, OutputStream::Stderr, CRASH_STDERR_LINES) } else { vec![] },
+    }
+}
+
+/// Persist a finished run; a no-op until `init` has been called. Never called under the
+/// registry lock since it writes to disk.
+fn save_run(record: &RunRecord) {
+    let Some(app) = APP_HANDLE.get() else { return };
+    if let Err(e) = storage::save_run_record(app, record) {
+        log::warn!("Failed to record run of {}: {e}", record.server_id);
+    }
+}
+
+/// Perform the `initialize` handshake, then ping periodically until the process goes away
+fn start_health_monitor(server_id: String, pid: u32, rpc: Arc<RpcChannel>, health: HealthCheckConfig) {
+    if !health.handshake {
+        return;
+    }
+    let spawned = std::thread::Builder::new().name(format!("mcp-{server_id}-health")).spawn(move || {
+        let started = Instant::now();
+        let init = rpc.request("initialize", mcp_rpc::initialize_params(), Duration::from_millis(health.startup_timeout_ms));
+        let latency = started.elapsed().as_millis() as u64;
+        match init {
+            Ok(result) => {
+                let _ = rpc.notify("notifications/initialized", Value::Null);
+                let ready = with_entry(&server_id, pid, |entry| {
+                    set_state(&mut entry.state, LifecycleState::Running, Some(format!("Initialize handshake completed in {latency}ms")), ExitInfo::default());
+                    entry.state.health = Some(HealthStatus::from_initialize(&result, latency));
+                });
+                if !ready {
+                    return;
+                }
+                log::info!("Server {server_id} ready after {latency}ms");
+            }
+            Err(e) => {
+                with_entry(&server_id, pid, |entry| fail_entry(entry, format!("Initialize handshake failed: {e}")));
+                return;
+            }
+        }
+
+        if health.ping_interval_ms == 0 {
+            return;
+        }
+        let interval = Duration::from_millis(health.ping_interval_ms);
+        loop {
+            let slept = Instant::now();
+            while slept.elapsed() < interval {
+                if rpc.is_closed() {
+                    return;
+                }
+                std::thread::sleep(Duration::from_millis(200).min(interval));
+            }
+            let sent = Instant::now();
+            let ping = rpc.request("ping", Value::Null, Duration::from_millis(health.ping_timeout_ms));
+            let latency = sent.elapsed().as_millis() as u64;
+            let alive = with_entry(&server_id, pid, |entry| {
+                let status = entry.state.health.get_or_insert_with(Default::default);
+                status.last_ping_at = Some(now_iso());
+                match ping {
+                    Ok(_) => {
+                        status.last_ping_latency_ms = Some(latency);
+                        status.consecutive_ping_failures = 0;
+                    }
+                    Err(e) => {
+                        status.last_ping_latency_ms = None;
+                        status.consecutive_ping_failures += 1;
+                        let failures = status.consecutive_ping_failures;
+                        if failures >= health.max_ping_failures {
+                            fail_entry(entry, format!("Unresponsive after {failures} failed pings: {e}"));
+                        }
+                    }
+                }
+            });
+            if !alive {
+                return;
+            }
+        }
+    });
+    if let Err(e) = spawned {
+        log::error!("Failed to start health monitor: {e}");
+    }
+}
+
+/// Check whether a live child has exited and apply its restart policy. Returns the run that
+/// ended, for the caller to save once the registry lock is released.
+fn poll_entry(entry: &mut ProcEntry) -> Result<Option<RunRecord>, String> {
+    if !matches!(entry.state.state, LifecycleState::Running | LifecycleState::Starting) {
+        return Ok(None);
+    }
+    let Some(status) = entry.child.try_wait().map_err(|e| format!("Failed to poll process: {e}"))? else {
+        update_uptime(entry);
+        let healthy_for = entry.started.elapsed().unwrap_or_default();
+        if entry.failures > 0 && healthy_for >= Duration::from_secs(entry.cfg.restart.reset_after_secs) {
+            entry.failures = 0;
+        }
+        return Ok(None);
+    };
+
+    update_uptime(entry);
+    entry.state.stopped_at = Some(now_iso());
+    if let Some(health) = entry.state.health.as_mut() {
+        health.ready = false;
+    }
+    // A child killed by the hub (failed handshake, hung) counts as a failure
+    let killed_by_hub = entry.exit_reason.is_some();
+    let success = status.success() && !killed_by_hub;
+    let reason = entry.exit_reason.take().unwrap_or_else(|| {
+        let tail = mcp_output::tail_text(&entry.state.server_id, STATUS_OUTPUT_LINES).unwrap_or_default();
+        match mcp_limits::diagnose(&entry.cfg.limits, &status, &tail) {
+            Some(limit) => format!("{}: {limit}", describe_exit(&status)),
+            None => describe_exit(&status),
+        }
+    });
+    let run = run_record(entry, &reason, !success, &ExitInfo::from_status(&status));
+    let policy = &entry.cfg.restart;
+    if !policy.wants_restart(success) {
+        entry.state.last_error = if success { None } else { Some(reason.clone()) };
+        let next = if success { LifecycleState::Stopped } else { LifecycleState::Error };
+        set_state(&mut entry.state, next, Some(reason), ExitInfo::from_status(&status));
+        return Ok(Some(run));
+    }
+
+    if entry.failures >= policy.max_retries {
+        let message = format!("{reason}; giving up after {} restart attempts", entry.failures);
+        log::error!("Server {} {message}", entry.state.server_id);
+        entry.state.last_error = Some(message.clone());
+        set_state(&mut entry.state, LifecycleState::Error, Some(message), ExitInfo::from_status(&status));
+        return Ok(Some(run));
+    }
+
+    let delay = policy.backoff(entry.failures);
+    entry.failures += 1;
+    entry.next_restart = Some(Instant::now() + delay);
+    let message = format!("{reason}; restarting in {}ms (attempt {}/{})", delay.as_millis(), entry.failures, policy.max_retries);
+    log::warn!("Server {} {message}", entry.state.server_id);
+    entry.state.last_error = Some(message.clone());
+    set_state(&mut entry.state, LifecycleState::Restarting, Some(message), ExitInfo::from_status(&status));
+    Ok(Some(run))
+}
+
+/// A restart whose backoff delay has elapsed, claimed under the registry lock
+struct DueRestart {
+    server_id: String,
+    cfg: StdioConfig,
+    /// `restart_count` when claimed; the entry must still match when the new child is attached
+    restart_count: u32,
+}
+
+/// Claim the restart of `entry` if its backoff delay has elapsed
+fn restart_due(entry: &mut ProcEntry) -> Option<DueRestart> {
+    if entry.state.state != LifecycleState::Restarting || entry.next_restart.is_some_and(|t| t > Instant::now()) {
+        return None;
+    }
+    entry.next_restart = None;
+    Some(DueRestart { server_id: entry.state.server_id.clone(), cfg: entry.cfg.clone(), restart_count: entry.state.restart_count })
+}
+
+/// Respawn a claimed server outside the registry lock (resolving its env may wait on the
+/// keyring), then attach the new child unless the server was stopped or replaced meanwhile
+fn restart(due: DueRestart) {
+    let spawned = secure_storage::resolve_env(&due.cfg.env).and_then(|env| spawn_child(&due.server_id, &due.cfg, env));
+    let start_ticks = spawned.as_ref().ok().and_then(|(child, _, _)| process_registry::start_ticks(child.id()));
+    let Ok(mut map) = processes().lock() else { return };
+    let entry = map.get_mut(&due.server_id).filter(|e| e.state.state == LifecycleState::Restarting && e.state.restart_count == due.restart_count);
+    let Some(entry) = entry else {
+        drop(map);
+        if let Ok((mut child, rpc, _)) = spawned {
+            rpc.close();
+            process_tree::kill_tree(&mut child);
+        }
+        return;
+    };
+    let server_id = due.server_id;
+    match spawned {
+        Ok((child, rpc, sandboxed)) => {
+            let pid = child.id();
+            entry.start_ticks = start_ticks;
+            entry.state.pid = Some(pid);
+            entry.state.sandboxed = sandboxed;
+            entry.child = child;
+            entry.rpc = rpc.clone();
+            entry.started = SystemTime::now();
+            entry.state.health = None;
+            entry.state.started_at = Some(now_iso());
+            entry.state.stopped_at = None;
+            entry.state.uptime = Some(0);
+            entry.state.restart_count += 1;
+            log::info!("Restarted server {server_id} (restart #{})", entry.state.restart_count);
+            let reason = format!("Restart #{}", entry.state.restart_count);
+            entry.start_cause = match &entry.state.last_error {
+                Some(cause) => format!("{reason} after: {cause}"),
+                None => reason.clone(),
+            };
+            set_state(&mut entry.state, initial_state(&entry.cfg), Some(reason), ExitInfo::default());
+            let health = entry.cfg.health.clone();
+            drop(map);
+            start_health_monitor(server_id, pid, rpc, health);
+        }
+        Err(e) => {
+            let policy = &entry.cfg.restart;
+            if entry.failures >= policy.max_retries {
+                let message = format!("{e}; giving up after {} restart attempts", entry.failures);
+                entry.state.last_error = Some(message.clone());
+                set_state(&mut entry.state, LifecycleState::Error, Some(message), ExitInfo::default());
+            } else {
+                let delay = policy.backoff(entry.failures);
+                entry.failures += 1;
+                entry.next_restart = Some(Instant::now() + delay);
+                entry.state.last_error = Some(format!("{e}; retrying in {}ms", delay.as_millis()));
+            }
+        }
+    }
+}
 
-    let child = cmd.spawn().map_err(|e| format!("Failed to start process: {e}"))?;
+/// Start the background thread that watches children and applies restart policies
+fn ensure_supervisor() {
+    SUPERVISOR.get_or_init(|| {
+        let spawned = std::thread::Builder::new().name("mcp-supervisor".into()).spawn(|| loop {
+            std::thread::sleep(SUPERVISOR_INTERVAL);
+            // Only bookkeeping happens under the registry lock; spawning, disk and /proc I/O
+            // run after it is released so status and RPC commands never wait on them
+            let (mut runs, mut restarts, mut sampled) = (Vec::new(), Vec::new(), Vec::new());
+            if let Ok(mut map) = processes().lock() {
+                for entry in map.values_mut() {
+                    match poll_entry(entry) {
+                        Ok(run) => runs.extend(run),
+                        Err(e) => log::warn!("Supervisor failed to poll {}: {e}", entry.state.server_id),
+                    }
+                    restarts.extend(restart_due(entry));
+                    sampled.extend(metrics_target(&mut entry.state));
+                }
+            }
+            runs.iter().for_each(save_run);
+            restarts.into_iter().for_each(restart);
+            for (server_id, pid) in sampled {
+                let sample = mcp_metrics::sample(&server_id, pid, mcp_metrics::SAMPLE_INTERVAL);
+                let Ok(mut map) = processes().lock() else { break };
+                let Some(entry) = map.get_mut(&server_id).filter(|e| e.state.pid == Some(pid)) else { continue };
+                entry.state.memory_usage = sample.as_ref().map(|s| s.memory_bytes);
+                entry.state.cpu_usage = sample.as_ref().map(|s| s.cpu_percent);
+                if let Some(reason) = sample.and_then(|s| mcp_limits::check_usage(&entry.cfg.limits, &s)) {
+                    fail_entry(entry, reason);
+                }
+            }
+            let mut sampled = Vec::new();
+            if let Ok(mut map) = adopted().lock() {
+                for entry in map.values_mut() {
+                    poll_adopted(entry);
+                    sampled.extend(metrics_target(&mut entry.state));
+                }
+            }
+            for (server_id, pid) in sampled {
+                let sample = mcp_metrics::sample(&server_id, pid, mcp_metrics::SAMPLE_INTERVAL);
+                if let Some(entry) = adopted().lock().ok().as_mut().and_then(|map| map.get_mut(&server_id)).filter(|e| e.state.pid == Some(pid)) {
+                    entry.state.memory_usage = sample.as_ref().map(|s| s.memory_bytes);
+                    entry.state.cpu_usage = sample.as_ref().map(|s| s.cpu_percent);
+                }
+            }
+            persist_registry();
+        });
+        if let Err(e) = spawned {
+            log::error!("Failed to start MCP supervisor: {e}");
+        }
+    });
+}
+
+/// Mark an adopted server stopped once its process is gone (exit status is unknowable)
+fn poll_adopted(entry: &mut AdoptedEntry) {
+    if entry.state.state != LifecycleState::Running {
+        return;
+    }
+    if process_registry::is_alive(&entry.record) {
+        if let Ok(elapsed) = entry.started.elapsed() {
+            entry.state.uptime = Some(elapsed.as_secs());
+        }
+        return;
+    }
+    entry.state.stopped_at = Some(now_iso());
+    entry.state.last_error = Some("Adopted process exited".into());
+    set_state(&mut entry.state, LifecycleState::Stopped, Some("Adopted process exited".into()), ExitInfo::default());
+    log::info!("Adopted server {} (pid {}) exited", entry.state.server_id, entry.record.pid);
+}
+
+fn record_for(entry: &ProcEntry) -> Option<RegistryRecord> {
+    if !matches!(entry.state.state, LifecycleState::Starting | LifecycleState::Running) {
+        return None;
+    }
+    let pid = entry.state.pid?;
+    Some(RegistryRecord {
+        server_id: entry.state.server_id.clone(),
+        pid,
+        started_at: entry.state.started_at.clone().unwrap_or_default(),
+        start_ticks: entry.start_ticks,
+        fingerprint: process_registry::fingerprint(&entry.cfg),
+        command: entry.cfg.command.clone(),
+    })
+}
+
+/// Write every live server (spawned or adopted) to the on-disk process registry
+fn persist_registry() {
+    let mut records: Vec<RegistryRecord> = match processes().lock() {
+        Ok(map) => map.values().filter_map(record_for).collect(),
+        Err(_) => return,
+    };
+    if let Ok(map) = adopted().lock() {
+        records.extend(map.values().filter(|e| e.state.state == LifecycleState::Running).map(|e| e.record.clone()));
+    }
+    records.sort_by(|a, b| a.server_id.cmp(&b.server_id));
+    process_registry::persist(&records);
+}
+
+/// Adopt or stop servers that survived a previous session; returns the ids that were adopted
+fn reconcile_orphans(records: Vec<RegistryRecord>, policy: OrphanPolicy) -> Vec<String> {
+    let mut adopted_ids = Vec::new();
+    for record in records {
+        if !process_registry::is_alive(&record) {
+            continue;
+        }
+        match policy {
+            OrphanPolicy::Kill => {
+                let outcome = process_tree::terminate_pid(record.pid, Duration::from_millis(process_tree::DEFAULT_STOP_GRACE_MS), false);
+                log::info!("Stopped orphaned server {} (pid {}, {:?})", record.server_id, record.pid, outcome.signal);
+            }
+            OrphanPolicy::Adopt => {
+                let started = chrono::DateTime::parse_from_rfc3339(&record.started_at).map(SystemTime::from).unwrap_or_else(|_| SystemTime::now());
+                let state = MCPServerProcess {
+                    server_id: record.server_id.clone(),
+                    pid: Some(record.pid),
+                    state: LifecycleState::Running,
+                    started_at: Some(record.started_at.clone()),
+                    uptime: started.elapsed().ok().map(|d| d.as_secs()),
+                    adopted: true,
+                    ..Default::default()
+                };
+                log::info!("Adopted orphaned server {} (pid {})", record.server_id, record.pid);
+                emit_state_change(&state, None, Some("Adopted from a previous session".into()), ExitInfo::default());
+                adopted_ids.push(record.server_id.clone());
+                if let Ok(mut map) = adopted().lock() {
+                    map.insert(record.server_id.clone(), AdoptedEntry { state, started, record });
+                }
+            }
+        }
+    }
+    adopted_ids
+}
+
+/// Reattach to or clean up servers left running by a crashed session, per the orphan policy,
+/// then run `then` on the same background thread. Anything that starts servers at launch goes
+/// in `then`, so it sees adopted survivors instead of starting second copies of them.
+pub fn recover(app: &AppHandle, then: impl FnOnce() + Send + 'static) {
+    let records = match process_registry::open(app) {
+        Ok(records) => Some(records),
+        Err(e) => {
+            log::warn!("Process registry unavailable: {e}");
+            None
+        }
+    };
+    let policy = process_registry::load_settings(app).orphan_policy;
+    // Stopping orphans can take a grace period each; don't hold up startup
+    let spawned = std::thread::Builder::new().name("mcp-recover".into()).spawn(move || {
+        if let Some(records) = records {
+            if !records.is_empty() {
+                log::info!("Found {} server(s) from a previous session; policy: {policy:?}", records.len());
+            }
+            reconcile_orphans(records, policy);
+            persist_registry();
+            ensure_supervisor();
+        }
+        then();
+    });
+    if let Err(e) = spawned {
+        log::error!("Failed to recover orphaned servers: {e}");
+    }
+}
+
+/// JSON-RPC channel of a server that has completed startup
+pub(crate) fn rpc_channel(server_id: &str) -> Result<Arc<RpcChannel>, String> {
+    let map = processes().lock().map_err(|_| "Lock poisoned")?;
+    let entry = map.get(server_id).ok_or_else(|| format!("No process for {server_id}"))?;
+    if entry.state.state != LifecycleState::Running {
+        return Err(format!("Server {server_id} is not running (state: {:?})", entry.state.state));
+    }
+    Ok(entry.rpc.clone())
+}
+
+#[tauri::command]
+pub async fn mcp_start_server(server_id: String, cfg: StdioConfig) -> Result<MCPServerProcess, String> {
+    tauri::async_runtime::spawn_blocking(move || start_server(server_id, cfg))
+        .await
+        .map_err(|e| format!("Start task failed: {e}"))?
+}
+
+/// Shared start path; may stop an adopted survivor first, so it blocks for up to its grace period
+pub(crate) fn start_server(server_id: String, cfg: StdioConfig) -> Result<MCPServerProcess, String> {
+    let _reservation = StartReservation::claim(&server_id)?;
+    // If already running, return current state
+    let mut previous = None;
+    if let Ok(state) = mcp_get_status(server_id.clone()) {
+        previous = Some(state.state.clone());
+        if matches!(state.state, LifecycleState::Starting | LifecycleState::Running | LifecycleState::Restarting) {
+            if !state.adopted {
+                return Ok(state);
+            }
+            // An adopted survivor launched from the same command is reused; otherwise it is replaced
+            let same_command = adopted()
+                .lock()
+                .ok()
+                .and_then(|map| map.get(&server_id).map(|e| e.record.fingerprint == process_registry::fingerprint(&cfg)))
+                .unwrap_or(false);
+            if same_command {
+                return Ok(state);
+            }
+            stop_server(&server_id, false, None)?;
+            previous = Some(LifecycleState::Stopped);
+        }
+    }
+    if let Ok(mut map) = adopted().lock() {
+        map.remove(&server_id);
+    }
+
+    let env = secure_storage::resolve_env(&cfg.env)?;
+    let (child, rpc, sandboxed) = spawn_child(&server_id, &cfg, env)?;
     let pid_val = child.id();
 
     let process = MCPServerProcess {
         server_id: server_id.clone(),
     pid: Some(pid_val),
-        state: LifecycleState::Running,
+        state: initial_state(&cfg),
         started_at: Some(now_iso()),
         stopped_at: None,
         restart_count: 0,
@@ -97,84 +977,225 @@ pub fn mcp_start_server(server_id: String, cfg: StdioConfig) -> Result<MCPServer
         cpu_usage: None,
         uptime: Some(0),
         output: None,
+        health: None,
+        adopted: false,
+        sandboxed,
+        config_hash: Some(process_registry::config_hash(&cfg)),
+        config_drift: None,
+        remote_url: None,
     };
 
-    let entry = ProcEntry { child, state: process.clone(), started: SystemTime::now() };
-    processes().lock().map_err(|_| "Lock poisoned")?.insert(server_id, entry);
+    emit_state_change(&process, previous, Some("Start requested".into()), ExitInfo::default());
+    let health = cfg.health.clone();
+    let start_ticks = process_registry::start_ticks(pid_val);
+    let entry = ProcEntry { child, state: process.clone(), started: SystemTime::now(), cfg, rpc: rpc.clone(), failures: 0, next_restart: None, exit_reason: None, start_cause: "Start requested".into(), start_ticks };
+    processes().lock().map_err(|_| "Lock poisoned")?.insert(server_id.clone(), entry);
+    start_health_monitor(server_id, pid_val, rpc, health);
+    ensure_supervisor();
+    persist_registry();
 
     Ok(process)
 }
 
+/// Ids of every server in the registry, whatever its state
+pub(crate) fn server_ids() -> Vec<String> {
+    let mut ids: Vec<String> = processes().lock().map(|map| map.keys().cloned().collect()).unwrap_or_default();
+    if let Ok(map) = adopted().lock() {
+        ids.extend(map.keys().cloned());
+    }
+    ids.extend(mcp_remote::ids());
+    ids
+}
+
+/// Stop a server and its whole process tree: SIGTERM, then SIGKILL after the
+/// configured grace period (immediately with `force`)
 #[tauri::command]
-pub fn mcp_stop_server(server_id: String, force: Option<bool>) -> Result<(), String> {
-    let mut map = processes().lock().map_err(|_| "Lock poisoned")?;
-    let force = force.unwrap_or(false);
-    let mut entry = map.remove(&server_id).ok_or_else(|| format!("No running process for {server_id}"))?;
+pub async fn mcp_stop_server(server_id: String, force: Option<bool>) -> Result<StopOutcome, String> {
+    tauri::async_runtime::spawn_blocking(move || stop_server(&server_id, force.unwrap_or(false), None))
+        .await
+        .map_err(|e| format!("Stop task failed: {e}"))?
+}
 
-    entry.state.state = LifecycleState::Stopping;
-    if force {
-        entry.child.kill().map_err(|e| format!("Failed to kill process: {e}"))?;
-    } else {
-        // Try to terminate gracefully; on Windows, there's no SIGTERM, so kill
-        #[cfg(unix)]
-        {
-            use nix::sys::signal::{kill, Signal};
-            use nix::unistd::Pid;
-            if let Some(pid) = entry.child.id() { let _ = kill(Pid::from_raw(pid as i32), Signal::SIGTERM); }
-        }
-        #[cfg(not(unix))]
-        {
-            let _ = entry.child.kill();
-        }
+/// Shared stop path; `max_grace` caps the configured grace period (used on app exit)
+pub(crate) fn stop_server(server_id: &str, force: bool, max_grace: Option<Duration>) -> Result<StopOutcome, String> {
+    let removed = processes().lock().map_err(|_| "Lock poisoned")?.remove(server_id);
+    let Some(mut entry) = removed else { return stop_adopted(server_id, force, max_grace) };
+    mcp_metrics::forget(server_id);
 
-        // Wait briefly
-        let _ = entry.child.wait();
+    // The registry lock is released so a slow shutdown doesn't stall other servers
+    let was_running = matches!(entry.state.state, LifecycleState::Running | LifecycleState::Starting);
+    set_state(&mut entry.state, LifecycleState::Stopping, Some("Stop requested".into()), ExitInfo::default());
+    entry.rpc.close();
+    let mut grace = Duration::from_millis(entry.cfg.stop_grace_ms.unwrap_or(process_tree::DEFAULT_STOP_GRACE_MS));
+    if let Some(max) = max_grace {
+        grace = grace.min(max);
     }
+    let outcome = process_tree::terminate(&mut entry.child, grace, force);
+    log::info!("Stopped server {server_id} ({:?} after {}ms)", outcome.signal, outcome.elapsed_ms);
 
-    entry.state.state = LifecycleState::Stopped;
     entry.state.stopped_at = Some(now_iso());
-    Ok(())
+    let reason = format!("Stopped after {}ms", outcome.elapsed_ms);
+    // Runs that already ended on their own were recorded by the supervisor
+    if was_running {
+        save_run(&run_record(&entry, &format!("Stop requested; {}", reason.to_lowercase()), false, &ExitInfo::from_stop(&outcome)));
+    }
+    set_state(&mut entry.state, LifecycleState::Stopped, Some(reason), ExitInfo::from_stop(&outcome));
+    persist_registry();
+    Ok(outcome)
+}
+
+fn stop_adopted(server_id: &str, force: bool, max_grace: Option<Duration>) -> Result<StopOutcome, String> {
+    let entry = adopted().lock().map_err(|_| "Lock poisoned")?.remove(server_id);
+    let Some(mut entry) = entry else {
+        // Remote servers have no process; stopping ends their monitor
+        mcp_remote::stop(server_id)?;
+        return Ok(StopOutcome { signal: None, exit_code: None, elapsed_ms: 0 });
+    };
+    mcp_metrics::forget(server_id);
+    let was_running = entry.state.state == LifecycleState::Running;
+    set_state(&mut entry.state, LifecycleState::Stopping, Some("Stop requested".into()), ExitInfo::default());
+    let outcome = if was_running && process_registry::is_alive(&entry.record) {
+        let grace = max_grace.unwrap_or(Duration::from_millis(process_tree::DEFAULT_STOP_GRACE_MS));
+        process_tree::terminate_pid(entry.record.pid, grace, force)
+    } else {
+        StopOutcome { signal: None, exit_code: None, elapsed_ms: 0 }
+    };
+    log::info!("Stopped adopted server {server_id} ({:?})", outcome.signal);
+    set_state(&mut entry.state, LifecycleState::Stopped, Some("Stopped adopted process".into()), ExitInfo::from_stop(&outcome));
+    persist_registry();
+    Ok(outcome)
 }
 
 #[tauri::command]
-pub fn mcp_restart_server(server_id: String, cfg: Option<StdioConfig>) -> Result<MCPServerProcess, String> {
+pub async fn mcp_restart_server(server_id: String, cfg: Option<StdioConfig>) -> Result<MCPServerProcess, String> {
+    tauri::async_runtime::spawn_blocking(move || restart_server(server_id, cfg))
+        .await
+        .map_err(|e| format!("Restart task failed: {e}"))?
+}
+
+/// Shared restart path: stop (waiting out the grace period), then start again
+pub(crate) fn restart_server(server_id: String, cfg: Option<StdioConfig>) -> Result<MCPServerProcess, String> {
+    // Without an explicit config, relaunch the way the hub last did (or as saved in servers.json)
+    let cfg = match cfg {
+        Some(c) => c,
+        None => last_config(&server_id).ok_or_else(|| format!("No known configuration for {server_id}; pass one to restart"))?,
+    };
     // Stop if exists (ignore errors)
-    let _ = mcp_stop_server(server_id.clone(), Some(false));
+    let _ = stop_server(&server_id, false, None);
     // Wait a moment
     std::thread::sleep(Duration::from_millis(300));
-    // Start again
-    match cfg {
-        Some(c) => mcp_start_server(server_id, c),
-        None => Err("Missing configuration for restart".into()),
+    start_server(server_id, cfg)
+}
+
+/// Launch config of a managed server, falling back to the one saved by the frontend
+fn last_config(server_id: &str) -> Option<StdioConfig> {
+    let managed = processes().lock().ok().and_then(|map| map.get(server_id).map(|e| e.cfg.clone()));
+    managed.or_else(|| saved_configs().remove(server_id))
+}
+
+/// Servers saved by the frontend in servers.json, of every transport
+pub(crate) fn saved_servers() -> Vec<Value> {
+    saved().map(|s| s.servers.as_ref().clone()).unwrap_or_default()
+}
+
+/// Stdio configs saved by the frontend in servers.json, keyed by server id
+pub(crate) fn saved_configs() -> HashMap<String, StdioConfig> {
+    saved().map(|s| s.configs.as_ref().clone()).unwrap_or_default()
+}
+
+/// Parsed servers.json, reused until the file's modification time or size changes
+#[derive(Clone)]
+struct SavedServers {
+    stamp: Option<(SystemTime, u64)>,
+    servers: Arc<Vec<Value>>,
+    configs: Arc<HashMap<String, StdioConfig>>,
+}
+
+static SAVED: OnceLock<Mutex<Option<SavedServers>>> = OnceLock::new();
+
+fn saved() -> Option<SavedServers> {
+    let dir = DATA_DIR.get()?;
+    let stamp = std::fs::metadata(dir.join("servers.json")).ok().and_then(|m| Some((m.modified().ok()?, m.len())));
+    let mut cache = SAVED.get_or_init(|| Mutex::new(None)).lock().ok()?;
+    if let Some(cached) = cache.as_ref().filter(|c| stamp.is_some() && c.stamp == stamp) {
+        return Some(cached.clone());
     }
+    let servers = storage::read_servers(dir).and_then(|json| serde_json::from_str::<Vec<Value>>(&json).map_err(|e| e.to_string())).unwrap_or_else(|e| {
+        log::debug!("Failed to read saved servers: {e}");
+        vec![]
+    });
+    let configs = servers
+        .iter()
+        .filter(|server| server["transportType"] == "stdio")
+        .filter_map(|server| Some((server["id"].as_str()?.to_string(), serde_json::from_value(server.clone()).ok()?)))
+        .collect();
+    let fresh = SavedServers { stamp, servers: Arc::new(servers), configs: Arc::new(configs) };
+    *cache = Some(fresh.clone());
+    Some(fresh)
+}
+
+/// Compare the launch config of a server with its saved counterpart
+fn refresh_drift(state: &mut MCPServerProcess, saved: &HashMap<String, StdioConfig>) {
+    state.config_drift = match (&state.config_hash, saved.get(&state.server_id)) {
+        (Some(hash), Some(cfg)) => Some(*hash != process_registry::config_hash(cfg)),
+        _ => None,
+    };
 }
 
 #[tauri::command]
 pub fn mcp_get_status(server_id: String) -> Result<MCPServerProcess, String> {
+    let saved = saved_configs();
     let mut map = processes().lock().map_err(|_| "Lock poisoned")?;
-    let entry = map.get_mut(&server_id).ok_or_else(|| format!("No process for {server_id}"))?;
+    let Some(entry) = map.get_mut(&server_id) else {
+        drop(map);
+        let mut adopted_map = adopted().lock().map_err(|_| "Lock poisoned")?;
+        let Some(entry) = adopted_map.get_mut(&server_id) else {
+            return mcp_remote::status(&server_id).ok_or_else(|| format!("No process for {server_id}"));
+        };
+        poll_adopted(entry);
+        refresh_metrics(&mut entry.state);
+        return Ok(entry.state.clone());
+    };
     // Refresh uptime; basic health check
-    if let Some(status) = entry.child.try_wait().map_err(|e| format!("Failed to poll process: {e}"))? {
-        if let Some(code) = status.code() {
-            entry.state.state = if code == 0 { LifecycleState::Stopped } else { LifecycleState::Error };
-            entry.state.stopped_at = Some(now_iso());
-            entry.state.last_error = if code == 0 { None } else { Some(format!("Exited with code {code}")) };
-        }
-    } else {
-        entry.state.state = LifecycleState::Running;
-        update_uptime(entry);
-    }
-    Ok(entry.state.clone())
+    let run = poll_entry(entry)?;
+    refresh_metrics(&mut entry.state);
+    refresh_output(entry);
+    refresh_drift(&mut entry.state, &saved);
+    let state = entry.state.clone();
+    drop(map);
+    run.iter().for_each(save_run);
+    Ok(state)
 }
 
 #[tauri::command]
 pub fn mcp_list_running() -> Result<Vec<MCPServerProcess>, String> {
     let mut results = Vec::new();
+    let saved = saved_configs();
     let mut map = processes().lock().map_err(|_| "Lock poisoned")?;
     for entry in map.values_mut() {
         update_uptime(entry);
+        refresh_metrics(&mut entry.state);
+        refresh_output(entry);
+        refresh_drift(&mut entry.state, &saved);
         results.push(entry.state.clone());
     }
+    drop(map);
+    // Survivors of a previous session, unless a fresh process already took over the id
+    let mut adopted_map = adopted().lock().map_err(|_| "Lock poisoned")?;
+    for entry in adopted_map.values_mut() {
+        if results.iter().any(|r| r.server_id == entry.state.server_id) {
+            continue;
+        }
+        poll_adopted(entry);
+        refresh_metrics(&mut entry.state);
+        results.push(entry.state.clone());
+    }
+    drop(adopted_map);
+    for state in mcp_remote::list() {
+        if !results.iter().any(|r| r.server_id == state.server_id) {
+            results.push(state);
+        }
+    }
     Ok(results)
 }
 
@@ -182,6 +1203,27 @@ pub fn mcp_list_running() -> Result<Vec<MCPServerProcess>, String> {
 mod tests {
     use super::*;
 
+    /// Minimal MCP server: answers every request carrying a numeric id
+    #[cfg(unix)]
+    const FAKE_MCP_SERVER: &str = r#"while read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  if [ -n "$id" ]; then
+    printf '{"jsonrpc":"2.0","id":%s,"result":{"protocolVersion":"2025-06-18","serverInfo":{"name":"fake","version":"1.0"},"capabilities":{"tools":{}}}}\n' "$id"
+  fi
+done"#;
+
+    #[cfg(unix)]
+    fn wait_for_state(server_id: &str, state: LifecycleState) -> MCPServerProcess {
+        for _ in 0..100 {
+            let s = mcp_get_status(server_id.to_string()).unwrap();
+            if s.state == state {
+                return s;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        panic!("{server_id} never reached {state:?}");
+    }
+
     /// Test LifecycleState enum variants and equality
     #[test]
     fn test_lifecycle_state_variants() {
@@ -232,6 +1274,7 @@ mod tests {
             cpu_usage: Some(5.5),
             uptime: Some(3600),
             output: Some("Server output".to_string()),
+            ..Default::default()
         };
 
         let json = serde_json::to_string(&process).unwrap();
@@ -260,6 +1303,7 @@ mod tests {
             cpu_usage: None,
             uptime: Some(60),
             output: None,
+            ..Default::default()
         };
 
         let json = serde_json::to_string(&process).unwrap();
@@ -295,6 +1339,7 @@ mod tests {
             args: vec!["server.js".to_string(), "--verbose".to_string()],
             env: env.clone(),
             cwd: Some("/app".to_string()),
+            ..Default::default()
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -317,6 +1362,7 @@ mod tests {
             args: vec![],
             env: HashMap::new(),
             cwd: None,
+            ..Default::default()
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -356,6 +1402,7 @@ mod tests {
             args: vec!["--port".to_string(), "8080".to_string()],
             env,
             cwd: Some("/var/app".to_string()),
+            ..Default::default()
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -380,6 +1427,7 @@ mod tests {
             cpu_usage: None,
             uptime: None,
             output: None,
+            ..Default::default()
         };
 
         let json = serde_json::to_string(&process).unwrap();
@@ -391,6 +1439,397 @@ mod tests {
         assert_eq!(deserialized.restart_count, 0);
     }
 
+    /// Test stdout/stderr of a spawned server are captured into the output buffer
+    #[cfg(unix)]
+    #[test]
+    fn test_start_server_captures_output() {
+        let cfg = StdioConfig {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), "echo to-stdout; echo to-stderr 1>&2".to_string()],
+            env: HashMap::new(),
+            cwd: None,
+            ..Default::default()
+        };
+        start_server("capture-test".to_string(), cfg).unwrap();
+
+        let mut lines = Vec::new();
+        for _ in 0..50 {
+            lines = mcp_output::mcp_get_output("capture-test".to_string(), None, None, None).unwrap();
+            if lines.len() >= 2 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert!(lines.iter().any(|l| l.line == "to-stdout" && l.stream == OutputStream::Stdout));
+        assert!(lines.iter().any(|l| l.line == "to-stderr" && l.stream == OutputStream::Stderr));
+
+        let status = mcp_get_status("capture-test".to_string()).unwrap();
+        assert!(status.output.unwrap_or_default().contains("to-stdout"));
+        assert!(status.memory_usage.is_none(), "exited servers report no memory usage");
+        let _ = processes().lock().unwrap().remove("capture-test");
+    }
+
+    /// Test restart policy defaults and serialization format (kebab-case)
+    #[test]
+    fn test_restart_policy_serde() {
+        let config: StdioConfig = serde_json::from_str(r#"{"command": "node"}"#).unwrap();
+        assert_eq!(config.restart, RestartPolicy::default());
+        assert_eq!(config.restart.mode, RestartMode::Never);
+
+        let config: StdioConfig = serde_json::from_str(
+            r#"{"command": "node", "restart": {"mode": "on-failure", "max_retries": 2}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.restart.mode, RestartMode::OnFailure);
+        assert_eq!(config.restart.max_retries, 2);
+        assert_eq!(config.restart.backoff_base_ms, 1000);
+        assert_eq!(serde_json::to_string(&RestartMode::Always).unwrap(), "\"always\"");
+    }
+
+    /// Test restart decisions for each mode
+    #[test]
+    fn test_restart_policy_wants_restart() {
+        let mut policy = RestartPolicy::default();
+        assert!(!policy.wants_restart(false));
+        policy.mode = RestartMode::OnFailure;
+        assert!(policy.wants_restart(false));
+        assert!(!policy.wants_restart(true));
+        policy.mode = RestartMode::Always;
+        assert!(policy.wants_restart(true));
+    }
+
+    /// Test exponential backoff doubles and respects the cap
+    #[test]
+    fn test_restart_policy_backoff() {
+        let policy = RestartPolicy { backoff_base_ms: 100, backoff_max_ms: 1000, ..Default::default() };
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(800));
+        assert_eq!(policy.backoff(4), Duration::from_millis(1000));
+        assert_eq!(policy.backoff(200), Duration::from_millis(1000));
+    }
+
+    /// Test status of a running server reports resource usage on Linux
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_status_reports_resource_usage() {
+        let cfg = StdioConfig {
+            command: "sleep".to_string(),
+            args: vec!["5".to_string()],
+            health: HealthCheckConfig { handshake: false, ..Default::default() },
+            ..Default::default()
+        };
+        start_server("metrics-test".to_string(), cfg).unwrap();
+        // The supervisor takes the first sample on its next tick
+        let mut status = mcp_get_status("metrics-test".to_string()).unwrap();
+        for _ in 0..50 {
+            if status.memory_usage.is_some() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+            status = mcp_get_status("metrics-test".to_string()).unwrap();
+        }
+        assert_eq!(status.state, LifecycleState::Running);
+        assert!(status.memory_usage.unwrap_or(0) > 0);
+        assert!(status.cpu_usage.is_some());
+        assert!(mcp_list_running().unwrap().iter().any(|p| p.server_id == "metrics-test" && p.memory_usage.is_some()));
+        // Status polls only read the latest sample
+        let recorded = mcp_metrics::mcp_get_metrics("metrics-test".to_string()).unwrap().samples.len();
+        for _ in 0..5 {
+            mcp_get_status("metrics-test".to_string()).unwrap();
+        }
+        assert_eq!(mcp_metrics::mcp_get_metrics("metrics-test".to_string()).unwrap().samples.len(), recorded);
+        stop_server("metrics-test", true, None).unwrap();
+        assert!(mcp_metrics::mcp_get_metrics("metrics-test".to_string()).unwrap().samples.is_empty());
+    }
+
+    /// Test health check config defaults
+    #[test]
+    fn test_health_check_config_defaults() {
+        let config: StdioConfig = serde_json::from_str(r#"{"command": "node"}"#).unwrap();
+        assert!(config.health.handshake);
+        assert_eq!(config.health.startup_timeout_ms, 30_000);
+        assert_eq!(config.health.ping_interval_ms, 30_000);
+        assert_eq!(config.health.max_ping_failures, 3);
+    }
+
+    /// Test a server stays Starting until the initialize handshake succeeds, then reports health
+    #[cfg(unix)]
+    #[test]
+    fn test_handshake_marks_server_running() {
+        let cfg = StdioConfig {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), FAKE_MCP_SERVER.to_string()],
+            health: HealthCheckConfig { ping_interval_ms: 50, ..Default::default() },
+            ..Default::default()
+        };
+        let started = start_server("handshake-test".to_string(), cfg).unwrap();
+        assert_eq!(started.state, LifecycleState::Starting);
+
+        let status = wait_for_state("handshake-test", LifecycleState::Running);
+        let health = status.health.unwrap();
+        assert!(health.ready);
+        assert_eq!(health.server_name.as_deref(), Some("fake"));
+        assert_eq!(health.protocol_version.as_deref(), Some("2025-06-18"));
+
+        let mut pinged = false;
+        for _ in 0..50 {
+            let health = mcp_get_status("handshake-test".to_string()).unwrap().health.unwrap();
+            if health.last_ping_latency_ms.is_some() {
+                pinged = true;
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        assert!(pinged, "ping latency should be reported");
+        stop_server("handshake-test", true, None).unwrap();
+    }
+
+    /// Test requests can be sent through the stdio bridge once the server is ready
+    #[cfg(unix)]
+    #[test]
+    fn test_rpc_bridge_round_trip() {
+        let cfg = StdioConfig {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), FAKE_MCP_SERVER.to_string()],
+            ..Default::default()
+        };
+        start_server("bridge-test".to_string(), cfg).unwrap();
+        wait_for_state("bridge-test", LifecycleState::Running);
+
+        let result = tauri::async_runtime::block_on(crate::mcp_rpc::mcp_send_request(
+            "bridge-test".to_string(),
+            "tools/list".to_string(),
+            None,
+            Some(5_000),
+        ))
+        .unwrap();
+        assert_eq!(result["serverInfo"]["name"], "fake");
+        crate::mcp_rpc::mcp_send_notification("bridge-test".to_string(), "notifications/cancelled".to_string(), None).unwrap();
+
+        stop_server("bridge-test", true, None).unwrap();
+        assert!(rpc_channel("bridge-test").is_err());
+    }
+
+    /// Test stopping escalates to SIGKILL after the configured grace period
+    #[cfg(unix)]
+    #[test]
+    fn test_stop_server_escalates_after_grace() {
+        let cfg = StdioConfig {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), "trap '' TERM; sleep 60".to_string()],
+            health: HealthCheckConfig { handshake: false, ..Default::default() },
+            stop_grace_ms: Some(200),
+            ..Default::default()
+        };
+        start_server("grace-test".to_string(), cfg).unwrap();
+        std::thread::sleep(Duration::from_millis(100));
+
+        let outcome = stop_server("grace-test", false, None).unwrap();
+        assert_eq!(outcome.signal, Some(process_tree::StopSignal::Sigkill));
+        assert!(outcome.elapsed_ms >= 200);
+        assert!(mcp_get_status("grace-test".to_string()).is_err());
+    }
+
+    /// Test a restart without a config relaunches with the stored one
+    #[cfg(unix)]
+    #[test]
+    fn test_restart_reuses_launch_config() {
+        assert!(restart_server("restart-unknown".to_string(), None).unwrap_err().contains("No known configuration"));
+
+        let cfg = StdioConfig {
+            command: "sleep".to_string(),
+            args: vec!["60".to_string()],
+            health: HealthCheckConfig { handshake: false, ..Default::default() },
+            ..Default::default()
+        };
+        let first = start_server("restart-reuse-config-test".to_string(), cfg.clone()).unwrap();
+        assert_eq!(first.config_hash, Some(process_registry::config_hash(&cfg)));
+
+        let second = restart_server("restart-reuse-config-test".to_string(), None).unwrap();
+        assert_ne!(first.pid, second.pid);
+        assert_eq!(second.config_hash, first.config_hash);
+        assert_eq!(second.state, LifecycleState::Running);
+
+        let mut state = second.clone();
+        let mut saved = HashMap::from([("restart-reuse-config-test".to_string(), cfg.clone())]);
+        refresh_drift(&mut state, &saved);
+        assert_eq!(state.config_drift, Some(false));
+        saved.get_mut("restart-reuse-config-test").unwrap().args = vec!["30".to_string()];
+        refresh_drift(&mut state, &saved);
+        assert_eq!(state.config_drift, Some(true));
+        stop_server("restart-reuse-config-test", true, None).unwrap();
+    }
+
+    /// Test concurrent starts of one server spawn a single process
+    #[cfg(unix)]
+    #[test]
+    fn test_concurrent_starts_spawn_once() {
+        let cfg = StdioConfig {
+            command: "sleep".to_string(),
+            args: vec!["60".to_string()],
+            health: HealthCheckConfig { handshake: false, ..Default::default() },
+            ..Default::default()
+        };
+        let starts: Vec<_> = (0..4)
+            .map(|_| {
+                let cfg = cfg.clone();
+                std::thread::spawn(move || start_server("concurrent-start-test".to_string(), cfg).unwrap())
+            })
+            .collect();
+        let pids: HashSet<_> = starts.into_iter().map(|t| t.join().unwrap().pid).collect();
+        assert_eq!(pids.len(), 1, "every start must report the same process");
+        stop_server("concurrent-start-test", true, None).unwrap();
+    }
+
+    /// Test survivors of a previous session are adopted or killed per the orphan policy
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_reconcile_orphans() {
+        let spawn_orphan = || {
+            let mut cmd = Command::new("sleep");
+            cmd.arg("60");
+            process_tree::isolate(&mut cmd);
+            let mut child = cmd.spawn().unwrap();
+            let pid = child.id();
+            // Reap it in the background the way init would once it is stopped
+            std::thread::spawn(move || child.wait());
+            RegistryRecord {
+                server_id: format!("orphan-{pid}"),
+                pid,
+                started_at: now_iso(),
+                start_ticks: process_registry::start_ticks(pid),
+                fingerprint: "f".into(),
+                command: "sleep".into(),
+            }
+        };
+
+        let kept = spawn_orphan();
+        let adopted_ids = reconcile_orphans(vec![kept.clone()], OrphanPolicy::Adopt);
+        assert_eq!(adopted_ids, vec![kept.server_id.clone()]);
+        let status = mcp_get_status(kept.server_id.clone()).unwrap();
+        assert!(status.adopted);
+        assert_eq!(status.state, LifecycleState::Running);
+        assert!(mcp_list_running().unwrap().iter().any(|p| p.server_id == kept.server_id));
+        assert!(rpc_channel(&kept.server_id).is_err());
+
+        let outcome = stop_server(&kept.server_id, false, None).unwrap();
+        assert_eq!(outcome.signal, Some(process_tree::StopSignal::Sigterm));
+        assert!(!process_registry::is_alive(&kept));
+        assert!(mcp_get_status(kept.server_id).is_err());
+
+        let killed = spawn_orphan();
+        assert!(reconcile_orphans(vec![killed.clone()], OrphanPolicy::Kill).is_empty());
+        assert!(!process_registry::is_alive(&killed));
+        assert!(mcp_get_status(killed.server_id).is_err());
+    }
+
+    /// Test transitions produce events only on change and carry exit details
+    #[cfg(unix)]
+    #[test]
+    fn test_set_state_emits_transitions() {
+        let mut process = MCPServerProcess { server_id: "state-test".into(), state: LifecycleState::Running, ..Default::default() };
+        assert!(set_state(&mut process, LifecycleState::Running, None, ExitInfo::default()).is_none());
+
+        let mut child = Command::new("sleep").arg("60").spawn().unwrap();
+        child.kill().unwrap();
+        let status = child.wait().unwrap();
+        let event = set_state(&mut process, LifecycleState::Error, Some("crashed".into()), ExitInfo::from_status(&status)).unwrap();
+        assert_eq!(process.state, LifecycleState::Error);
+        assert_eq!(event.previous, Some(LifecycleState::Running));
+        assert_eq!(event.state, LifecycleState::Error);
+        assert_eq!(event.exit_code, None);
+        assert_eq!(event.signal.as_deref(), Some("SIGKILL"));
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["previous"], "running");
+        assert_eq!(json["reason"], "crashed");
+        assert!(json["timestamp"].is_string());
+    }
+
+    /// Test a server killed by the kernel CPU limit reports which limit it hit
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_cpu_limit_reported_in_last_error() {
+        let cfg = StdioConfig {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), "while :; do :; done".to_string()],
+            health: HealthCheckConfig { handshake: false, ..Default::default() },
+            limits: ResourceLimits { max_cpu_seconds: Some(1), ..Default::default() },
+            ..Default::default()
+        };
+        start_server("cpu-limit-test".to_string(), cfg).unwrap();
+        let status = wait_for_state("cpu-limit-test", LifecycleState::Error);
+        assert!(status.last_error.unwrap().contains("CPU time limit (max_cpu_seconds 1)"));
+        stop_server("cpu-limit-test", true, None).unwrap();
+    }
+
+    /// Test the supervisor kills a tree that exceeds its process limit
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_process_limit_enforced_by_supervisor() {
+        let cfg = StdioConfig {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), "sleep 60 & sleep 60 & wait".to_string()],
+            health: HealthCheckConfig { handshake: false, ..Default::default() },
+            limits: ResourceLimits { max_processes: Some(2), ..Default::default() },
+            ..Default::default()
+        };
+        start_server("proc-limit-test".to_string(), cfg).unwrap();
+        let status = wait_for_state("proc-limit-test", LifecycleState::Error);
+        assert!(status.last_error.unwrap().contains("processes > max_processes 2"));
+        stop_server("proc-limit-test", true, None).unwrap();
+    }
+
+    /// Test a server that never answers initialize moves to Error on timeout
+    #[cfg(unix)]
+    #[test]
+    fn test_handshake_timeout_sets_error() {
+        let cfg = StdioConfig {
+            command: "sleep".to_string(),
+            args: vec!["30".to_string()],
+            health: HealthCheckConfig { startup_timeout_ms: 100, ..Default::default() },
+            ..Default::default()
+        };
+        start_server("handshake-timeout-test".to_string(), cfg).unwrap();
+        let status = wait_for_state("handshake-timeout-test", LifecycleState::Error);
+        assert!(status.last_error.unwrap().starts_with("Initialize handshake failed"));
+        let _ = processes().lock().unwrap().remove("handshake-timeout-test");
+    }
+
+    /// Test the supervisor restarts a crashing server and gives up after max retries
+    #[cfg(unix)]
+    #[test]
+    fn test_supervisor_restarts_until_max_retries() {
+        let cfg = StdioConfig {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), "exit 3".to_string()],
+            restart: RestartPolicy {
+                mode: RestartMode::OnFailure,
+                max_retries: 2,
+                backoff_base_ms: 10,
+                backoff_max_ms: 20,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        start_server("restart-test".to_string(), cfg).unwrap();
+
+        let mut status = None;
+        for _ in 0..100 {
+            let s = mcp_get_status("restart-test".to_string()).unwrap();
+            if s.state == LifecycleState::Error {
+                status = Some(s);
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        let status = status.expect("server should end up in error state");
+        assert_eq!(status.restart_count, 2);
+        assert!(status.last_error.unwrap().contains("giving up after 2 restart attempts"));
+        let _ = processes().lock().unwrap().remove("restart-test");
+    }
+
     /// Test state transitions validity
     #[test]
     fn test_state_transition_logic() {
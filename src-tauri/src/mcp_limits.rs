@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+use std::process::{Command, ExitStatus};
+
+use crate::mcp_metrics::MetricSample;
+
+/// Per-server resource caps. Address space, CPU time and open files are enforced by the
+/// kernel (setrlimit before exec, Linux only); RSS and process count are enforced by the
+/// supervisor from sampled metrics, since Linux ignores RLIMIT_RSS and RLIMIT_NPROC counts
+/// every process of the user rather than the server's tree.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ResourceLimits {
+    /// Virtual address space per process, in MiB (RLIMIT_AS)
+    #[serde(default)]
+    pub max_address_space_mb: Option<u64>,
+    /// Resident memory of the whole process tree, in MiB
+    #[serde(default)]
+    pub max_rss_mb: Option<u64>,
+    /// CPU time per process, in seconds (RLIMIT_CPU)
+    #[serde(default)]
+    pub max_cpu_seconds: Option<u64>,
+    /// Open file descriptors per process (RLIMIT_NOFILE)
+    #[serde(default)]
+    pub max_open_files: Option<u64>,
+    /// Processes in the server's tree, including itself
+    #[serde(default)]
+    pub max_processes: Option<u32>,
+}
+
+/// Extra CPU seconds between SIGXCPU (soft limit) and SIGKILL (hard limit)
+const CPU_HARD_MARGIN_SECS: u64 = 2;
+
+impl ResourceLimits {
+    fn has_kernel_limits(&self) -> bool {
+        self.max_address_space_mb.is_some() || self.max_cpu_seconds.is_some() || self.max_open_files.is_some()
+    }
+}
+
+/// Install a pre-exec hook applying the kernel-enforced limits to the child
+pub fn apply(cmd: &mut Command, limits: &ResourceLimits) {
+    if !limits.has_kernel_limits() {
+        return;
+    }
+    #[cfg(target_os = "linux")]
+    {
+        use nix::sys::resource::{getrlimit, setrlimit, Resource};
+        use std::os::unix::process::CommandExt;
+
+        // Computed up front: nothing may allocate between fork and exec
+        let mut wanted: Vec<(Resource, u64, u64)> = Vec::with_capacity(3);
+        if let Some(mb) = limits.max_address_space_mb {
+            let bytes = mb.saturating_mul(1024 * 1024);
+            wanted.push((Resource::RLIMIT_AS, bytes, bytes));
+        }
+        if let Some(secs) = limits.max_cpu_seconds {
+            wanted.push((Resource::RLIMIT_CPU, secs, secs.saturating_add(CPU_HARD_MARGIN_SECS)));
+        }
+        if let Some(n) = limits.max_open_files {
+            wanted.push((Resource::RLIMIT_NOFILE, n, n));
+        }
+        // SAFETY: getrlimit/setrlimit are async-signal-safe and the closure does not allocate
+        unsafe {
+            cmd.pre_exec(move || {
+                for &(resource, soft, hard) in &wanted {
+                    // An unprivileged process can only lower its hard limit
+                    let (_, current_hard) = getrlimit(resource).map_err(|e| std::io::Error::from_raw_os_error(e as i32))?;
+                    let hard = hard.min(current_hard);
+                    setrlimit(resource, soft.min(hard), hard).map_err(|e| std::io::Error::from_raw_os_error(e as i32))?;
+                }
+                Ok(())
+            });
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = cmd;
+        log::warn!("Address space, CPU and open file limits are only enforced on Linux");
+    }
+}
+
+/// Reason to kill a server whose sampled usage exceeds the supervisor-enforced limits
+pub fn check_usage(limits: &ResourceLimits, sample: &MetricSample) -> Option<String> {
+    if let Some(max_mb) = limits.max_rss_mb {
+        let rss_mb = sample.memory_bytes / (1024 * 1024);
+        if rss_mb > max_mb {
+            return Some(format!("Killed for exceeding memory limit: RSS {rss_mb} MiB > max_rss_mb {max_mb} MiB"));
+        }
+    }
+    if let Some(max) = limits.max_processes {
+        if sample.process_count > max {
+            return Some(format!("Killed for exceeding process limit: {} processes > max_processes {max}", sample.process_count));
+        }
+    }
+    None
+}
+
+/// Best guess at which kernel-enforced limit ended a process, from its exit status and recent output
+pub fn diagnose(limits: &ResourceLimits, status: &ExitStatus, output_tail: &str) -> Option<String> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(secs) = limits.max_cpu_seconds {
+            if status.signal() == Some(nix::sys::signal::Signal::SIGXCPU as i32) {
+                return Some(format!("hit CPU time limit (max_cpu_seconds {secs})"));
+            }
+        }
+    }
+    let tail = output_tail.to_lowercase();
+    if let Some(n) = limits.max_open_files {
+        if tail.contains("emfile") || tail.contains("too many open files") {
+            return Some(format!("hit open files limit (max_open_files {n})"));
+        }
+    }
+    if let Some(mb) = limits.max_address_space_mb {
+        let oom = ["out of memory", "cannot allocate memory", "enomem", "memoryerror", "bad_alloc", "allocation failed"];
+        if oom.iter().any(|needle| tail.contains(needle)) {
+            return Some(format!("hit address space limit (max_address_space_mb {mb})"));
+        }
+    }
+    let _ = status;
+    None
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    /// Test kernel limits are visible to the child
+    #[test]
+    fn test_apply_sets_rlimits() {
+        let limits = ResourceLimits { max_address_space_mb: Some(4096), max_cpu_seconds: Some(30), max_open_files: Some(64), ..Default::default() };
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "ulimit -n; ulimit -t; ulimit -v"]);
+        apply(&mut cmd, &limits);
+        let out = cmd.output().unwrap();
+        let text = String::from_utf8_lossy(&out.stdout);
+        assert_eq!(text.lines().collect::<Vec<_>>(), vec!["64", "30", &(4096 * 1024).to_string()]);
+    }
+
+    /// Test the supervisor-enforced limits
+    #[test]
+    fn test_check_usage() {
+        let sample = MetricSample { timestamp: String::new(), memory_bytes: 600 * 1024 * 1024, cpu_percent: 0.0, process_count: 4 };
+        assert!(check_usage(&ResourceLimits::default(), &sample).is_none());
+        let rss = ResourceLimits { max_rss_mb: Some(512), ..Default::default() };
+        assert!(check_usage(&rss, &sample).unwrap().contains("max_rss_mb 512"));
+        let procs = ResourceLimits { max_processes: Some(3), ..Default::default() };
+        assert!(check_usage(&procs, &sample).unwrap().contains("4 processes"));
+    }
+
+    /// Test exits are attributed to the limit that caused them
+    #[test]
+    fn test_diagnose() {
+        use std::os::unix::process::ExitStatusExt;
+        let limits = ResourceLimits { max_cpu_seconds: Some(1), max_open_files: Some(16), ..Default::default() };
+        let xcpu = ExitStatus::from_raw(nix::sys::signal::Signal::SIGXCPU as i32);
+        assert!(diagnose(&limits, &xcpu, "").unwrap().contains("CPU time"));
+        let exited = ExitStatus::from_raw(1 << 8);
+        assert!(diagnose(&limits, &exited, "Error: EMFILE: too many open files").unwrap().contains("open files"));
+        assert!(diagnose(&limits, &exited, "FATAL ERROR: heap out of memory").is_none());
+        assert!(diagnose(&ResourceLimits::default(), &xcpu, "").is_none());
+    }
+}
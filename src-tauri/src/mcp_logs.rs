@@ -0,0 +1,532 @@
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock, RwLock},
+    thread::JoinHandle,
+    time::{Duration, SystemTime},
+};
+use tauri::{AppHandle, Manager};
+
+use crate::mcp_output::OutputStream;
+use crate::storage;
+
+/// Directory under the app data dir holding one subdirectory of logs per server
+pub const LOGS_DIR: &str = "logs";
+/// File currently being written; rotated files are named after their rotation time
+const CURRENT_LOG: &str = "current.log";
+/// Original server id, since directory names are sanitized and hashed
+const SERVER_ID_FILE: &str = ".server_id";
+
+/// Rotation and retention of the per-server log files
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LogRotation {
+    /// Write server output to log files at all
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Rotate once the current file reaches this size
+    #[serde(default = "default_max_file_bytes")]
+    pub max_file_bytes: u64,
+    /// Rotate once the current file is this old, even if small
+    #[serde(default = "default_rotate_after_hours")]
+    pub rotate_after_hours: u64,
+    /// Rotated files kept per server
+    #[serde(default = "default_max_files")]
+    pub max_files: usize,
+    /// Rotated files older than this are deleted
+    #[serde(default = "default_max_age_days")]
+    pub max_age_days: u64,
+    /// Gzip rotated files
+    #[serde(default = "default_true")]
+    pub compress: bool,
+}
+
+fn default_true() -> bool { true }
+fn default_max_file_bytes() -> u64 { 5 * 1024 * 1024 }
+fn default_rotate_after_hours() -> u64 { 24 }
+fn default_max_files() -> usize { 10 }
+fn default_max_age_days() -> u64 { 14 }
+
+impl Default for LogRotation {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_file_bytes: default_max_file_bytes(),
+            rotate_after_hours: default_rotate_after_hours(),
+            max_files: default_max_files(),
+            max_age_days: default_max_age_days(),
+            compress: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LogFileInfo {
+    pub server_id: String,
+    /// File name within the server's log directory, used with `mcp_read_log`
+    pub name: String,
+    pub size: u64,
+    pub modified: Option<String>,
+    pub compressed: bool,
+    /// The file currently being written
+    pub current: bool,
+}
+
+/// Append-only log for one server with size/age based rotation
+#[derive(Debug)]
+pub struct RotatingLog {
+    dir: PathBuf,
+    file: Option<File>,
+    size: u64,
+    opened: SystemTime,
+    /// Compression and retention of the last rotated file, run off the writing thread
+    pending: Option<JoinHandle<()>>,
+}
+
+impl RotatingLog {
+    pub fn open(dir: PathBuf) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir, file: None, size: 0, opened: SystemTime::now(), pending: None })
+    }
+
+    fn current_path(&self) -> PathBuf {
+        self.dir.join(CURRENT_LOG)
+    }
+
+    fn ensure_open(&mut self) -> io::Result<&mut File> {
+        if self.file.is_none() {
+            let path = self.current_path();
+            let file = OpenOptions::new().create(true).append(true).open(&path)?;
+            let meta = file.metadata()?;
+            self.size = meta.len();
+            // A file left by a previous session keeps aging from its creation
+            self.opened = meta.created().or_else(|_| meta.modified()).unwrap_or_else(|_| SystemTime::now());
+            self.file = Some(file);
+        }
+        Ok(self.file.as_mut().expect("log file was just opened"))
+    }
+
+    pub fn write_line(&mut self, line: &str, rotation: &LogRotation) -> io::Result<()> {
+        self.ensure_open()?;
+        let too_big = self.size > 0 && self.size + line.len() as u64 + 1 > rotation.max_file_bytes;
+        let too_old = self.opened.elapsed().unwrap_or_default() >= Duration::from_secs(rotation.rotate_after_hours * 3600);
+        if too_big || (too_old && self.size > 0) {
+            self.rotate(rotation)?;
+        }
+        let file = self.ensure_open()?;
+        file.write_all(line.as_bytes())?;
+        file.write_all(b"\n")?;
+        self.size += line.len() as u64 + 1;
+        Ok(())
+    }
+
+    /// Move the current file aside, then gzip it (if configured) and apply retention in the background
+    pub fn rotate(&mut self, rotation: &LogRotation) -> io::Result<()> {
+        self.file = None;
+        let current = self.current_path();
+        let rotated = if current.exists() {
+            let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ").to_string();
+            // Rotations within the same millisecond get a counter; `_` sorts after the `.` of
+            // the plain name, so names stay in rotation order
+            let taken = |name: &str| [name.to_string(), format!("{name}.gz")].iter().any(|n| self.dir.join(n).exists());
+            let mut name = format!("{stamp}.log");
+            let mut n = 1;
+            while taken(&name) {
+                name = format!("{stamp}_{n}.log");
+                n += 1;
+            }
+            let rotated = self.dir.join(name);
+            fs::rename(&current, &rotated)?;
+            Some(rotated)
+        } else {
+            None
+        };
+        self.size = 0;
+        self.opened = SystemTime::now();
+        // One job at a time per server, so retention never counts a file mid-compression
+        self.wait_pending();
+        let dir = self.dir.clone();
+        let (compress_file, keep, max_age) = (rotated.filter(|_| rotation.compress), rotation.max_files, Duration::from_secs(rotation.max_age_days * 86_400));
+        self.pending = Some(std::thread::spawn(move || {
+            if let Some(path) = compress_file {
+                if let Err(e) = compress(&path) {
+                    log::warn!("Failed to compress {}: {e}", path.display());
+                }
+            }
+            if let Err(e) = prune(&dir, keep, Some(max_age)) {
+                log::warn!("Failed to prune logs in {}: {e}", dir.display());
+            }
+        }));
+        Ok(())
+    }
+
+    /// Wait for the background work of the last rotation
+    pub fn wait_pending(&mut self) {
+        if let Some(handle) = self.pending.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Gzip `path` next to itself; the `.gz` only appears once complete
+fn compress(path: &Path) -> io::Result<()> {
+    let mut gz_name = path.as_os_str().to_owned();
+    gz_name.push(".gz");
+    let mut tmp_name = gz_name.clone();
+    tmp_name.push(".tmp");
+    let mut encoder = GzEncoder::new(File::create(&tmp_name)?, Compression::default());
+    io::copy(&mut File::open(path)?, &mut encoder)?;
+    encoder.finish()?;
+    fs::rename(&tmp_name, &gz_name)?;
+    fs::remove_file(path)
+}
+
+/// Rotated files in a server's log directory, oldest first (names sort chronologically)
+fn rotated_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.file_name().and_then(|n| n.to_str()).is_some_and(|n| n != CURRENT_LOG && (n.ends_with(".log") || n.ends_with(".log.gz"))))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Delete rotated files beyond the newest `keep`, and those older than `max_age`; returns how many were deleted
+fn prune(dir: &Path, keep: usize, max_age: Option<Duration>) -> io::Result<usize> {
+    let files = rotated_files(dir)?;
+    let excess = files.len().saturating_sub(keep);
+    let mut deleted = 0;
+    for (i, path) in files.iter().enumerate() {
+        let expired = max_age.is_some_and(|age| fs::metadata(path).and_then(|m| m.modified()).ok().and_then(|t| t.elapsed().ok()).is_some_and(|e| e >= age));
+        if i < excess || expired {
+            match fs::remove_file(path) {
+                Ok(()) => deleted += 1,
+                // Already gone, e.g. compressed by a rotation in the background
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+    Ok(deleted)
+}
+
+/// Read a log file, decompressing rotated gzip files
+fn read_file(path: &Path) -> io::Result<String> {
+    let mut bytes = Vec::new();
+    if path.extension().is_some_and(|ext| ext == "gz") {
+        GzDecoder::new(File::open(path)?).read_to_end(&mut bytes)?;
+    } else {
+        File::open(path)?.read_to_end(&mut bytes)?;
+    }
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Shared settings and the open logs; each log has its own lock so file I/O of one server
+/// never blocks the others
+struct LogState {
+    root: PathBuf,
+    rotation: LogRotation,
+    writers: HashMap<String, Arc<Mutex<RotatingLog>>>,
+}
+
+static LOGS: OnceLock<Mutex<Option<LogState>>> = OnceLock::new();
+
+fn logs() -> &'static Mutex<Option<LogState>> {
+    LOGS.get_or_init(|| Mutex::new(None))
+}
+
+/// Held shared by every write and exclusively by `with_writes_paused`, so no write can
+/// recreate a log directory while it is being deleted
+static WRITE_GATE: RwLock<()> = RwLock::new(());
+
+fn server_dir(root: &Path, server_id: &str) -> PathBuf {
+    root.join(storage::safe_file_name(server_id))
+}
+
+/// Open a server's log, recording its id next to the files
+fn open_server_log(root: &Path, server_id: &str) -> io::Result<RotatingLog> {
+    let log = RotatingLog::open(server_dir(root, server_id))?;
+    fs::write(log.dir.join(SERVER_ID_FILE), server_id)?;
+    Ok(log)
+}
+
+/// Start writing server output under `<app data>/logs`
+pub fn init(app: &AppHandle) {
+    let Ok(dir) = app.path().app_data_dir() else { return };
+    let rotation = crate::process_registry::load_settings(app).logs;
+    set_root(dir.join(LOGS_DIR), rotation);
+}
+
+/// Point the logs at `root`, closing any open files
+pub fn set_root(root: PathBuf, rotation: LogRotation) {
+    if let Ok(mut state) = logs().lock() {
+        *state = Some(LogState { root, rotation, writers: HashMap::new() });
+    }
+}
+
+/// Apply new rotation settings to subsequent writes
+pub fn configure(rotation: LogRotation) {
+    if let Ok(mut guard) = logs().lock() {
+        if let Some(state) = guard.as_mut() {
+            state.rotation = rotation;
+        }
+    }
+}
+
+/// Close every open log file and finish pending compression (before the log directory is deleted)
+fn close_all() {
+    let writers: Vec<_> = match logs().lock() {
+        Ok(mut guard) => guard.as_mut().map(|state| state.writers.drain().map(|(_, log)| log).collect()).unwrap_or_default(),
+        Err(_) => return,
+    };
+    for writer in writers {
+        if let Ok(mut log) = writer.lock() {
+            log.wait_pending();
+        }
+    }
+}
+
+/// Run `f` with every log closed and writes held back until it returns; output written in
+/// the meantime waits for it
+pub fn with_writes_paused<T>(f: impl FnOnce() -> T) -> T {
+    let _gate = WRITE_GATE.write();
+    close_all();
+    f()
+}
+
+/// Append a line of server output; a no-op until `init` has been called or when disabled
+pub fn write(server_id: &str, stream: OutputStream, timestamp: &str, line: &str) {
+    let _gate = WRITE_GATE.read();
+    let (root, rotation, writer) = {
+        let Ok(guard) = logs().lock() else { return };
+        let Some(state) = guard.as_ref() else { return };
+        if !state.rotation.enabled {
+            return;
+        }
+        (state.root.clone(), state.rotation.clone(), state.writers.get(server_id).cloned())
+    };
+    let writer = match writer {
+        Some(writer) => writer,
+        None => {
+            let log = match open_server_log(&root, server_id) {
+                Ok(log) => Arc::new(Mutex::new(log)),
+                Err(err) => {
+                    log::warn!("Failed to open log for {server_id}: {err}");
+                    return;
+                }
+            };
+            let Ok(mut guard) = logs().lock() else { return };
+            let Some(state) = guard.as_mut() else { return };
+            state.writers.entry(server_id.to_string()).or_insert(log).clone()
+        }
+    };
+    let Ok(mut log) = writer.lock() else { return };
+    let stream = if stream == OutputStream::Stdout { "stdout" } else { "stderr" };
+    if let Err(e) = log.write_line(&format!("{timestamp} [{stream}] {line}"), &rotation) {
+        log::warn!("Failed to write log for {server_id}: {e}");
+    }
+}
+
+fn log_root(app: &AppHandle) -> Result<PathBuf, String> {
+    let root = logs().lock().ok().and_then(|state| state.as_ref().map(|s| s.root.clone()));
+    match root {
+        Some(root) => Ok(root),
+        None => app.path().app_data_dir().map(|d| d.join(LOGS_DIR)).map_err(|e| format!("Failed to get app data directory: {}", e)),
+    }
+}
+
+/// Ids of the servers that have a log directory under `root`
+fn server_ids(root: &Path) -> Vec<String> {
+    fs::read_dir(root).map(|entries| entries.flatten().filter_map(|e| fs::read_to_string(e.path().join(SERVER_ID_FILE)).ok()).collect()).unwrap_or_default()
+}
+
+/// Log files of one server, oldest first with the current file last
+pub fn list_files(root: &Path, server_id: &str) -> Result<Vec<LogFileInfo>, String> {
+    let dir = server_dir(root, server_id);
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+    let mut paths = rotated_files(&dir).map_err(|e| format!("Failed to list logs: {e}"))?;
+    let current = dir.join(CURRENT_LOG);
+    if current.exists() {
+        paths.push(current);
+    }
+    Ok(paths
+        .into_iter()
+        .filter_map(|path| {
+            let meta = fs::metadata(&path).ok()?;
+            let name = path.file_name()?.to_string_lossy().to_string();
+            Some(LogFileInfo {
+                server_id: server_id.to_string(),
+                compressed: name.ends_with(".gz"),
+                current: name == CURRENT_LOG,
+                modified: meta.modified().ok().map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339()),
+                size: meta.len(),
+                name,
+            })
+        })
+        .collect())
+}
+
+/// Resolve a file name from `list_files`, refusing anything outside the server's log directory
+fn log_file(root: &Path, server_id: &str, name: &str) -> Result<PathBuf, String> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err(format!("Invalid log file name: {name}"));
+    }
+    let path = server_dir(root, server_id).join(name);
+    if !path.is_file() {
+        return Err(format!("No log file {name} for {server_id}"));
+    }
+    Ok(path)
+}
+
+/// Whole history of a server's logs as text, oldest first
+pub fn read_all(root: &Path, server_id: &str) -> Result<String, String> {
+    let mut text = String::new();
+    for info in list_files(root, server_id)? {
+        let path = log_file(root, server_id, &info.name)?;
+        text.push_str(&read_file(&path).map_err(|e| format!("Failed to read log {}: {e}", info.name))?);
+    }
+    Ok(text)
+}
+
+/// Delete a server's logs (all servers when `server_id` is None). With `older_than_days`,
+/// only rotated files older than that are removed. Returns the number of files deleted.
+pub fn purge(root: &Path, server_id: Option<&str>, older_than_days: Option<u64>) -> Result<usize, String> {
+    let dirs: Vec<PathBuf> = match server_id {
+        Some(id) => vec![server_dir(root, id)],
+        None => fs::read_dir(root).map(|entries| entries.flatten().map(|e| e.path()).filter(|p| p.is_dir()).collect()).unwrap_or_default(),
+    };
+    let mut deleted = 0;
+    for dir in dirs.into_iter().filter(|d| d.exists()) {
+        match older_than_days {
+            Some(days) => deleted += prune(&dir, usize::MAX, Some(Duration::from_secs(days * 86_400))).map_err(|e| format!("Failed to purge logs: {e}"))?,
+            None => {
+                deleted += rotated_files(&dir).map(|files| files.len()).unwrap_or(0) + usize::from(dir.join(CURRENT_LOG).exists());
+                fs::remove_dir_all(&dir).map_err(|e| format!("Failed to purge logs: {e}"))?;
+            }
+        }
+    }
+    Ok(deleted)
+}
+
+/// List log files, for one server or all of them
+#[tauri::command]
+pub fn mcp_list_logs(app: AppHandle, server_id: Option<String>) -> Result<Vec<LogFileInfo>, String> {
+    let root = log_root(&app)?;
+    let ids: Vec<String> = match server_id {
+        Some(id) => vec![id],
+        None => server_ids(&root),
+    };
+    let mut files = Vec::new();
+    for id in ids {
+        files.extend(list_files(&root, &id)?);
+    }
+    Ok(files)
+}
+
+/// Read one log file (by name from `mcp_list_logs`), or the current file; `tail` keeps the last lines
+#[tauri::command]
+pub fn mcp_read_log(app: AppHandle, server_id: String, name: Option<String>, tail: Option<usize>) -> Result<String, String> {
+    let root = log_root(&app)?;
+    let path = log_file(&root, &server_id, name.as_deref().unwrap_or(CURRENT_LOG))?;
+    let text = read_file(&path).map_err(|e| format!("Failed to read log: {e}"))?;
+    Ok(match tail {
+        Some(n) => {
+            let lines: Vec<&str> = text.lines().collect();
+            lines[lines.len().saturating_sub(n)..].join("\n")
+        }
+        None => text,
+    })
+}
+
+/// Write a server's complete log history, decompressed, to `destination`
+#[tauri::command]
+pub fn mcp_export_logs(app: AppHandle, server_id: String, destination: String) -> Result<(), String> {
+    let text = read_all(&log_root(&app)?, &server_id)?;
+    fs::write(&destination, text).map_err(|e| format!("Failed to export logs: {e}"))
+}
+
+/// Delete logs; see `purge`
+#[tauri::command]
+pub fn mcp_purge_logs(app: AppHandle, server_id: Option<String>, older_than_days: Option<u64>) -> Result<usize, String> {
+    let root = log_root(&app)?;
+    match older_than_days {
+        // Everything goes, including the files being written
+        None => with_writes_paused(|| purge(&root, server_id.as_deref(), None)),
+        Some(_) => purge(&root, server_id.as_deref(), older_than_days),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn rotation(max_file_bytes: u64, max_files: usize, compress: bool) -> LogRotation {
+        LogRotation { max_file_bytes, max_files, compress, ..Default::default() }
+    }
+
+    /// Test files rotate by size, are gzipped and capped in number
+    #[test]
+    fn test_rotation_by_size() {
+        let temp = TempDir::new().unwrap();
+        let dir = server_dir(temp.path(), "srv");
+        let mut log = RotatingLog::open(dir.clone()).unwrap();
+        let settings = rotation(20, 2, true);
+        for i in 0..5 {
+            // Several rotations land in the same millisecond
+            log.write_line(&format!("line number {i}"), &settings).unwrap();
+        }
+        log.wait_pending();
+        let files = list_files(temp.path(), "srv").unwrap();
+        let rotated: Vec<_> = files.iter().filter(|f| !f.current).collect();
+        assert_eq!(rotated.len(), 2);
+        assert!(rotated.iter().all(|f| f.compressed && f.name.ends_with(".log.gz")));
+        assert!(files.last().unwrap().current);
+
+        // Oldest kept file first, current last
+        let text = read_all(temp.path(), "srv").unwrap();
+        assert_eq!(text.lines().collect::<Vec<_>>(), vec!["line number 2", "line number 3", "line number 4"]);
+    }
+
+    /// Test file names cannot escape the server's log directory
+    #[test]
+    fn test_log_file_rejects_traversal() {
+        let temp = TempDir::new().unwrap();
+        let mut log = RotatingLog::open(server_dir(temp.path(), "srv")).unwrap();
+        log.write_line("x", &LogRotation::default()).unwrap();
+        assert!(log_file(temp.path(), "srv", CURRENT_LOG).is_ok());
+        assert!(log_file(temp.path(), "srv", "../srv/current.log").is_err());
+        assert!(log_file(temp.path(), "srv", "missing.log").is_err());
+    }
+
+    /// Test servers are listed by their original ids
+    #[test]
+    fn test_server_ids() {
+        let temp = TempDir::new().unwrap();
+        let mut log = open_server_log(temp.path(), "team/server").unwrap();
+        log.write_line("hello", &LogRotation::default()).unwrap();
+        assert_eq!(server_ids(temp.path()), vec!["team/server".to_string()]);
+        assert_eq!(list_files(temp.path(), "team/server").unwrap().len(), 1);
+    }
+
+    /// Test purging by age keeps recent files and full purges remove everything
+    #[test]
+    fn test_purge() {
+        let temp = TempDir::new().unwrap();
+        let mut log = RotatingLog::open(server_dir(temp.path(), "srv")).unwrap();
+        let settings = rotation(1, 10, false);
+        log.write_line("a", &settings).unwrap();
+        log.write_line("b", &settings).unwrap();
+        log.wait_pending();
+        assert_eq!(purge(temp.path(), Some("srv"), Some(1)).unwrap(), 0);
+        assert_eq!(purge(temp.path(), None, Some(0)).unwrap(), 1);
+        assert_eq!(purge(temp.path(), Some("srv"), None).unwrap(), 1);
+        assert!(list_files(temp.path(), "srv").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,322 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::{HashMap, VecDeque}, sync::{Mutex, OnceLock}, time::{Duration, Instant}};
+
+/// Samples kept per server (10 minutes at the supervisor's sampling interval)
+pub const HISTORY_LEN: usize = 120;
+/// How often the supervisor samples running servers in the background
+pub const SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+/// Sustained CPU usage (percent of one core) considered runaway
+const RUNAWAY_CPU_PERCENT: f32 = 90.0;
+/// Number of consecutive samples above `RUNAWAY_CPU_PERCENT` before flagging
+const RUNAWAY_CPU_SAMPLES: usize = 6;
+/// Resident memory considered runaway
+const RUNAWAY_MEMORY_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+/// Event emitted when a server is first flagged as runaway
+pub const RUNAWAY_EVENT: &str = "mcp-server-runaway";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricSample {
+    pub timestamp: String,
+    /// Resident set size of the server and all its descendants
+    pub memory_bytes: u64,
+    /// CPU usage of the process tree since the previous sample, in percent of one core
+    pub cpu_percent: f32,
+    pub process_count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerMetrics {
+    pub server_id: String,
+    pub pid: Option<u32>,
+    pub samples: Vec<MetricSample>,
+    pub runaway: bool,
+    pub runaway_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct RunawayEvent {
+    server_id: String,
+    reason: String,
+}
+
+#[derive(Debug)]
+struct Tracker {
+    pid: u32,
+    last_proc_ticks: u64,
+    last_total_ticks: u64,
+    last_at: Instant,
+    history: VecDeque<MetricSample>,
+    runaway_reason: Option<String>,
+}
+
+static TRACKERS: OnceLock<Mutex<HashMap<String, Tracker>>> = OnceLock::new();
+
+fn trackers() -> &'static Mutex<HashMap<String, Tracker>> {
+    TRACKERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Decide whether a history of samples looks like a runaway server
+fn detect_runaway(history: &VecDeque<MetricSample>) -> Option<String> {
+    let latest = history.back()?;
+    if latest.memory_bytes >= RUNAWAY_MEMORY_BYTES {
+        return Some(format!("Memory usage {} MiB exceeds {} MiB", latest.memory_bytes / (1024 * 1024), RUNAWAY_MEMORY_BYTES / (1024 * 1024)));
+    }
+    if history.len() >= RUNAWAY_CPU_SAMPLES && history.iter().rev().take(RUNAWAY_CPU_SAMPLES).all(|s| s.cpu_percent >= RUNAWAY_CPU_PERCENT) {
+        return Some(format!("CPU usage above {RUNAWAY_CPU_PERCENT}% for {RUNAWAY_CPU_SAMPLES} consecutive samples"));
+    }
+    None
+}
+
+/// Latest recorded sample of the process `pid`, without reading /proc or adding to the history
+pub fn latest(server_id: &str, pid: u32) -> Option<MetricSample> {
+    let map = trackers().lock().ok()?;
+    map.get(server_id).filter(|t| t.pid == pid)?.history.back().cloned()
+}
+
+/// Sample the process tree rooted at `pid` into the history, reusing the last sample if it is
+/// younger than `max_age`. Only the supervisor samples, so the history stays evenly spaced.
+pub fn sample(server_id: &str, pid: u32, max_age: Duration) -> Option<MetricSample> {
+    let mut map = trackers().lock().ok()?;
+    if let Some(t) = map.get(server_id) {
+        if t.pid == pid && t.last_at.elapsed() < max_age {
+            return t.history.back().cloned();
+        }
+    }
+
+    let reading = procfs::read_tree(pid)?;
+    let now = Instant::now();
+    let tracker = map.entry(server_id.to_string()).or_insert_with(|| Tracker {
+        pid,
+        last_proc_ticks: reading.proc_ticks,
+        last_total_ticks: reading.total_ticks,
+        last_at: now,
+        history: VecDeque::with_capacity(HISTORY_LEN),
+        runaway_reason: None,
+    });
+    if tracker.pid != pid {
+        // Server was restarted; start a fresh baseline but keep the history
+        tracker.pid = pid;
+        tracker.last_proc_ticks = reading.proc_ticks;
+        tracker.last_total_ticks = reading.total_ticks;
+    }
+
+    let d_proc = reading.proc_ticks.saturating_sub(tracker.last_proc_ticks) as f64;
+    let d_total = reading.total_ticks.saturating_sub(tracker.last_total_ticks) as f64;
+    let cpu_percent = if d_total > 0.0 { (d_proc / (d_total / reading.cpu_count as f64) * 100.0) as f32 } else { 0.0 };
+
+    let sample = MetricSample {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        memory_bytes: reading.rss_bytes,
+        cpu_percent,
+        process_count: reading.process_count,
+    };
+    tracker.last_proc_ticks = reading.proc_ticks;
+    tracker.last_total_ticks = reading.total_ticks;
+    tracker.last_at = now;
+    if tracker.history.len() == HISTORY_LEN {
+        tracker.history.pop_front();
+    }
+    tracker.history.push_back(sample.clone());
+
+    let reason = detect_runaway(&tracker.history);
+    if let (Some(r), None) = (&reason, &tracker.runaway_reason) {
+        log::warn!("Server {server_id} flagged as runaway: {r}");
+        crate::mcp_lifecycle::emit_event(RUNAWAY_EVENT, RunawayEvent { server_id: server_id.to_string(), reason: r.clone() });
+    }
+    tracker.runaway_reason = reason;
+    Some(sample)
+}
+
+/// Drop the sampling state of a server that is no longer managed
+pub fn forget(server_id: &str) {
+    if let Ok(mut map) = trackers().lock() {
+        map.remove(server_id);
+    }
+}
+
+/// Get the sample history and runaway flag for a server
+#[tauri::command]
+pub fn mcp_get_metrics(server_id: String) -> Result<ServerMetrics, String> {
+    let map = trackers().lock().map_err(|_| "Lock poisoned")?;
+    Ok(match map.get(&server_id) {
+        Some(t) => ServerMetrics {
+            server_id,
+            pid: Some(t.pid),
+            samples: t.history.iter().cloned().collect(),
+            runaway: t.runaway_reason.is_some(),
+            runaway_reason: t.runaway_reason.clone(),
+        },
+        None => ServerMetrics { server_id, pid: None, samples: vec![], runaway: false, runaway_reason: None },
+    })
+}
+
+/// Aggregated resource usage of a process tree at one instant
+#[derive(Debug)]
+struct TreeReading {
+    proc_ticks: u64,
+    total_ticks: u64,
+    cpu_count: u32,
+    rss_bytes: u64,
+    process_count: u32,
+}
+
+#[cfg(target_os = "linux")]
+mod procfs {
+    use super::TreeReading;
+    use std::{collections::HashMap, fs};
+
+    /// Parse `/proc/<pid>/stat` into (ppid, utime + stime)
+    pub(super) fn parse_stat(content: &str) -> Option<(u32, u64)> {
+        // comm may contain spaces and parentheses, so split after the last ')'
+        let rest = &content[content.rfind(')')? + 1..];
+        let fields: Vec<&str> = rest.split_whitespace().collect();
+        let ppid = fields.get(1)?.parse().ok()?;
+        let utime: u64 = fields.get(11)?.parse().ok()?;
+        let stime: u64 = fields.get(12)?.parse().ok()?;
+        Some((ppid, utime + stime))
+    }
+
+    /// Parse `/proc/stat` into (total jiffies across all CPUs, CPU count)
+    pub(super) fn parse_cpu_totals(content: &str) -> Option<(u64, u32)> {
+        let mut lines = content.lines();
+        let total = lines.next()?.strip_prefix("cpu ")?.split_whitespace().filter_map(|v| v.parse::<u64>().ok()).sum();
+        let cpus = content
+            .lines()
+            .filter(|l| l.strip_prefix("cpu").is_some_and(|r| r.starts_with(|c: char| c.is_ascii_digit())))
+            .count() as u32;
+        Some((total, cpus.max(1)))
+    }
+
+    fn rss_bytes(pid: u32) -> u64 {
+        fs::read_to_string(format!("/proc/{pid}/status"))
+            .ok()
+            .and_then(|s| {
+                s.lines()
+                    .find_map(|l| l.strip_prefix("VmRSS:"))
+                    .and_then(|v| v.split_whitespace().next()?.parse::<u64>().ok())
+            })
+            .map(|kb| kb * 1024)
+            .unwrap_or(0)
+    }
+
+    /// `root` followed by all its transitive children
+    pub(super) fn descendants(table: &HashMap<u32, (u32, u64)>, root: u32) -> Vec<u32> {
+        let mut out = vec![root];
+        let mut i = 0;
+        while i < out.len() {
+            let parent = out[i];
+            out.extend(table.iter().filter(|(pid, (ppid, _))| *ppid == parent && **pid != parent).map(|(pid, _)| *pid));
+            i += 1;
+        }
+        out
+    }
+
+    pub(super) fn read_tree(root: u32) -> Option<TreeReading> {
+        let (total_ticks, cpu_count) = parse_cpu_totals(&fs::read_to_string("/proc/stat").ok()?)?;
+        let mut table = HashMap::new();
+        for entry in fs::read_dir("/proc").ok()?.flatten() {
+            let Some(pid) = entry.file_name().to_str().and_then(|n| n.parse::<u32>().ok()) else { continue };
+            if let Some(stat) = fs::read_to_string(entry.path().join("stat")).ok().as_deref().and_then(parse_stat) {
+                table.insert(pid, stat);
+            }
+        }
+        if !table.contains_key(&root) {
+            return None;
+        }
+        let tree = descendants(&table, root);
+        Some(TreeReading {
+            proc_ticks: tree.iter().filter_map(|p| table.get(p)).map(|(_, t)| t).sum(),
+            total_ticks,
+            cpu_count,
+            rss_bytes: tree.iter().map(|p| rss_bytes(*p)).sum(),
+            process_count: tree.len() as u32,
+        })
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod procfs {
+    use super::TreeReading;
+
+    pub(super) fn read_tree(_root: u32) -> Option<TreeReading> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_with(cpu: f32, mem: u64) -> MetricSample {
+        MetricSample { timestamp: "t".into(), memory_bytes: mem, cpu_percent: cpu, process_count: 1 }
+    }
+
+    /// Test runaway detection for sustained CPU and excessive memory
+    #[test]
+    fn test_detect_runaway() {
+        let mut history = VecDeque::new();
+        assert!(detect_runaway(&history).is_none());
+
+        for _ in 0..RUNAWAY_CPU_SAMPLES - 1 {
+            history.push_back(sample_with(99.0, 1024));
+        }
+        assert!(detect_runaway(&history).is_none());
+        history.push_back(sample_with(99.0, 1024));
+        assert!(detect_runaway(&history).unwrap().contains("CPU"));
+
+        history.push_back(sample_with(5.0, 1024));
+        assert!(detect_runaway(&history).is_none());
+        history.push_back(sample_with(5.0, RUNAWAY_MEMORY_BYTES));
+        assert!(detect_runaway(&history).unwrap().contains("Memory"));
+    }
+
+    /// Test metrics for an unknown server are empty
+    #[test]
+    fn test_get_metrics_unknown_server() {
+        let metrics = mcp_get_metrics("no-such-server".into()).unwrap();
+        assert!(metrics.samples.is_empty());
+        assert!(!metrics.runaway);
+        assert!(metrics.pid.is_none());
+    }
+
+    /// Test parsing /proc/<pid>/stat with a tricky comm field
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_parse_stat() {
+        let stat = "1234 (my (weird) proc) S 42 1234 1234 0 -1 4194560 100 0 0 0 250 50 0 0 20 0 1 0 100 0 0";
+        assert_eq!(procfs::parse_stat(stat), Some((42, 300)));
+        assert_eq!(procfs::parse_stat("garbage"), None);
+    }
+
+    /// Test parsing /proc/stat totals and CPU count
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_parse_cpu_totals() {
+        let stat = "cpu  10 20 30 40\ncpu0 5 10 15 20\ncpu1 5 10 15 20\nintr 1 2 3\n";
+        assert_eq!(procfs::parse_cpu_totals(stat), Some((100, 2)));
+    }
+
+    /// Test descendant discovery walks the whole tree
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_descendants() {
+        let table: HashMap<u32, (u32, u64)> = [(10, (1, 0)), (11, (10, 0)), (12, (11, 0)), (13, (1, 0))].into_iter().collect();
+        let mut tree = procfs::descendants(&table, 10);
+        tree.sort();
+        assert_eq!(tree, vec![10, 11, 12]);
+    }
+
+    /// Test sampling the current process yields a non-zero RSS
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_sample_self() {
+        let pid = std::process::id();
+        let first = sample("metrics-self-test", pid, Duration::ZERO).unwrap();
+        assert!(first.memory_bytes > 0);
+        assert!(first.process_count >= 1);
+        sample("metrics-self-test", pid, Duration::ZERO).unwrap();
+        assert_eq!(mcp_get_metrics("metrics-self-test".into()).unwrap().samples.len(), 2);
+        forget("metrics-self-test");
+        assert!(mcp_get_metrics("metrics-self-test".into()).unwrap().samples.is_empty());
+    }
+}
@@ -0,0 +1,325 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::{HashMap, VecDeque}, io::{BufRead, BufReader, Read}, sync::{Mutex, OnceLock}, thread::JoinHandle};
+
+/// Number of lines retained per server before the oldest are dropped
+pub const DEFAULT_OUTPUT_CAPACITY: usize = 2000;
+/// Longer lines are truncated before being stored or emitted
+const MAX_LINE_BYTES: usize = 8 * 1024;
+/// Event emitted to the frontend for every captured line
+pub const OUTPUT_EVENT: &str = "mcp-server-output";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputLine {
+    /// Monotonic per-server sequence number, usable as a cursor for range queries
+    pub seq: u64,
+    pub timestamp: String,
+    pub stream: OutputStream,
+    pub line: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OutputEvent {
+    pub server_id: String,
+    #[serde(flatten)]
+    pub line: OutputLine,
+}
+
+/// Fixed-capacity ring buffer of recent output lines for one server
+#[derive(Debug)]
+pub struct OutputBuffer {
+    lines: VecDeque<OutputLine>,
+    capacity: usize,
+    next_seq: u64,
+}
+
+impl OutputBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self { lines: VecDeque::with_capacity(capacity.min(256)), capacity: capacity.max(1), next_seq: 0 }
+    }
+
+    pub fn push(&mut self, stream: OutputStream, line: String) -> OutputLine {
+        let entry = OutputLine { seq: self.next_seq, timestamp: chrono::Utc::now().to_rfc3339(), stream, line };
+        self.next_seq += 1;
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(entry.clone());
+        entry
+    }
+
+    /// Last `n` lines, oldest first
+    pub fn tail(&self, n: usize) -> Vec<OutputLine> {
+        let skip = self.lines.len().saturating_sub(n);
+        self.lines.iter().skip(skip).cloned().collect()
+    }
+
+    /// Up to `limit` lines with `seq >= from_seq`, oldest first
+    pub fn range(&self, from_seq: u64, limit: usize) -> Vec<OutputLine> {
+        self.lines.iter().filter(|l| l.seq >= from_seq).take(limit).cloned().collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+}
+
+static OUTPUTS: OnceLock<Mutex<HashMap<String, OutputBuffer>>> = OnceLock::new();
+
+fn outputs() -> &'static Mutex<HashMap<String, OutputBuffer>> {
+    OUTPUTS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn truncate_line(mut line: String) -> String {
+    if line.len() > MAX_LINE_BYTES {
+        let mut cut = MAX_LINE_BYTES;
+        while !line.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        line.truncate(cut);
+        line.push_str("…[truncated]");
+    }
+    line
+}
+
+/// Read through the next newline into `buf`, keeping only the first `MAX_LINE_BYTES + 1`
+/// bytes (enough for `truncate_line` to see the overflow) so an endless line can't grow
+/// memory. Returns the number of bytes consumed, 0 at end of stream.
+fn read_capped_line(reader: &mut impl BufRead, buf: &mut Vec<u8>) -> std::io::Result<usize> {
+    let mut consumed = 0;
+    loop {
+        let available = match reader.fill_buf() {
+            Ok(available) => available,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        if available.is_empty() {
+            return Ok(consumed);
+        }
+        let newline = available.iter().position(|&b| b == b'\n');
+        let len = newline.map_or(available.len(), |i| i + 1);
+        let room = (MAX_LINE_BYTES + 1).saturating_sub(buf.len());
+        buf.extend_from_slice(&available[..len.min(room)]);
+        reader.consume(len);
+        consumed += len;
+        if newline.is_some() {
+            return Ok(consumed);
+        }
+    }
+}
+
+/// Store a line in the server's buffer and push it to the frontend
+pub fn record(server_id: &str, stream: OutputStream, line: String) -> Option<OutputLine> {
+    let entry = {
+        let mut map = outputs().lock().ok()?;
+        map.entry(server_id.to_string())
+            .or_insert_with(|| OutputBuffer::new(DEFAULT_OUTPUT_CAPACITY))
+            .push(stream, truncate_line(line))
+    };
+    crate::mcp_logs::write(server_id, stream, &entry.timestamp, &entry.line);
+    crate::mcp_lifecycle::emit_event(OUTPUT_EVENT, OutputEvent { server_id: server_id.to_string(), line: entry.clone() });
+    Some(entry)
+}
+
+/// Drain a child pipe on a background thread so the server never blocks on a full pipe
+pub fn attach<R: Read + Send + 'static>(server_id: String, stream: OutputStream, reader: R) -> std::io::Result<JoinHandle<()>> {
+    attach_with(server_id, stream, reader, |_| {})
+}
+
+/// Like `attach`, but also hands every line to `on_line`, followed by `None` once the pipe closes
+pub fn attach_with<R, F>(server_id: String, stream: OutputStream, reader: R, mut on_line: F) -> std::io::Result<JoinHandle<()>>
+where
+    R: Read + Send + 'static,
+    F: FnMut(Option<&str>) + Send + 'static,
+{
+    std::thread::Builder::new()
+        .name(format!("mcp-{}-{}", server_id, if stream == OutputStream::Stdout { "stdout" } else { "stderr" }))
+        .spawn(move || {
+            let mut reader = BufReader::new(reader);
+            let mut buf = Vec::new();
+            loop {
+                buf.clear();
+                match read_capped_line(&mut reader, &mut buf) {
+                    Ok(0) => break,
+                    Ok(_) => {
+                        let text = String::from_utf8_lossy(&buf);
+                        let text = text.trim_end_matches(['\n', '\r']);
+                        on_line(Some(text));
+                        record(&server_id, stream, text.to_string());
+                    }
+                    Err(e) => {
+                        log::debug!("Output reader for {server_id} stopped: {e}");
+                        break;
+                    }
+                }
+            }
+            on_line(None);
+        })
+}
+
+/// Recent output joined as text, used for `MCPServerProcess.output`
+pub fn tail_text(server_id: &str, n: usize) -> Option<String> {
+    let map = outputs().lock().ok()?;
+    let buf = map.get(server_id)?;
+    if buf.is_empty() {
+        return None;
+    }
+    Some(buf.tail(n).into_iter().map(|l| l.line).collect::<Vec<_>>().join("\n"))
+}
+
+/// Last `n` lines of one stream, oldest first
+pub fn tail_lines(server_id: &str, stream: OutputStream, n: usize) -> Vec<String> {
+    let Ok(map) = outputs().lock() else { return vec![] };
+    let Some(buf) = map.get(server_id) else { return vec![] };
+    let mut lines: Vec<String> = buf.lines.iter().rev().filter(|l| l.stream == stream).take(n).map(|l| l.line.clone()).collect();
+    lines.reverse();
+    lines
+}
+
+/// Get buffered output for a server: the last `tail` lines (default 200), or
+/// up to `limit` lines starting at sequence number `from_seq`
+#[tauri::command]
+pub fn mcp_get_output(server_id: String, tail: Option<usize>, from_seq: Option<u64>, limit: Option<usize>) -> Result<Vec<OutputLine>, String> {
+    let map = outputs().lock().map_err(|_| "Lock poisoned")?;
+    let Some(buf) = map.get(&server_id) else { return Ok(vec![]) };
+    Ok(match from_seq {
+        Some(from) => buf.range(from, limit.unwrap_or(DEFAULT_OUTPUT_CAPACITY)),
+        None => buf.tail(tail.unwrap_or(200)),
+    })
+}
+
+/// Discard buffered output for a server
+#[tauri::command]
+pub fn mcp_clear_output(server_id: String) -> Result<(), String> {
+    if let Some(buf) = outputs().lock().map_err(|_| "Lock poisoned")?.get_mut(&server_id) {
+        buf.clear();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test ring buffer drops the oldest lines when full
+    #[test]
+    fn test_buffer_evicts_oldest() {
+        let mut buf = OutputBuffer::new(3);
+        for i in 0..5 {
+            buf.push(OutputStream::Stdout, format!("line {i}"));
+        }
+        let lines = buf.tail(10);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].line, "line 2");
+        assert_eq!(lines[0].seq, 2);
+        assert_eq!(lines[2].seq, 4);
+    }
+
+    /// Test tail and range queries
+    #[test]
+    fn test_buffer_tail_and_range() {
+        let mut buf = OutputBuffer::new(10);
+        for i in 0..6 {
+            let stream = if i % 2 == 0 { OutputStream::Stdout } else { OutputStream::Stderr };
+            buf.push(stream, format!("l{i}"));
+        }
+        let tail = buf.tail(2);
+        assert_eq!(tail.len(), 2);
+        assert_eq!(tail[1].line, "l5");
+        assert_eq!(tail[1].stream, OutputStream::Stderr);
+
+        let range = buf.range(2, 3);
+        assert_eq!(range.iter().map(|l| l.seq).collect::<Vec<_>>(), vec![2, 3, 4]);
+        assert!(buf.range(100, 5).is_empty());
+    }
+
+    /// Test per-stream tails skip the other stream
+    #[test]
+    fn test_tail_lines_by_stream() {
+        for (i, stream) in [OutputStream::Stderr, OutputStream::Stdout, OutputStream::Stderr, OutputStream::Stderr].into_iter().enumerate() {
+            record("tail-lines-test", stream, format!("l{i}"));
+        }
+        assert_eq!(tail_lines("tail-lines-test", OutputStream::Stderr, 2), vec!["l2", "l3"]);
+        assert_eq!(tail_lines("tail-lines-test", OutputStream::Stdout, 5), vec!["l1"]);
+        assert!(tail_lines("tail-lines-missing", OutputStream::Stderr, 5).is_empty());
+    }
+
+    /// Test long lines are truncated on a char boundary
+    #[test]
+    fn test_truncate_line() {
+        let long = "é".repeat(MAX_LINE_BYTES);
+        let out = truncate_line(long);
+        assert!(out.ends_with("[truncated]"));
+        assert!(out.len() < MAX_LINE_BYTES + 32);
+        assert_eq!(truncate_line("short".into()), "short");
+    }
+
+    /// Test a line is cut at the limit while it is read, and the next line is intact
+    #[test]
+    fn test_read_capped_line() {
+        let data = format!("{}\nnext\n", "x".repeat(MAX_LINE_BYTES * 4));
+        let mut reader = BufReader::with_capacity(1024, data.as_bytes());
+        let mut buf = Vec::new();
+        assert_eq!(read_capped_line(&mut reader, &mut buf).unwrap(), MAX_LINE_BYTES * 4 + 1);
+        assert_eq!(buf.len(), MAX_LINE_BYTES + 1);
+        assert!(truncate_line(String::from_utf8(buf).unwrap()).ends_with("[truncated]"));
+
+        let mut buf = Vec::new();
+        read_capped_line(&mut reader, &mut buf).unwrap();
+        assert_eq!(buf, b"next\n");
+        let mut buf = Vec::new();
+        assert_eq!(read_capped_line(&mut reader, &mut buf).unwrap(), 0);
+    }
+
+    /// Test OutputStream serialization format (lowercase)
+    #[test]
+    fn test_output_stream_serde() {
+        assert_eq!(serde_json::to_string(&OutputStream::Stderr).unwrap(), "\"stderr\"");
+        let ev = OutputEvent {
+            server_id: "s".into(),
+            line: OutputLine { seq: 1, timestamp: "t".into(), stream: OutputStream::Stdout, line: "hi".into() },
+        };
+        let json = serde_json::to_value(&ev).unwrap();
+        assert_eq!(json["server_id"], "s");
+        assert_eq!(json["line"], "hi");
+        assert_eq!(json["stream"], "stdout");
+    }
+
+    /// Test a reader thread drains a pipe into the buffer
+    #[test]
+    fn test_attach_reads_lines() {
+        let data: &'static [u8] = b"first\r\nsecond\nno-newline";
+        attach("attach-test".into(), OutputStream::Stderr, data).unwrap().join().unwrap();
+        let lines = mcp_get_output("attach-test".into(), None, None, None).unwrap();
+        assert_eq!(lines.iter().map(|l| l.line.as_str()).collect::<Vec<_>>(), vec!["first", "second", "no-newline"]);
+        assert_eq!(tail_text("attach-test", 2).as_deref(), Some("second\nno-newline"));
+
+        mcp_clear_output("attach-test".into()).unwrap();
+        assert!(tail_text("attach-test", 2).is_none());
+    }
+
+    /// Test the line hook sees every line and then the end of the stream
+    #[test]
+    fn test_attach_with_hook() {
+        let data: &'static [u8] = b"a\nb\n";
+        let (tx, rx) = std::sync::mpsc::channel();
+        attach_with("hook-test".into(), OutputStream::Stdout, data, move |line| {
+            tx.send(line.map(str::to_string)).unwrap();
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+        let seen: Vec<Option<String>> = rx.try_iter().collect();
+        assert_eq!(seen, vec![Some("a".to_string()), Some("b".to_string()), None]);
+    }
+}
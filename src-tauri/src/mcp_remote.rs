@@ -0,0 +1,582 @@
+use reqwest::blocking::{Client, Response};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, CONTENT_TYPE};
+use reqwest::{StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::mcp_lifecycle::{self, ExitInfo, HealthCheckConfig, HealthStatus, LifecycleState, MCPServerProcess};
+use crate::{mcp_rpc, secure_storage};
+
+const SESSION_HEADER: &str = "mcp-session-id";
+const PROTOCOL_HEADER: &str = "mcp-protocol-version";
+/// Probe interval used when the config sets `ping_interval_ms` to 0
+const DEFAULT_PROBE_INTERVAL: Duration = Duration::from_secs(30);
+/// Time allowed for the best-effort `DELETE` that ends a Streamable HTTP session
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// HTTP transports a remote MCP server can be reached over
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RemoteTransport {
+    /// Legacy HTTP+SSE: a GET event stream plus a POST endpoint announced on it
+    #[default]
+    Sse,
+    /// Streamable HTTP: every message is a POST to the same endpoint
+    Http,
+}
+
+/// How to reach and probe a remote server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteConfig {
+    pub url: String,
+    #[serde(default)]
+    pub transport: RemoteTransport,
+    /// Sent with every request; values may reference stored secrets as `${keyring:<key>}`
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// `startup_timeout_ms` bounds the `initialize` handshake, `ping_interval_ms` the time between
+    /// probes, and `max_ping_failures` the failed probes after which the server is reported in error
+    #[serde(default)]
+    pub health: HealthCheckConfig,
+}
+
+struct RemoteEntry {
+    state: MCPServerProcess,
+    /// Set to end the monitor thread; also identifies which monitor owns the entry
+    stop: Arc<AtomicBool>,
+    reachable_since: Option<Instant>,
+}
+
+static REMOTES: OnceLock<Mutex<HashMap<String, RemoteEntry>>> = OnceLock::new();
+
+fn remotes() -> &'static Mutex<HashMap<String, RemoteEntry>> {
+    REMOTES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// A JSON-RPC session with a remote server over either transport
+struct Session {
+    client: Client,
+    url: Url,
+    transport: RemoteTransport,
+    headers: HeaderMap,
+    next_id: u64,
+    /// Assigned by a Streamable HTTP server in response to `initialize`
+    session_id: Option<String>,
+    /// Open event stream of a legacy SSE server and the endpoint it announced
+    stream: Option<(BufReader<Response>, Url)>,
+    initialized: bool,
+}
+
+impl Session {
+    fn new(cfg: &RemoteConfig) -> Result<Self, String> {
+        let url = Url::parse(&cfg.url).map_err(|e| format!("Invalid URL {}: {e}", cfg.url))?;
+        let mut headers = HeaderMap::new();
+        for (name, value) in secure_storage::resolve_env(&cfg.headers)? {
+            let name = HeaderName::from_bytes(name.as_bytes()).map_err(|e| format!("Invalid header name {name}: {e}"))?;
+            let value = HeaderValue::from_str(&value).map_err(|e| format!("Invalid value for header {name}: {e}"))?;
+            headers.insert(name, value);
+        }
+        let client = Client::builder().build().map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+        Ok(Self { client, url, transport: cfg.transport, headers, next_id: 1, session_id: None, stream: None, initialized: false })
+    }
+
+    /// POST a JSON-RPC message, remembering the session id a Streamable HTTP server hands out
+    fn post(&mut self, url: Url, message: &Value, timeout: Duration) -> Result<Response, String> {
+        let mut request = self
+            .client
+            .post(url)
+            .headers(self.headers.clone())
+            .header(CONTENT_TYPE, "application/json")
+            .header(ACCEPT, "application/json, text/event-stream")
+            .timeout(timeout)
+            .json(message);
+        if let Some(session) = &self.session_id {
+            request = request.header(SESSION_HEADER, session);
+        }
+        if self.initialized {
+            request = request.header(PROTOCOL_HEADER, mcp_rpc::PROTOCOL_VERSION);
+        }
+        let response = request.send().map_err(|e| format!("Request failed: {e}"))?;
+        let status = response.status();
+        if status == StatusCode::NOT_FOUND && self.session_id.is_some() {
+            return Err("Session expired".to_string());
+        }
+        if !status.is_success() {
+            return Err(format!("HTTP {status}"));
+        }
+        if let Some(session) = response.headers().get(SESSION_HEADER).and_then(|v| v.to_str().ok()) {
+            self.session_id = Some(session.to_string());
+        }
+        Ok(response)
+    }
+
+    /// Open the event stream of a legacy SSE server and wait for its message endpoint
+    fn open_stream(&mut self, timeout: Duration) -> Result<(), String> {
+        let response = self
+            .client
+            .get(self.url.clone())
+            .headers(self.headers.clone())
+            .header(ACCEPT, "text/event-stream")
+            .timeout(timeout)
+            .send()
+            .map_err(|e| format!("Failed to open event stream: {e}"))?;
+        if !response.status().is_success() {
+            return Err(format!("HTTP {}", response.status()));
+        }
+        let mut reader = BufReader::new(response);
+        while let Some((event, data)) = next_event(&mut reader)? {
+            if event == "endpoint" {
+                let endpoint = self.url.join(data.trim()).map_err(|e| format!("Invalid endpoint {data}: {e}"))?;
+                self.stream = Some((reader, endpoint));
+                return Ok(());
+            }
+        }
+        Err("Event stream closed before announcing an endpoint".to_string())
+    }
+
+    fn request(&mut self, method: &str, params: Value, timeout: Duration) -> Result<Value, String> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let mut message = json!({ "jsonrpc": "2.0", "id": id, "method": method });
+        if !params.is_null() {
+            message["params"] = params;
+        }
+        match self.transport {
+            RemoteTransport::Http => {
+                let response = self.post(self.url.clone(), &message, timeout)?;
+                let is_stream = response.headers().get(CONTENT_TYPE).and_then(|v| v.to_str().ok()).is_some_and(|v| v.starts_with("text/event-stream"));
+                if is_stream {
+                    let mut reader = BufReader::new(response);
+                    while let Some((_, data)) = next_event(&mut reader)? {
+                        if let Some(reply) = serde_json::from_str(&data).ok().and_then(|msg| take_reply(&msg, id)) {
+                            return reply;
+                        }
+                    }
+                    return Err(format!("No response to {method}"));
+                }
+                let body: Value = response.json().map_err(|e| format!("Invalid response to {method}: {e}"))?;
+                take_reply(&body, id).unwrap_or_else(|| Err(format!("No response to {method}")))
+            }
+            RemoteTransport::Sse => {
+                if self.stream.is_none() {
+                    self.open_stream(timeout)?;
+                }
+                let endpoint = self.stream.as_ref().map(|(_, endpoint)| endpoint.clone()).ok_or("No event stream")?;
+                self.post(endpoint, &message, timeout)?;
+                let (reader, _) = self.stream.as_mut().ok_or("No event stream")?;
+                while let Some((event, data)) = next_event(reader)? {
+                    if event != "message" {
+                        continue;
+                    }
+                    if let Some(reply) = serde_json::from_str(&data).ok().and_then(|msg| take_reply(&msg, id)) {
+                        return reply;
+                    }
+                }
+                Err(format!("Event stream closed before the response to {method}"))
+            }
+        }
+    }
+
+    fn notify(&mut self, method: &str, timeout: Duration) -> Result<(), String> {
+        let message = json!({ "jsonrpc": "2.0", "method": method });
+        let url = match (&self.transport, &self.stream) {
+            (RemoteTransport::Sse, Some((_, endpoint))) => endpoint.clone(),
+            _ => self.url.clone(),
+        };
+        self.post(url, &message, timeout).map(drop)
+    }
+
+    /// Perform the `initialize` handshake and return its result
+    fn initialize(&mut self, timeout: Duration) -> Result<Value, String> {
+        let result = self.request("initialize", mcp_rpc::initialize_params(), timeout)?;
+        self.initialized = true;
+        if let Err(e) = self.notify("notifications/initialized", timeout) {
+            log::debug!("Failed to send initialized notification to {}: {e}", self.url);
+        }
+        Ok(result)
+    }
+
+    /// Drop the session so the next probe starts with a fresh handshake
+    fn reset(&mut self) {
+        if let (RemoteTransport::Http, Some(session)) = (self.transport, self.session_id.take()) {
+            let _ = self.client.delete(self.url.clone()).headers(self.headers.clone()).header(SESSION_HEADER, session).timeout(CLOSE_TIMEOUT).send();
+        }
+        self.stream = None;
+        self.initialized = false;
+    }
+}
+
+/// Read the next server-sent event as `(event, data)`; `None` once the stream ends
+fn next_event(reader: &mut impl BufRead) -> Result<Option<(String, String)>, String> {
+    let mut event = String::new();
+    let mut data: Vec<String> = Vec::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).map_err(|e| format!("Event stream failed: {e}"))? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            if !data.is_empty() {
+                let event = if event.is_empty() { "message".to_string() } else { event };
+                return Ok(Some((event, data.join("\n"))));
+            }
+            event.clear();
+            continue;
+        }
+        if line.starts_with(':') {
+            continue;
+        }
+        let (field, value) = line.split_once(':').map(|(f, v)| (f, v.strip_prefix(' ').unwrap_or(v))).unwrap_or((line, ""));
+        match field {
+            "event" => event = value.to_string(),
+            "data" => data.push(value.to_string()),
+            _ => {}
+        }
+    }
+}
+
+/// The outcome of request `id` if `message` (or a batch containing it) answers it
+fn take_reply(message: &Value, id: u64) -> Option<Result<Value, String>> {
+    if let Some(batch) = message.as_array() {
+        return batch.iter().find_map(|m| take_reply(m, id));
+    }
+    if message.get("id").and_then(Value::as_u64) != Some(id) {
+        return None;
+    }
+    if let Some(err) = message.get("error") {
+        let code = err.get("code").and_then(Value::as_i64).unwrap_or(0);
+        let msg = err.get("message").and_then(Value::as_str).unwrap_or("unknown error");
+        return Some(Err(format!("JSON-RPC error {code}: {msg}")));
+    }
+    Some(Ok(message.get("result").cloned().unwrap_or(Value::Null)))
+}
+
+/// A successful probe: the handshake (if one was needed) and the ping latency
+struct Probe {
+    handshake: Option<(Value, u64)>,
+    ping_latency_ms: u64,
+}
+
+fn probe(session: &mut Session, health: &HealthCheckConfig) -> Result<Probe, String> {
+    let mut handshake = None;
+    if !session.initialized {
+        let started = Instant::now();
+        let result = session.initialize(Duration::from_millis(health.startup_timeout_ms)).map_err(|e| format!("Initialize failed: {e}"))?;
+        handshake = Some((result, started.elapsed().as_millis() as u64));
+    }
+    let sent = Instant::now();
+    session.request("ping", Value::Null, Duration::from_millis(health.ping_timeout_ms)).map_err(|e| format!("Ping failed: {e}"))?;
+    Ok(Probe { handshake, ping_latency_ms: sent.elapsed().as_millis() as u64 })
+}
+
+/// Fold a probe result into the server's status
+fn apply_probe(entry: &mut RemoteEntry, result: Result<Probe, String>, max_failures: u32) {
+    let state = &mut entry.state;
+    match result {
+        Ok(probe) => {
+            let mut status = match probe.handshake {
+                Some((result, latency)) => HealthStatus::from_initialize(&result, latency),
+                None => state.health.take().unwrap_or_default(),
+            };
+            status.last_ping_at = Some(mcp_lifecycle::now_iso());
+            status.last_ping_latency_ms = Some(probe.ping_latency_ms);
+            status.consecutive_ping_failures = 0;
+            state.health = Some(status);
+            state.last_error = None;
+            entry.reachable_since.get_or_insert_with(Instant::now);
+            let reason = format!("Reachable, ping answered in {}ms", probe.ping_latency_ms);
+            mcp_lifecycle::set_state(state, LifecycleState::Running, Some(reason), ExitInfo::default());
+        }
+        Err(e) => {
+            let status = state.health.get_or_insert_with(Default::default);
+            status.ready = false;
+            status.last_ping_at = Some(mcp_lifecycle::now_iso());
+            status.last_ping_latency_ms = None;
+            status.consecutive_ping_failures += 1;
+            let failures = status.consecutive_ping_failures;
+            state.last_error = Some(e.clone());
+            if failures >= max_failures {
+                entry.reachable_since = None;
+                let reason = format!("Unreachable after {failures} failed checks: {e}");
+                if mcp_lifecycle::set_state(state, LifecycleState::Error, Some(reason.clone()), ExitInfo::default()).is_some() {
+                    log::warn!("Remote server {}: {reason}", state.server_id);
+                }
+            }
+        }
+    }
+}
+
+/// Run `f` on the entry of `server_id` if it still belongs to the monitor owning `stop`
+fn with_remote(server_id: &str, stop: &Arc<AtomicBool>, f: impl FnOnce(&mut RemoteEntry)) -> bool {
+    let Ok(mut map) = remotes().lock() else { return false };
+    match map.get_mut(server_id) {
+        Some(entry) if Arc::ptr_eq(&entry.stop, stop) && !stop.load(Ordering::SeqCst) => {
+            f(entry);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Probe the server until stopped; the session is kept between probes over Streamable HTTP,
+/// while legacy SSE servers get a fresh connection (and handshake) each time
+fn start_monitor(server_id: String, cfg: RemoteConfig, stop: Arc<AtomicBool>) {
+    let spawned = std::thread::Builder::new().name(format!("mcp-{server_id}-remote")).spawn(move || {
+        let interval = match cfg.health.ping_interval_ms {
+            0 => DEFAULT_PROBE_INTERVAL,
+            ms => Duration::from_millis(ms),
+        };
+        let max_failures = cfg.health.max_ping_failures.max(1);
+        let mut session = match Session::new(&cfg) {
+            Ok(session) => session,
+            Err(e) => {
+                with_remote(&server_id, &stop, |entry| {
+                    entry.state.last_error = Some(e.clone());
+                    mcp_lifecycle::set_state(&mut entry.state, LifecycleState::Error, Some(e), ExitInfo::default());
+                });
+                return;
+            }
+        };
+        loop {
+            let result = probe(&mut session, &cfg.health);
+            if result.is_err() || session.transport == RemoteTransport::Sse {
+                session.reset();
+            }
+            if !with_remote(&server_id, &stop, |entry| apply_probe(entry, result, max_failures)) {
+                break;
+            }
+            let slept = Instant::now();
+            while slept.elapsed() < interval && !stop.load(Ordering::SeqCst) {
+                std::thread::sleep(Duration::from_millis(100).min(interval));
+            }
+            if stop.load(Ordering::SeqCst) {
+                break;
+            }
+        }
+        session.reset();
+    });
+    if let Err(e) = spawned {
+        log::error!("Failed to start remote monitor: {e}");
+    }
+}
+
+/// Current status of a monitored remote server
+pub(crate) fn status(server_id: &str) -> Option<MCPServerProcess> {
+    let mut map = remotes().lock().ok()?;
+    let entry = map.get_mut(server_id)?;
+    entry.state.uptime = entry.reachable_since.map(|since| since.elapsed().as_secs());
+    Some(entry.state.clone())
+}
+
+pub(crate) fn list() -> Vec<MCPServerProcess> {
+    let ids = ids();
+    ids.iter().filter_map(|id| status(id)).collect()
+}
+
+pub(crate) fn ids() -> Vec<String> {
+    remotes().lock().map(|map| map.keys().cloned().collect()).unwrap_or_default()
+}
+
+/// End monitoring of a remote server
+pub(crate) fn stop(server_id: &str) -> Result<(), String> {
+    let entry = remotes().lock().map_err(|_| "Lock poisoned")?.remove(server_id);
+    let mut entry = entry.ok_or_else(|| format!("No running process for {server_id}"))?;
+    entry.stop.store(true, Ordering::SeqCst);
+    mcp_lifecycle::set_state(&mut entry.state, LifecycleState::Stopped, Some("Monitoring stopped".into()), ExitInfo::default());
+    log::info!("Stopped monitoring remote server {server_id}");
+    Ok(())
+}
+
+/// Start monitoring a remote server; its status is then reported by `mcp_get_status` and
+/// `mcp_list_running`, and `mcp_stop_server` ends the monitoring
+#[tauri::command]
+pub fn mcp_start_remote_monitor(server_id: String, cfg: RemoteConfig) -> Result<MCPServerProcess, String> {
+    Url::parse(&cfg.url).map_err(|e| format!("Invalid URL {}: {e}", cfg.url))?;
+    // A new config replaces the running monitor
+    let _ = stop(&server_id);
+    let mut state = MCPServerProcess {
+        server_id: server_id.clone(),
+        started_at: Some(mcp_lifecycle::now_iso()),
+        remote_url: Some(cfg.url.clone()),
+        ..Default::default()
+    };
+    mcp_lifecycle::set_state(&mut state, LifecycleState::Starting, Some(format!("Monitoring {}", cfg.url)), ExitInfo::default());
+    let stop = Arc::new(AtomicBool::new(false));
+    let entry = RemoteEntry { state: state.clone(), stop: stop.clone(), reachable_since: None };
+    remotes().lock().map_err(|_| "Lock poisoned")?.insert(server_id.clone(), entry);
+    start_monitor(server_id, cfg, stop);
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::mpsc;
+
+    /// Request line, headers (lowercased) and body of one HTTP request
+    fn read_request(stream: &mut TcpStream) -> Option<(String, HashMap<String, String>, String)> {
+        let mut reader = BufReader::new(stream.try_clone().ok()?);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).ok()?;
+        let mut headers = HashMap::new();
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).ok()?;
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            let (name, value) = line.split_once(':')?;
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+        let len = headers.get("content-length").and_then(|v| v.parse().ok()).unwrap_or(0);
+        let mut body = vec![0; len];
+        reader.read_exact(&mut body).ok()?;
+        Some((request_line.trim().to_string(), headers, String::from_utf8(body).ok()?))
+    }
+
+    fn respond(stream: &mut TcpStream, status: &str, extra: &str, body: &str) {
+        let _ = write!(stream, "HTTP/1.1 {status}\r\n{extra}Content-Length: {}\r\nConnection: close\r\n\r\n{body}", body.len());
+    }
+
+    fn reply_to(body: &str) -> Option<String> {
+        let msg: Value = serde_json::from_str(body).ok()?;
+        let id = msg.get("id")?;
+        let result = match msg["method"].as_str()? {
+            "initialize" => json!({ "protocolVersion": mcp_rpc::PROTOCOL_VERSION, "capabilities": {}, "serverInfo": { "name": "mock-remote", "version": "1.0" } }),
+            _ => json!({}),
+        };
+        Some(json!({ "jsonrpc": "2.0", "id": id, "result": result }).to_string())
+    }
+
+    /// Streamable HTTP server: hands out a session on `initialize` and answers in SSE framing
+    fn streamable_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let Some((line, headers, body)) = read_request(&mut stream) else { continue };
+                if line.starts_with("DELETE") {
+                    respond(&mut stream, "200 OK", "", "");
+                    continue;
+                }
+                let is_init = body.contains("\"initialize\"");
+                if !is_init && headers.get(SESSION_HEADER).map(String::as_str) != Some("session-1") {
+                    respond(&mut stream, "400 Bad Request", "", "missing session");
+                    continue;
+                }
+                match reply_to(&body) {
+                    Some(reply) if is_init => respond(&mut stream, "200 OK", "Content-Type: application/json\r\nMcp-Session-Id: session-1\r\n", &reply),
+                    Some(reply) => respond(&mut stream, "200 OK", "Content-Type: text/event-stream\r\n", &format!("event: message\ndata: {reply}\n\n")),
+                    None => respond(&mut stream, "202 Accepted", "", ""),
+                }
+            }
+        });
+        format!("http://{addr}/mcp")
+    }
+
+    /// Legacy SSE server: replies are pushed onto the open event stream
+    fn sse_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let events: Arc<Mutex<Option<mpsc::Sender<String>>>> = Arc::new(Mutex::new(None));
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let Some((line, _, body)) = read_request(&mut stream) else { continue };
+                if line.starts_with("GET") {
+                    let (tx, rx) = mpsc::channel();
+                    *events.lock().unwrap() = Some(tx);
+                    std::thread::spawn(move || {
+                        let _ = write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\r\n: hello\n\nevent: endpoint\ndata: /messages?session=1\n\n");
+                        for reply in rx {
+                            if write!(stream, "event: message\ndata: {reply}\n\n").is_err() {
+                                break;
+                            }
+                        }
+                    });
+                    continue;
+                }
+                if !line.contains("/messages?session=1") {
+                    respond(&mut stream, "404 Not Found", "", "");
+                    continue;
+                }
+                respond(&mut stream, "202 Accepted", "", "");
+                if let (Some(reply), Some(tx)) = (reply_to(&body), events.lock().unwrap().as_ref()) {
+                    let _ = tx.send(reply);
+                }
+            }
+        });
+        format!("http://{addr}/sse")
+    }
+
+    fn wait_for(server_id: &str, state: LifecycleState) -> MCPServerProcess {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        loop {
+            let current = status(server_id).expect("monitored");
+            if current.state == state || Instant::now() > deadline {
+                return current;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+    }
+
+    fn health(ping_interval_ms: u64) -> HealthCheckConfig {
+        HealthCheckConfig { startup_timeout_ms: 2000, ping_interval_ms, ping_timeout_ms: 2000, max_ping_failures: 2, ..Default::default() }
+    }
+
+    /// Test both transports reach Running with handshake details and ping latency
+    #[test]
+    fn test_remote_monitor_transports() {
+        for (server_id, url, transport) in [("remote-http-test", streamable_server(), RemoteTransport::Http), ("remote-sse-test", sse_server(), RemoteTransport::Sse)] {
+            let cfg = RemoteConfig { url: url.clone(), transport, headers: HashMap::new(), health: health(100) };
+            let started = mcp_start_remote_monitor(server_id.to_string(), cfg).unwrap();
+            assert_eq!(started.state, LifecycleState::Starting);
+            assert_eq!(started.remote_url.as_deref(), Some(url.as_str()));
+
+            let running = wait_for(server_id, LifecycleState::Running);
+            assert_eq!(running.state, LifecycleState::Running, "{transport:?}: {:?}", running.last_error);
+            let checks = running.health.unwrap();
+            assert!(checks.ready);
+            assert_eq!(checks.server_name.as_deref(), Some("mock-remote"));
+            assert!(checks.last_ping_latency_ms.is_some());
+
+            // Later probes keep succeeding (reusing the session over Streamable HTTP)
+            std::thread::sleep(Duration::from_millis(350));
+            let later = mcp_lifecycle::mcp_get_status(server_id.to_string()).unwrap();
+            assert_eq!(later.state, LifecycleState::Running, "{transport:?}: {:?}", later.last_error);
+            assert!(mcp_lifecycle::mcp_list_running().unwrap().iter().any(|s| s.server_id == server_id));
+
+            mcp_lifecycle::stop_server(server_id, false, None).unwrap();
+            assert!(status(server_id).is_none());
+        }
+    }
+
+    /// Test an unreachable endpoint ends up in Error after the allowed failures
+    #[test]
+    fn test_unreachable_remote_reports_error() {
+        let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let cfg = RemoteConfig { url: format!("http://127.0.0.1:{port}/mcp"), transport: RemoteTransport::Http, headers: HashMap::new(), health: health(50) };
+        mcp_start_remote_monitor("remote-down-test".into(), cfg).unwrap();
+
+        let failed = wait_for("remote-down-test", LifecycleState::Error);
+        assert_eq!(failed.state, LifecycleState::Error);
+        assert!(failed.last_error.unwrap().starts_with("Initialize failed"));
+        let checks = failed.health.unwrap();
+        assert!(!checks.ready);
+        assert!(checks.consecutive_ping_failures >= 2);
+        assert!(failed.uptime.is_none());
+        stop("remote-down-test").unwrap();
+    }
+}
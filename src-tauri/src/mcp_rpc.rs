@@ -0,0 +1,343 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    io::Write,
+    sync::{atomic::{AtomicBool, AtomicU64, Ordering}, mpsc, Mutex},
+    time::Duration,
+};
+
+/// MCP protocol revision announced in the `initialize` request
+pub const PROTOCOL_VERSION: &str = "2025-06-18";
+/// Default timeout for requests sent from the frontend
+pub(crate) const DEFAULT_REQUEST_TIMEOUT_MS: u64 = 60_000;
+/// Event carrying a notification sent by a server
+pub const NOTIFICATION_EVENT: &str = "mcp-server-notification";
+/// Event carrying a request sent by a server (sampling, roots, elicitation...)
+pub const SERVER_REQUEST_EVENT: &str = "mcp-server-request";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerMessageEvent {
+    pub server_id: String,
+    pub message: Value,
+}
+
+/// Classification of a line read from a server's stdout
+#[derive(Debug, PartialEq)]
+pub enum Incoming {
+    /// Answered a pending request
+    Response,
+    Notification(Value),
+    Request(Value),
+    /// Not JSON-RPC, or a response nobody is waiting for
+    Other,
+}
+
+/// JSON-RPC connection to a managed server over its stdin/stdout
+pub struct RpcChannel {
+    stdin: Mutex<Box<dyn Write + Send>>,
+    pending: Mutex<HashMap<u64, mpsc::Sender<Value>>>,
+    next_id: AtomicU64,
+    closed: AtomicBool,
+}
+
+impl std::fmt::Debug for RpcChannel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RpcChannel").field("closed", &self.is_closed()).finish_non_exhaustive()
+    }
+}
+
+impl RpcChannel {
+    pub fn new<W: Write + Send + 'static>(stdin: W) -> Self {
+        Self { stdin: Mutex::new(Box::new(stdin)), pending: Mutex::new(HashMap::new()), next_id: AtomicU64::new(1), closed: AtomicBool::new(false) }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
+    /// Mark the connection closed and fail every in-flight request
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.clear();
+        }
+    }
+
+    fn send(&self, message: &Value) -> Result<(), String> {
+        if self.is_closed() {
+            return Err("Server connection is closed".into());
+        }
+        let mut line = serde_json::to_string(message).map_err(|e| format!("Failed to serialize message: {e}"))?;
+        line.push('\n');
+        let mut stdin = self.stdin.lock().map_err(|_| "Lock poisoned")?;
+        stdin
+            .write_all(line.as_bytes())
+            .and_then(|_| stdin.flush())
+            .map_err(|e| format!("Failed to write to server stdin: {e}"))
+    }
+
+    /// Send a request and block until the matching response arrives or `timeout` elapses
+    pub fn request(&self, method: &str, params: Value, timeout: Duration) -> Result<Value, String> {
+        let response = self.request_message(method, params, timeout)?;
+        if let Some(err) = response.get("error") {
+            let code = err.get("code").and_then(Value::as_i64).unwrap_or_default();
+            let msg = err.get("message").and_then(Value::as_str).unwrap_or("unknown error");
+            return Err(format!("JSON-RPC error {code}: {msg}"));
+        }
+        Ok(response.get("result").cloned().unwrap_or(Value::Null))
+    }
+
+    /// Like `request`, but returns the whole response message, JSON-RPC errors included
+    pub fn request_message(&self, method: &str, params: Value, timeout: Duration) -> Result<Value, String> {
+        self.request_message_with_id(self.next_request_id(), method, params, timeout)
+    }
+
+    /// Reserve the id of a request sent later with `request_message_with_id`, for callers that
+    /// must know it before the response arrives (e.g. to relay a cancellation)
+    pub fn next_request_id(&self) -> u64 {
+        self.next_id.fetch_add(1, Ordering::SeqCst)
+    }
+
+    /// `request_message` under an id from `next_request_id`
+    pub fn request_message_with_id(&self, id: u64, method: &str, params: Value, timeout: Duration) -> Result<Value, String> {
+        let (tx, rx) = mpsc::channel();
+        self.pending.lock().map_err(|_| "Lock poisoned")?.insert(id, tx);
+
+        let mut message = json!({ "jsonrpc": "2.0", "id": id, "method": method });
+        if !params.is_null() {
+            message["params"] = params;
+        }
+        if let Err(e) = self.send(&message) {
+            self.forget(id);
+            return Err(e);
+        }
+
+        match rx.recv_timeout(timeout) {
+            Ok(response) => Ok(response),
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                self.forget(id);
+                Err(format!("Request '{method}' timed out after {}ms", timeout.as_millis()))
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => Err("Server exited before responding".into()),
+        }
+    }
+
+    /// Send a notification (no response expected)
+    pub fn notify(&self, method: &str, params: Value) -> Result<(), String> {
+        let mut message = json!({ "jsonrpc": "2.0", "method": method });
+        if !params.is_null() {
+            message["params"] = params;
+        }
+        self.send(&message)
+    }
+
+    fn forget(&self, id: u64) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.remove(&id);
+        }
+    }
+
+    /// Answer a request the server sent to the hub
+    pub fn respond(&self, id: Value, outcome: Result<Value, Value>) -> Result<(), String> {
+        let message = match outcome {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+        };
+        self.send(&message)
+    }
+
+    /// Route one line read from the server's stdout
+    pub fn handle_line(&self, line: &str) -> Incoming {
+        let Ok(message) = serde_json::from_str::<Value>(line) else { return Incoming::Other };
+        if message.get("jsonrpc").and_then(Value::as_str) != Some("2.0") {
+            return Incoming::Other;
+        }
+        if message.get("method").is_some() {
+            return if message.get("id").is_some() { Incoming::Request(message) } else { Incoming::Notification(message) };
+        }
+        let Some(id) = message.get("id").and_then(Value::as_u64) else { return Incoming::Other };
+        let Some(tx) = self.pending.lock().ok().and_then(|mut p| p.remove(&id)) else { return Incoming::Other };
+        let _ = tx.send(message);
+        Incoming::Response
+    }
+
+    /// Route a stdout line and forward server-initiated messages to the frontend
+    pub fn dispatch(&self, server_id: &str, line: &str) {
+        match self.handle_line(line) {
+            Incoming::Notification(message) => {
+                crate::mcp_bridge::broadcast(server_id, &message);
+                crate::mcp_gateway::forward_notification(server_id, &message);
+                crate::mcp_lifecycle::emit_event(NOTIFICATION_EVENT, ServerMessageEvent { server_id: server_id.to_string(), message });
+            }
+            Incoming::Request(message) if message.get("method").and_then(Value::as_str) == Some("ping") => {
+                // Servers may ping the client; answer directly instead of round-tripping the UI
+                if let Err(e) = self.respond(message["id"].clone(), Ok(json!({}))) {
+                    log::debug!("Failed to answer ping from {server_id}: {e}");
+                }
+            }
+            Incoming::Request(message) => {
+                crate::mcp_lifecycle::emit_event(SERVER_REQUEST_EVENT, ServerMessageEvent { server_id: server_id.to_string(), message });
+            }
+            Incoming::Response | Incoming::Other => {}
+        }
+    }
+}
+
+/// Parameters of the `initialize` request sent by the hub
+pub fn initialize_params() -> Value {
+    json!({
+        "protocolVersion": PROTOCOL_VERSION,
+        "capabilities": {},
+        "clientInfo": { "name": "mcp-hub-next", "version": env!("CARGO_PKG_VERSION") },
+    })
+}
+
+/// Send a JSON-RPC request to a managed server and wait for its response
+#[tauri::command]
+pub async fn mcp_send_request(server_id: String, method: String, params: Option<Value>, timeout_ms: Option<u64>) -> Result<Value, String> {
+    let rpc = crate::mcp_lifecycle::rpc_channel(&server_id)?;
+    let timeout = Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_REQUEST_TIMEOUT_MS));
+    tauri::async_runtime::spawn_blocking(move || rpc.request(&method, params.unwrap_or(Value::Null), timeout))
+        .await
+        .map_err(|e| format!("Request task failed: {e}"))?
+}
+
+/// Send a JSON-RPC notification to a managed server
+#[tauri::command]
+pub fn mcp_send_notification(server_id: String, method: String, params: Option<Value>) -> Result<(), String> {
+    crate::mcp_lifecycle::rpc_channel(&server_id)?.notify(&method, params.unwrap_or(Value::Null))
+}
+
+/// Answer a request previously forwarded through the `mcp-server-request` event
+#[tauri::command]
+pub fn mcp_send_response(server_id: String, id: Value, result: Option<Value>, error: Option<Value>) -> Result<(), String> {
+    let outcome = match error {
+        Some(err) => Err(err),
+        None => Ok(result.unwrap_or_else(|| json!({}))),
+    };
+    crate::mcp_lifecycle::rpc_channel(&server_id)?.respond(id, outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn written_lines(buf: &SharedBuf) -> Vec<Value> {
+        let data = buf.0.lock().unwrap().clone();
+        String::from_utf8(data).unwrap().lines().map(|l| serde_json::from_str(l).unwrap()).collect()
+    }
+
+    /// Test a request is written as one JSON line and resolved by the matching response
+    #[test]
+    fn test_request_response_correlation() {
+        let buf = SharedBuf::default();
+        let channel = Arc::new(RpcChannel::new(buf.clone()));
+
+        let responder = {
+            let channel = channel.clone();
+            let buf = buf.clone();
+            std::thread::spawn(move || {
+                for _ in 0..100 {
+                    if let Some(req) = written_lines(&buf).first().cloned() {
+                        let id = req["id"].as_u64().unwrap();
+                        assert_eq!(channel.handle_line(r#"{"jsonrpc":"2.0","id":999,"result":{}}"#), Incoming::Other);
+                        assert_eq!(
+                            channel.handle_line(&format!(r#"{{"jsonrpc":"2.0","id":{id},"result":{{"ok":true}}}}"#)),
+                            Incoming::Response
+                        );
+                        return;
+                    }
+                    std::thread::sleep(Duration::from_millis(10));
+                }
+            })
+        };
+
+        let result = channel.request("tools/list", Value::Null, Duration::from_secs(5)).unwrap();
+        responder.join().unwrap();
+        assert_eq!(result["ok"], true);
+
+        let sent = written_lines(&buf);
+        assert_eq!(sent[0]["jsonrpc"], "2.0");
+        assert_eq!(sent[0]["method"], "tools/list");
+        assert!(sent[0].get("params").is_none());
+    }
+
+    /// Test JSON-RPC error responses surface as Err
+    #[test]
+    fn test_request_error_response() {
+        let channel = Arc::new(RpcChannel::new(SharedBuf::default()));
+        let c = channel.clone();
+        let t = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            c.handle_line(r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32601,"message":"Method not found"}}"#)
+        });
+        let err = channel.request("nope", json!({}), Duration::from_secs(5)).unwrap_err();
+        assert_eq!(t.join().unwrap(), Incoming::Response);
+        assert_eq!(err, "JSON-RPC error -32601: Method not found");
+    }
+
+    /// Test timeouts and closed channels
+    #[test]
+    fn test_request_timeout_and_close() {
+        let channel = RpcChannel::new(SharedBuf::default());
+        let err = channel.request("ping", Value::Null, Duration::from_millis(20)).unwrap_err();
+        assert!(err.contains("timed out"));
+
+        channel.close();
+        assert!(channel.is_closed());
+        assert!(channel.request("ping", Value::Null, Duration::from_millis(20)).is_err());
+        assert!(channel.notify("notifications/initialized", Value::Null).is_err());
+    }
+
+    /// Test classification of server-initiated messages and non-JSON lines
+    #[test]
+    fn test_handle_line_classifies_messages() {
+        let channel = RpcChannel::new(SharedBuf::default());
+        assert_eq!(channel.handle_line("plain log output"), Incoming::Other);
+        assert_eq!(channel.handle_line(r#"{"id":1,"result":{}}"#), Incoming::Other);
+        assert!(matches!(
+            channel.handle_line(r#"{"jsonrpc":"2.0","method":"notifications/message","params":{}}"#),
+            Incoming::Notification(_)
+        ));
+        assert!(matches!(channel.handle_line(r#"{"jsonrpc":"2.0","id":1,"method":"roots/list"}"#), Incoming::Request(_)));
+    }
+
+    /// Test server pings are answered directly and responses are well-formed
+    #[test]
+    fn test_dispatch_answers_server_ping() {
+        let buf = SharedBuf::default();
+        let channel = RpcChannel::new(buf.clone());
+        channel.dispatch("srv", r#"{"jsonrpc":"2.0","id":"abc","method":"ping"}"#);
+        channel.respond(json!(7), Err(json!({"code": -1, "message": "denied"}))).unwrap();
+
+        let sent = written_lines(&buf);
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent[0]["id"], "abc");
+        assert_eq!(sent[0]["result"], json!({}));
+        assert_eq!(sent[1]["id"], 7);
+        assert_eq!(sent[1]["error"]["message"], "denied");
+    }
+
+    /// Test initialize parameters announce the protocol version and client
+    #[test]
+    fn test_initialize_params() {
+        let params = initialize_params();
+        assert_eq!(params["protocolVersion"], PROTOCOL_VERSION);
+        assert_eq!(params["clientInfo"]["name"], "mcp-hub-next");
+    }
+}
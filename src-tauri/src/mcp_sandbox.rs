@@ -0,0 +1,402 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::mcp_lifecycle::StdioConfig;
+
+/// Paths most runtimes (node, python, shared libraries) need to read. Of `/run` only the
+/// resolver config `/etc/resolv.conf` commonly links to is granted. Landlock does not govern
+/// connecting to Unix sockets, so the D-Bus and keyring sockets are kept out of reach by the
+/// seccomp filter on `AF_UNIX` and by `SCRUBBED_ENV` instead.
+const SYSTEM_READ_PATHS: &[&str] = &["/usr", "/bin", "/sbin", "/lib", "/lib32", "/lib64", "/etc", "/opt", "/nix/store", "/proc", "/sys", "/run/systemd/resolve", "/dev/urandom", "/dev/random"];
+/// Version-manager directories (relative to home) holding one runtime per subdirectory; an
+/// executable inside one gets read access to its whole runtime, e.g. `~/.nvm/versions/node/v20`
+const RUNTIME_ROOTS: &[&str] = &[
+    ".nvm/versions/node",
+    ".volta/tools/image/node",
+    ".local/share/fnm/node-versions",
+    ".pyenv/versions",
+    ".local/share/uv/python",
+    ".rustup/toolchains",
+    ".asdf/installs/nodejs",
+    ".asdf/installs/python",
+];
+/// Writable scratch space and the harmless device nodes; the rest of /dev stays off limits
+const SYSTEM_WRITE_PATHS: &[&str] = &["/tmp", "/dev/null", "/dev/zero", "/dev/full", "/dev/tty", "/dev/ptmx", "/dev/pts", "/dev/shm"];
+/// Session bus and agent addresses removed from a sandboxed server's environment, so it is not
+/// pointed at the Secret Service holding the hub's keyring entries
+const SCRUBBED_ENV: &[&str] = &[
+    "DBUS_SESSION_BUS_ADDRESS",
+    "DBUS_SYSTEM_BUS_ADDRESS",
+    "DBUS_STARTER_ADDRESS",
+    "GNOME_KEYRING_CONTROL",
+    "SSH_AUTH_SOCK",
+    "GPG_AGENT_INFO",
+];
+
+/// Declarative sandbox for an untrusted server (Linux only). Filesystem access is confined with
+/// Landlock to system directories, the install directory and the allowlists. Unix sockets and,
+/// optionally, outbound network are blocked with a seccomp filter on socket creation.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SandboxPolicy {
+    /// Directory the server was installed to, readable and writable (defaults to `cwd`)
+    #[serde(default)]
+    pub install_dir: Option<String>,
+    /// Extra paths the server may read and execute from; `~` expands to the home directory
+    #[serde(default)]
+    pub allow_read: Vec<String>,
+    /// Extra paths the server may read and write
+    #[serde(default)]
+    pub allow_write: Vec<String>,
+    /// Refuse IPv4/IPv6 sockets
+    #[serde(default)]
+    pub block_network: bool,
+    /// Allow creating Unix sockets (D-Bus, the keyring, local daemons); refused by default
+    #[serde(default)]
+    pub allow_unix_sockets: bool,
+    /// Allow reading system directories (/usr, /etc, ...) and writing /tmp and /dev/null-like devices
+    #[serde(default = "default_true")]
+    pub system_paths: bool,
+    /// Start unsandboxed with a warning when the kernel lacks Landlock, instead of refusing to start
+    #[serde(default)]
+    pub best_effort: bool,
+}
+
+fn default_true() -> bool { true }
+
+impl Default for SandboxPolicy {
+    fn default() -> Self {
+        Self { install_dir: None, allow_read: vec![], allow_write: vec![], block_network: false, allow_unix_sockets: false, system_paths: true, best_effort: false }
+    }
+}
+
+/// Effective sandbox of a server config, for display in the UI
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SandboxSummary {
+    /// Landlock ABI version of the running kernel, `None` when unavailable
+    pub landlock_abi: Option<u32>,
+    pub read_paths: Vec<String>,
+    pub write_paths: Vec<String>,
+    pub block_network: bool,
+    pub block_unix_sockets: bool,
+    /// The server would start with these restrictions enforced
+    pub enforced: bool,
+}
+
+/// Paths granted by a policy, limited to those that exist
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SandboxPaths {
+    pub read: Vec<PathBuf>,
+    pub write: Vec<PathBuf>,
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix('~'), dirs::home_dir()) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => home.join(rest.trim_start_matches('/')),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Locate the server's executable the way `execvp` would
+fn find_command(command: &str, cwd: Option<&Path>) -> Option<PathBuf> {
+    let candidate = Path::new(command);
+    if command.contains('/') {
+        let path = match cwd {
+            Some(cwd) if candidate.is_relative() => cwd.join(candidate),
+            _ => candidate.to_path_buf(),
+        };
+        return path.canonicalize().ok();
+    }
+    std::env::split_paths(&std::env::var_os("PATH")?).map(|dir| dir.join(command)).find(|p| p.is_file())?.canonicalize().ok()
+}
+
+/// The runtime installation containing `exe`, when it lives under one of `RUNTIME_ROOTS` in `home`
+fn runtime_dir(exe: &Path, home: &Path) -> Option<PathBuf> {
+    RUNTIME_ROOTS.iter().find_map(|root| {
+        let root = home.join(root);
+        let version = exe.strip_prefix(&root).ok()?.components().next()?;
+        Some(root.join(version))
+    })
+}
+
+/// Resolve the paths a policy grants to `cfg`. Only the executable itself is readable so the
+/// server can start, plus its runtime when installed by a known version manager; anything else
+/// (e.g. the rest of `~/.local` for `~/.local/bin/uvx`) must be granted through `allow_read`.
+pub fn resolve(cfg: &StdioConfig, policy: &SandboxPolicy) -> SandboxPaths {
+    let install_dir = policy.install_dir.as_deref().or(cfg.cwd.as_deref()).map(expand_home);
+    let mut read: Vec<PathBuf> = Vec::new();
+    let mut write: Vec<PathBuf> = Vec::new();
+    if policy.system_paths {
+        read.extend(SYSTEM_READ_PATHS.iter().map(PathBuf::from));
+        write.extend(SYSTEM_WRITE_PATHS.iter().map(PathBuf::from));
+    }
+    if let Some(exe) = find_command(&cfg.command, install_dir.as_deref()) {
+        read.extend(dirs::home_dir().and_then(|home| runtime_dir(&exe, &home)));
+        read.push(exe);
+    }
+    read.extend(policy.allow_read.iter().map(|p| expand_home(p)));
+    write.extend(install_dir);
+    write.extend(policy.allow_write.iter().map(|p| expand_home(p)));
+
+    let keep = |paths: Vec<PathBuf>| {
+        let mut kept: Vec<PathBuf> = Vec::new();
+        for path in paths {
+            if !path.exists() {
+                log::debug!("Sandbox path {} does not exist; skipping", path.display());
+            } else if !kept.contains(&path) {
+                kept.push(path);
+            }
+        }
+        kept
+    };
+    SandboxPaths { read: keep(read), write: keep(write) }
+}
+
+/// Landlock ABI version supported by the running kernel
+#[cfg(target_os = "linux")]
+pub fn landlock_abi() -> Option<u32> {
+    const LANDLOCK_CREATE_RULESET_VERSION: libc::c_uint = 1;
+    // SAFETY: with the VERSION flag the kernel only reads the flags argument
+    let version = unsafe { libc::syscall(libc::SYS_landlock_create_ruleset, std::ptr::null::<libc::c_void>(), 0usize, LANDLOCK_CREATE_RULESET_VERSION) };
+    u32::try_from(version).ok().filter(|v| *v > 0)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn landlock_abi() -> Option<u32> {
+    None
+}
+
+/// Describe what the sandbox would enforce for `cfg` on this machine
+pub fn describe(cfg: &StdioConfig) -> SandboxSummary {
+    let Some(policy) = &cfg.sandbox else { return SandboxSummary { landlock_abi: landlock_abi(), ..Default::default() } };
+    let paths = resolve(cfg, policy);
+    let to_strings = |paths: Vec<PathBuf>| paths.into_iter().map(|p| p.to_string_lossy().to_string()).collect();
+    SandboxSummary {
+        landlock_abi: landlock_abi(),
+        read_paths: to_strings(paths.read),
+        write_paths: to_strings(paths.write),
+        block_network: policy.block_network,
+        block_unix_sockets: !policy.allow_unix_sockets,
+        enforced: landlock_abi().is_some(),
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::SandboxPaths;
+    use landlock::{path_beneath_rules, Access, AccessFs, Ruleset, RulesetAttr, RulesetCreated, RulesetCreatedAttr, ABI};
+    use seccompiler::{BackendError, BpfProgram, SeccompAction, SeccompCmpArgLen, SeccompCmpOp, SeccompCondition, SeccompFilter, SeccompRule, TargetArch};
+    use std::collections::BTreeMap;
+
+    /// Newest access rights requested; older kernels silently get the subset they support
+    const TARGET_ABI: ABI = ABI::V5;
+
+    pub fn ruleset(paths: &SandboxPaths) -> Result<RulesetCreated, String> {
+        let err = |e: landlock::RulesetError| format!("Failed to build sandbox ruleset: {e}");
+        Ruleset::default()
+            .handle_access(AccessFs::from_all(TARGET_ABI))
+            .map_err(err)?
+            .create()
+            .map_err(err)?
+            .add_rules(path_beneath_rules(&paths.read, AccessFs::from_read(TARGET_ABI)))
+            .map_err(err)?
+            .add_rules(path_beneath_rules(&paths.write, AccessFs::from_all(TARGET_ABI)))
+            .map_err(err)
+    }
+
+    /// Socket domains refused for `policy`; empty when no filter is needed
+    pub fn blocked_domains(policy: &super::SandboxPolicy) -> Vec<i32> {
+        let mut domains = Vec::new();
+        if !policy.allow_unix_sockets {
+            domains.push(libc::AF_UNIX);
+        }
+        if policy.block_network {
+            domains.extend([libc::AF_INET, libc::AF_INET6]);
+        }
+        domains
+    }
+
+    /// Fail socket creation in `domains` with EACCES. io_uring is refused as well since it can
+    /// create sockets without going through socket(2); socketpair(2) keeps working.
+    pub fn socket_filter(domains: &[i32]) -> Result<BpfProgram, String> {
+        let err = |e: BackendError| format!("Failed to build socket filter: {e}");
+        let arch = TargetArch::try_from(std::env::consts::ARCH).map_err(err)?;
+        let domain_rule = |domain: i32| {
+            SeccompCondition::new(0, SeccompCmpArgLen::Dword, SeccompCmpOp::Eq, domain as u64).and_then(|cond| SeccompRule::new(vec![cond]))
+        };
+        let socket_rules = domains.iter().map(|d| domain_rule(*d)).collect::<Result<Vec<_>, _>>().map_err(err)?;
+        let rules = BTreeMap::from([(libc::SYS_socket, socket_rules), (libc::SYS_io_uring_setup, vec![])]);
+        let filter = SeccompFilter::new(rules, SeccompAction::Allow, SeccompAction::Errno(libc::EACCES as u32), arch).map_err(err)?;
+        BpfProgram::try_from(filter).map_err(err)
+    }
+}
+
+/// Install a pre-exec hook confining the child to the server's sandbox policy.
+/// Returns whether a sandbox was applied.
+pub fn apply(cmd: &mut Command, cfg: &StdioConfig) -> Result<bool, String> {
+    let Some(policy) = &cfg.sandbox else { return Ok(false) };
+    if landlock_abi().is_none() {
+        if policy.best_effort {
+            log::warn!("Landlock is not available; starting {} without a sandbox", cfg.command);
+            return Ok(false);
+        }
+        return Err("Sandbox requested but Landlock is not available (requires Linux 5.13+ with Landlock enabled)".to_string());
+    }
+    #[cfg(target_os = "linux")]
+    {
+        use landlock::RulesetStatus;
+        use std::os::unix::process::CommandExt;
+
+        for var in SCRUBBED_ENV.iter().filter(|var| !cfg.env.contains_key(**var)) {
+            cmd.env_remove(var);
+        }
+        // Built up front: nothing may allocate between fork and exec
+        let mut ruleset = Some(linux::ruleset(&resolve(cfg, policy))?);
+        let domains = linux::blocked_domains(policy);
+        let filter = if domains.is_empty() { None } else { Some(linux::socket_filter(&domains)?) };
+        let denied = || std::io::Error::from_raw_os_error(libc::EPERM);
+        // SAFETY: restrict_self and apply_filter only issue prctl/landlock/seccomp syscalls
+        unsafe {
+            cmd.pre_exec(move || {
+                let Some(ruleset) = ruleset.take() else { return Ok(()) };
+                match ruleset.restrict_self() {
+                    Ok(status) if status.ruleset != RulesetStatus::NotEnforced => {}
+                    _ => return Err(denied()),
+                }
+                if let Some(filter) = &filter {
+                    seccompiler::apply_filter(filter).map_err(|_| denied())?;
+                }
+                Ok(())
+            });
+        }
+        Ok(true)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = cmd;
+        unreachable!("landlock_abi() is None off Linux")
+    }
+}
+
+/// Describe the effective sandbox of a server config (paths granted, network, kernel support)
+#[tauri::command]
+pub fn mcp_describe_sandbox(cfg: StdioConfig) -> Result<SandboxSummary, String> {
+    Ok(describe(&cfg))
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn sandboxed(script: &str, install_dir: &Path, policy: SandboxPolicy) -> StdioConfig {
+        StdioConfig {
+            command: "sh".into(),
+            args: vec!["-c".into(), script.into()],
+            cwd: Some(install_dir.to_string_lossy().to_string()),
+            sandbox: Some(policy),
+            ..Default::default()
+        }
+    }
+
+    /// Run `cfg` through `apply`, returning (success, stderr)
+    fn run(cfg: &StdioConfig) -> (bool, String) {
+        let mut cmd = Command::new(&cfg.command);
+        cmd.args(&cfg.args).current_dir(cfg.cwd.as_deref().unwrap());
+        assert!(apply(&mut cmd, cfg).unwrap());
+        let out = cmd.output().unwrap();
+        (out.status.success(), String::from_utf8_lossy(&out.stderr).to_string())
+    }
+
+    /// Test resolution keeps existing paths once and includes the install dir and the executable only
+    #[test]
+    fn test_resolve_paths() {
+        let install = TempDir::new().unwrap();
+        let policy = SandboxPolicy { allow_read: vec!["/does/not/exist".into(), "/usr".into()], system_paths: false, ..Default::default() };
+        let cfg = sandboxed("true", install.path(), policy);
+        let paths = resolve(&cfg, cfg.sandbox.as_ref().unwrap());
+        assert_eq!(paths.write, vec![install.path().to_path_buf()]);
+        assert!(paths.read.iter().all(|p| p.exists()));
+        assert_eq!(paths.read.iter().filter(|p| *p == Path::new("/usr")).count(), 1);
+        // The executable file itself, never its `bin` parent
+        let sh = find_command("sh", None).unwrap();
+        assert!(paths.read.contains(&sh));
+        assert!(!paths.read.iter().any(|p| sh.starts_with(p) && *p != sh && *p != Path::new("/usr")));
+
+        let home = Path::new("/home/u");
+        assert_eq!(runtime_dir(Path::new("/home/u/.nvm/versions/node/v20.1.0/lib/node_modules/npm/bin/npx-cli.js"), home), Some(PathBuf::from("/home/u/.nvm/versions/node/v20.1.0")));
+        assert_eq!(runtime_dir(Path::new("/home/u/.local/bin/uvx"), home), None);
+        assert_eq!(runtime_dir(Path::new("/home/u/.cargo/bin/server"), home), None);
+        assert_eq!(expand_home("~/x"), dirs::home_dir().unwrap().join("x"));
+        assert_eq!(expand_home("~x"), PathBuf::from("~x"));
+
+        let policy: SandboxPolicy = serde_json::from_str("{}").unwrap();
+        assert!(policy.system_paths && !policy.best_effort && !policy.block_network);
+    }
+
+    /// Test the filesystem is confined to the install dir and allowlisted paths
+    #[test]
+    fn test_filesystem_confined() {
+        if landlock_abi().is_none() {
+            eprintln!("Landlock unavailable; skipping");
+            return;
+        }
+        let install = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        std::fs::write(outside.path().join("secret"), "x").unwrap();
+        let script = format!("echo ok > state && cat {}/secret", outside.path().display());
+        // /tmp is a system path, so rely on explicit grants only
+        let base = SandboxPolicy { system_paths: false, allow_read: vec!["/usr".into(), "/bin".into(), "/lib".into(), "/lib64".into(), "/etc".into()], ..Default::default() };
+
+        let (ok, stderr) = run(&sandboxed(&script, install.path(), base.clone()));
+        assert!(!ok);
+        assert!(stderr.contains("Permission denied"), "{stderr}");
+        assert!(install.path().join("state").exists());
+
+        let allowed = SandboxPolicy { allow_read: [base.allow_read.clone(), vec![outside.path().to_string_lossy().to_string()]].concat(), ..base };
+        let (ok, stderr) = run(&sandboxed(&script, install.path(), allowed));
+        assert!(ok, "{stderr}");
+    }
+
+    /// Test Unix sockets are refused and bus addresses scrubbed, while /dev/null stays writable
+    #[test]
+    fn test_unix_sockets_blocked() {
+        // The system interpreter: version-manager shims live outside the granted paths
+        if landlock_abi().is_none() || !Path::new("/usr/bin/python3").exists() {
+            return;
+        }
+        let install = TempDir::new().unwrap();
+        let script = "import os, socket\nopen('/dev/null', 'w').write('x')\nassert 'DBUS_SESSION_BUS_ADDRESS' not in os.environ\nsocket.socket(socket.AF_UNIX)";
+        let mut cfg = sandboxed("", install.path(), SandboxPolicy::default());
+        cfg.command = "/usr/bin/python3".into();
+        cfg.args = vec!["-c".into(), script.into()];
+        let mut cmd = Command::new(&cfg.command);
+        cmd.args(&cfg.args).current_dir(install.path()).env("DBUS_SESSION_BUS_ADDRESS", "unix:path=/run/user/0/bus");
+        assert!(apply(&mut cmd, &cfg).unwrap());
+        let out = cmd.output().unwrap();
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        assert!(!out.status.success());
+        assert!(stderr.contains("PermissionError"), "{stderr}");
+
+        cfg.sandbox = Some(SandboxPolicy { allow_unix_sockets: true, ..Default::default() });
+        let (ok, stderr) = run(&cfg);
+        assert!(ok, "{stderr}");
+    }
+
+    /// Test outbound network is refused when blocked
+    #[test]
+    fn test_network_blocked() {
+        if landlock_abi().is_none() || !Path::new("/bin/bash").exists() {
+            return;
+        }
+        let install = TempDir::new().unwrap();
+        let mut cfg = sandboxed("", install.path(), SandboxPolicy { block_network: true, ..Default::default() });
+        cfg.command = "bash".into();
+        cfg.args = vec!["-c".into(), "exec 3<>/dev/tcp/127.0.0.1/9".into()];
+        let (ok, stderr) = run(&cfg);
+        assert!(!ok);
+        assert!(stderr.contains("Permission denied"), "{stderr}");
+    }
+}
@@ -0,0 +1,341 @@
+use std::{
+    borrow::Cow,
+    io::{BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    process::{Child, Command},
+    sync::{atomic::{AtomicBool, Ordering}, Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+use tauri::{AppHandle, Manager, Url, WebviewUrl};
+
+use crate::mcp_lifecycle::{RestartMode, RestartPolicy};
+use crate::process_tree::{self, StopOutcome};
+
+/// Port tried first; overridden by `TAURI_NEXT_PORT`, falls back to any free port
+pub const DEFAULT_PORT: u16 = 34115;
+/// Endpoint answered by the launcher written in `scripts/tauri-build.js`
+const HEALTH_PATH: &str = "/__tauri/health";
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+const HEALTH_INTERVAL: Duration = Duration::from_secs(2);
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(2);
+/// Consecutive failed health probes before node is considered hung
+const MAX_HEALTH_FAILURES: u32 = 3;
+/// Custom protocol serving the native loading/error page
+pub const STATUS_SCHEME: &str = "hub-status";
+const WINDOW_LABEL: &str = "main";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Starting,
+    Ready,
+    Restarting,
+    Failed,
+}
+
+#[derive(Debug, Clone)]
+pub struct Status {
+    pub phase: Phase,
+    pub message: String,
+}
+
+static STATUS: OnceLock<Mutex<Status>> = OnceLock::new();
+static NODE: OnceLock<Mutex<Option<Child>>> = OnceLock::new();
+static STOPPING: AtomicBool = AtomicBool::new(false);
+
+fn status() -> &'static Mutex<Status> {
+    STATUS.get_or_init(|| Mutex::new(Status { phase: Phase::Starting, message: "Starting MCP Hub…".into() }))
+}
+
+fn node() -> &'static Mutex<Option<Child>> {
+    NODE.get_or_init(|| Mutex::new(None))
+}
+
+fn set_status(phase: Phase, message: impl Into<String>) {
+    let message = message.into();
+    match phase {
+        Phase::Failed => log::error!("Next server: {message}"),
+        Phase::Restarting => log::warn!("Next server: {message}"),
+        _ => log::info!("Next server: {message}"),
+    }
+    if let Ok(mut s) = status().lock() {
+        *s = Status { phase, message };
+    }
+}
+
+/// Backoff used between node restarts
+fn restart_policy() -> RestartPolicy {
+    RestartPolicy { mode: RestartMode::Always, max_retries: 10, backoff_base_ms: 1_000, backoff_max_ms: 30_000, reset_after_secs: 120 }
+}
+
+/// `preferred` if it can be bound on loopback, otherwise a port picked by the OS
+pub fn pick_port(preferred: u16) -> std::io::Result<u16> {
+    if TcpListener::bind(("127.0.0.1", preferred)).is_ok() {
+        return Ok(preferred);
+    }
+    Ok(TcpListener::bind(("127.0.0.1", 0))?.local_addr()?.port())
+}
+
+/// GET the health endpoint and report whether it answered with a 2xx status
+pub fn probe_health(port: u16, timeout: Duration) -> bool {
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    let Ok(mut stream) = TcpStream::connect_timeout(&addr, timeout) else { return false };
+    let _ = stream.set_read_timeout(Some(timeout));
+    let _ = stream.set_write_timeout(Some(timeout));
+    let request = format!("GET {HEALTH_PATH} HTTP/1.1\r\nHost: 127.0.0.1:{port}\r\nConnection: close\r\n\r\n");
+    if stream.write_all(request.as_bytes()).is_err() {
+        return false;
+    }
+    let mut status_line = String::new();
+    BufReader::new(stream).read_line(&mut status_line).is_ok()
+        && status_line.split_whitespace().nth(1).is_some_and(|code| code.starts_with('2'))
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Loading/error page shown while the UI server is unavailable; reloads itself to pick up new status
+pub fn status_page(status: &Status) -> String {
+    let (title, refresh) = match status.phase {
+        Phase::Failed => ("MCP Hub could not start", ""),
+        Phase::Restarting => ("Restarting MCP Hub…", r#"<meta http-equiv="refresh" content="2">"#),
+        Phase::Starting | Phase::Ready => ("Starting MCP Hub…", r#"<meta http-equiv="refresh" content="2">"#),
+    };
+    format!(
+        r#"<!doctype html><html><head><meta charset="utf-8">{refresh}<title>{title}</title>
+<style>body{{font-family:system-ui,sans-serif;display:flex;align-items:center;justify-content:center;height:100vh;margin:0;background:#0b0b0f;color:#e4e4e7}}
+main{{max-width:32rem;text-align:center}}p{{color:#a1a1aa;word-break:break-word}}</style></head>
+<body><main><h1>{title}</h1><p>{message}</p></main></body></html>"#,
+        message = escape_html(&status.message)
+    )
+}
+
+/// Handler for the `hub-status` protocol
+pub fn status_response() -> tauri::http::Response<Cow<'static, [u8]>> {
+    let page = status().lock().map(|s| status_page(&s)).unwrap_or_default();
+    tauri::http::Response::builder()
+        .header("Content-Type", "text/html; charset=utf-8")
+        .body(Cow::Owned(page.into_bytes()))
+        .unwrap_or_default()
+}
+
+fn status_url() -> Url {
+    #[cfg(any(windows, target_os = "android"))]
+    let url = format!("http://{STATUS_SCHEME}.localhost/");
+    #[cfg(not(any(windows, target_os = "android")))]
+    let url = format!("{STATUS_SCHEME}://localhost/");
+    Url::parse(&url).expect("static status URL is valid")
+}
+
+/// Point the main window at `url`, creating it on first use
+fn show(app: &AppHandle, url: Url) {
+    if let Some(win) = app.get_webview_window(WINDOW_LABEL) {
+        if let Err(e) = win.navigate(url) {
+            log::error!("Failed to navigate main window: {e}");
+        }
+        return;
+    }
+    let webview_url = if url.scheme() == STATUS_SCHEME { WebviewUrl::CustomProtocol(url) } else { WebviewUrl::External(url) };
+    match tauri::WebviewWindowBuilder::new(app, WINDOW_LABEL, webview_url).title("mcp-hub-next").build() {
+        Ok(win) => {
+            let _ = win.set_focus();
+        }
+        Err(e) => log::error!("Failed to create main window: {e}"),
+    }
+}
+
+/// Sleep in short steps so shutdown isn't delayed by a long backoff
+fn sleep_unless_stopping(duration: Duration) {
+    let until = Instant::now() + duration;
+    while !STOPPING.load(Ordering::SeqCst) && Instant::now() < until {
+        std::thread::sleep(Duration::from_millis(100));
+    }
+}
+
+/// Exit status of the running node process; `Some` once it is gone (or was taken by `stop`)
+fn node_exited() -> Option<String> {
+    let mut slot = node().lock().ok()?;
+    let Some(child) = slot.as_mut() else { return Some("stopped".into()) };
+    match child.try_wait() {
+        Ok(Some(status)) => Some(format!("exited with {status}")),
+        Ok(None) => None,
+        Err(e) => Some(format!("could not be polled: {e}")),
+    }
+}
+
+fn kill_node() {
+    if let Some(mut child) = node().lock().ok().and_then(|mut slot| slot.take()) {
+        process_tree::kill_tree(&mut child);
+    }
+}
+
+/// Run node until it crashes or stops answering; returns the reason
+fn run_once(node_path: &Path, server_js: &Path, resource_dir: &Path, preferred: u16, app: &AppHandle) -> Result<String, String> {
+    let port = pick_port(preferred).map_err(|e| format!("No free port available: {e}"))?;
+    if port != preferred {
+        log::warn!("Port {preferred} is busy; serving the UI on {port}");
+    }
+
+    // Own process group so the whole tree is stopped on restart and on exit
+    let mut cmd = Command::new(node_path);
+    cmd.arg(server_js).env("PORT", port.to_string()).env("HOST", "127.0.0.1").current_dir(resource_dir);
+    process_tree::isolate(&mut cmd);
+    let child = cmd.spawn().map_err(|e| format!("Failed to spawn {}: {e}", node_path.display()))?;
+    if let Ok(mut slot) = node().lock() {
+        *slot = Some(child);
+    }
+
+    let started = Instant::now();
+    loop {
+        if let Some(reason) = node_exited() {
+            kill_node();
+            return Ok(format!("Node {reason} during startup"));
+        }
+        if probe_health(port, HEALTH_TIMEOUT) {
+            break;
+        }
+        if started.elapsed() >= STARTUP_TIMEOUT {
+            kill_node();
+            return Ok(format!("Node did not become healthy within {}s", STARTUP_TIMEOUT.as_secs()));
+        }
+        std::thread::sleep(Duration::from_millis(200));
+    }
+
+    set_status(Phase::Ready, format!("Listening on 127.0.0.1:{port}"));
+    match Url::parse(&format!("http://127.0.0.1:{port}")) {
+        Ok(url) => show(app, url),
+        Err(e) => log::error!("Failed to build Next server URL: {e}"),
+    }
+
+    let mut failures = 0;
+    loop {
+        sleep_unless_stopping(HEALTH_INTERVAL);
+        if let Some(reason) = node_exited() {
+            // Take down anything node left behind in its group before relaunching
+            kill_node();
+            return Ok(format!("Node {reason}"));
+        }
+        if probe_health(port, HEALTH_TIMEOUT) {
+            failures = 0;
+            continue;
+        }
+        failures += 1;
+        if failures >= MAX_HEALTH_FAILURES {
+            kill_node();
+            return Ok(format!("Node stopped answering {HEALTH_PATH} ({failures} failed checks)"));
+        }
+    }
+}
+
+/// Launch the embedded Next.js server and keep it alive, showing a native status page meanwhile
+pub fn start(app: AppHandle) {
+    let spawned = std::thread::Builder::new().name("next-server".into()).spawn(move || {
+        show(&app, status_url());
+
+        // Resolve resource directory where we bundled .next and public
+        let resource_dir = match app.path().resource_dir() {
+            Ok(p) => p,
+            Err(e) => return set_status(Phase::Failed, format!("Failed to resolve resource directory: {e}")),
+        };
+
+        // Node binary path (bundled), fallback to system 'node'
+        let node_name = if cfg!(target_os = "windows") { "node.exe" } else { "node" };
+        let bundled_node = resource_dir.join("resources").join("node").join(node_name);
+        let node_path = if bundled_node.exists() { bundled_node } else { PathBuf::from("node") };
+
+        // Server entry (our small launcher written by scripts/tauri-build.js)
+        let server_js = resource_dir.join("resources").join("server.js");
+        if !server_js.exists() {
+            return set_status(Phase::Failed, format!("Server launcher not found at {}", server_js.display()));
+        }
+
+        let preferred = std::env::var("TAURI_NEXT_PORT").ok().and_then(|p| p.parse().ok()).unwrap_or(DEFAULT_PORT);
+        let policy = restart_policy();
+        let mut failures = 0;
+        while !STOPPING.load(Ordering::SeqCst) {
+            let started = Instant::now();
+            // Err means node could not even be launched; both cases go through the same backoff
+            let reason = run_once(&node_path, &server_js, &resource_dir, preferred, &app).unwrap_or_else(|e| e);
+            if STOPPING.load(Ordering::SeqCst) {
+                break;
+            }
+            if started.elapsed() >= Duration::from_secs(policy.reset_after_secs) {
+                failures = 0;
+            }
+            if failures >= policy.max_retries {
+                set_status(Phase::Failed, format!("{reason}; giving up after {failures} restart attempts"));
+                show(&app, status_url());
+                break;
+            }
+            let delay = policy.backoff(failures);
+            failures += 1;
+            set_status(
+                Phase::Restarting,
+                format!("{reason}; restarting in {}s (attempt {failures}/{})", delay.as_secs().max(1), policy.max_retries),
+            );
+            show(&app, status_url());
+            sleep_unless_stopping(delay);
+        }
+    });
+    if let Err(e) = spawned {
+        log::error!("Failed to start Next server supervisor: {e}");
+    }
+}
+
+/// Stop supervising and terminate node, if it is running
+pub fn stop(grace: Duration) -> Option<StopOutcome> {
+    STOPPING.store(true, Ordering::SeqCst);
+    let mut child = node().lock().ok()?.take()?;
+    Some(process_tree::terminate(&mut child, grace, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    /// Serve one canned HTTP response and return the port
+    fn serve_once(response: &'static str) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            if let Ok((mut stream, _)) = listener.accept() {
+                let mut buf = [0u8; 512];
+                let _ = stream.read(&mut buf);
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        port
+    }
+
+    /// Test a busy preferred port falls back to a free one
+    #[test]
+    fn test_pick_port_falls_back_when_busy() {
+        let busy = TcpListener::bind("127.0.0.1:0").unwrap();
+        let busy_port = busy.local_addr().unwrap().port();
+        let port = pick_port(busy_port).unwrap();
+        assert_ne!(port, busy_port);
+        assert!(TcpListener::bind(("127.0.0.1", port)).is_ok());
+    }
+
+    /// Test only 2xx answers count as healthy
+    #[test]
+    fn test_probe_health() {
+        assert!(probe_health(serve_once("HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n"), Duration::from_secs(2)));
+        assert!(!probe_health(serve_once("HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n"), Duration::from_secs(2)));
+        let closed = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        assert!(!probe_health(closed, Duration::from_millis(500)));
+    }
+
+    /// Test the status page escapes messages and only auto-refreshes while recovering
+    #[test]
+    fn test_status_page() {
+        let failed = Status { phase: Phase::Failed, message: "<script>x</script>".into() };
+        let page = status_page(&failed);
+        assert!(page.contains("&lt;script&gt;"));
+        assert!(!page.contains("http-equiv"));
+        let restarting = Status { phase: Phase::Restarting, message: "crashed".into() };
+        assert!(status_page(&restarting).contains("http-equiv=\"refresh\""));
+    }
+}
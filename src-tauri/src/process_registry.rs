@@ -0,0 +1,302 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+};
+use tauri::{AppHandle, Manager};
+
+use crate::mcp_lifecycle::StdioConfig;
+use crate::mcp_logs::LogRotation;
+
+const REGISTRY_FILE: &str = "process_registry.json";
+const SETTINGS_FILE: &str = "lifecycle_settings.json";
+
+/// A running server as recorded on disk, used to find survivors after a crash
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RegistryRecord {
+    pub server_id: String,
+    pub pid: u32,
+    pub started_at: String,
+    /// Kernel start time of the process, guards against PID reuse
+    pub start_ticks: Option<u64>,
+    /// Hash of command, args and cwd (see `fingerprint`)
+    pub fingerprint: String,
+    pub command: String,
+}
+
+/// What to do with servers still running from a previous session
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OrphanPolicy {
+    /// Monitor them as "adopted" servers (no stdio, no restarts)
+    #[default]
+    Adopt,
+    /// Stop them
+    Kill,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LifecycleSettings {
+    #[serde(default)]
+    pub orphan_policy: OrphanPolicy,
+    /// Rotation and retention of per-server log files
+    #[serde(default)]
+    pub logs: LogRotation,
+}
+
+static REGISTRY_PATH: OnceLock<PathBuf> = OnceLock::new();
+/// Last content written, so the supervisor only touches the disk on change
+static LAST_WRITTEN: OnceLock<Mutex<Option<String>>> = OnceLock::new();
+
+fn app_data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    Ok(dir)
+}
+
+/// FNV-1a over NUL-terminated parts, so hashes survive toolchain upgrades
+fn fnv1a<'a>(parts: impl Iterator<Item = &'a str>) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for part in parts {
+        for byte in part.bytes().chain(std::iter::once(0)) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    format!("{hash:016x}")
+}
+
+/// Stable identifier of a server's launch command
+pub fn fingerprint(cfg: &StdioConfig) -> String {
+    fnv1a(std::iter::once(cfg.command.as_str()).chain(cfg.args.iter().map(String::as_str)).chain(cfg.cwd.as_deref()))
+}
+
+/// Hash of everything that shapes a launch as saved in servers.json: the command plus
+/// environment (unresolved, so secrets stay out of it) and sandbox policy
+pub fn config_hash(cfg: &StdioConfig) -> String {
+    let mut env: Vec<(&String, &String)> = cfg.env.iter().collect();
+    env.sort();
+    let sandbox = cfg.sandbox.as_ref().and_then(|policy| serde_json::to_string(policy).ok()).unwrap_or_default();
+    let cwd = cfg.cwd.as_deref().unwrap_or_default();
+    let parts = std::iter::once(cfg.command.as_str())
+        .chain(cfg.args.iter().map(String::as_str))
+        .chain([cwd, sandbox.as_str()])
+        .chain(env.into_iter().flat_map(|(k, v)| [k.as_str(), v.as_str()]));
+    fnv1a(parts)
+}
+
+/// Kernel start time of `pid`, in a platform-specific unit that is only ever compared
+/// for equality: clock ticks since boot on Linux, microseconds since the epoch on macOS,
+/// 100ns intervals since 1601 on Windows
+#[cfg(target_os = "linux")]
+pub fn start_ticks(pid: u32) -> Option<u64> {
+    let stat = fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    let rest = &stat[stat.rfind(')')? + 1..];
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    if fields.first() == Some(&"Z") {
+        return None;
+    }
+    // Field 22 of stat; `rest` starts at field 3
+    fields.get(19)?.parse().ok()
+}
+
+#[cfg(target_os = "macos")]
+pub fn start_ticks(pid: u32) -> Option<u64> {
+    let mut info: libc::proc_bsdinfo = unsafe { std::mem::zeroed() };
+    let size = std::mem::size_of::<libc::proc_bsdinfo>() as libc::c_int;
+    let read = unsafe { libc::proc_pidinfo(pid as libc::c_int, libc::PROC_PIDTBSDINFO, 0, (&mut info as *mut libc::proc_bsdinfo).cast(), size) };
+    if read != size || info.pbi_status == libc::SZOMB {
+        return None;
+    }
+    Some(info.pbi_start_tvsec * 1_000_000 + info.pbi_start_tvusec)
+}
+
+#[cfg(windows)]
+pub fn start_ticks(pid: u32) -> Option<u64> {
+    use std::ffi::c_void;
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct FileTime {
+        low: u32,
+        high: u32,
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn OpenProcess(access: u32, inherit: i32, pid: u32) -> *mut c_void;
+        fn GetProcessTimes(process: *mut c_void, creation: *mut FileTime, exit: *mut FileTime, kernel: *mut FileTime, user: *mut FileTime) -> i32;
+        fn GetExitCodeProcess(process: *mut c_void, code: *mut u32) -> i32;
+        fn CloseHandle(handle: *mut c_void) -> i32;
+    }
+    const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+    const STILL_ACTIVE: u32 = 259;
+
+    let handle = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid) };
+    if handle.is_null() {
+        return None;
+    }
+    let (mut creation, mut exit, mut kernel, mut user) = (FileTime::default(), FileTime::default(), FileTime::default(), FileTime::default());
+    let mut code = 0;
+    let ok = unsafe {
+        GetProcessTimes(handle, &mut creation, &mut exit, &mut kernel, &mut user) != 0
+            && GetExitCodeProcess(handle, &mut code) != 0
+    };
+    unsafe { CloseHandle(handle) };
+    // An exited process lingers while anyone holds a handle to it
+    if !ok || code != STILL_ACTIVE {
+        return None;
+    }
+    Some((creation.high as u64) << 32 | creation.low as u64)
+}
+
+/// Other platforms can't tell a survivor from a recycled PID, so orphans are never adopted
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+pub fn start_ticks(_pid: u32) -> Option<u64> {
+    None
+}
+
+/// True if the recorded process is still alive and provably the same one
+pub fn is_alive(record: &RegistryRecord) -> bool {
+    match (record.start_ticks, start_ticks(record.pid)) {
+        (Some(recorded), Some(current)) => recorded == current,
+        // Without a start time a recycled PID can't be told apart; leave it alone
+        _ => false,
+    }
+}
+
+pub fn load_records(path: &Path) -> Vec<RegistryRecord> {
+    let Ok(content) = fs::read_to_string(path) else { return vec![] };
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        log::warn!("Ignoring unreadable process registry {}: {e}", path.display());
+        vec![]
+    })
+}
+
+pub fn save_records(path: &Path, records: &[RegistryRecord]) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(records).map_err(|e| format!("Failed to serialize process registry: {e}"))?;
+    let mut last = LAST_WRITTEN.get_or_init(|| Mutex::new(None)).lock().map_err(|_| "Lock poisoned")?;
+    if last.as_deref() == Some(content.as_str()) {
+        return Ok(());
+    }
+    fs::write(path, &content).map_err(|e| format!("Failed to write process registry: {e}"))?;
+    *last = Some(content);
+    Ok(())
+}
+
+/// Read the records left by the previous session and start persisting to the same file
+pub fn open(app: &AppHandle) -> Result<Vec<RegistryRecord>, String> {
+    let path = app_data_dir(app)?.join(REGISTRY_FILE);
+    let records = load_records(&path);
+    let _ = REGISTRY_PATH.set(path);
+    Ok(records)
+}
+
+/// Persist the current set of running servers; a no-op until `open` has been called
+pub fn persist(records: &[RegistryRecord]) {
+    let Some(path) = REGISTRY_PATH.get() else { return };
+    if let Err(e) = save_records(path, records) {
+        log::warn!("{e}");
+    }
+}
+
+pub fn load_settings(app: &AppHandle) -> LifecycleSettings {
+    app_data_dir(app)
+        .ok()
+        .and_then(|dir| fs::read_to_string(dir.join(SETTINGS_FILE)).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Get lifecycle settings (orphan handling)
+#[tauri::command]
+pub fn mcp_get_lifecycle_settings(app: AppHandle) -> Result<LifecycleSettings, String> {
+    Ok(load_settings(&app))
+}
+
+/// Save lifecycle settings; log rotation applies immediately, the orphan policy on the next launch
+#[tauri::command]
+pub fn mcp_set_lifecycle_settings(app: AppHandle, settings: LifecycleSettings) -> Result<(), String> {
+    let path = app_data_dir(&app)?.join(SETTINGS_FILE);
+    let content = serde_json::to_string_pretty(&settings).map_err(|e| format!("Failed to serialize settings: {e}"))?;
+    fs::write(path, content).map_err(|e| format!("Failed to write lifecycle settings: {e}"))?;
+    crate::mcp_logs::configure(settings.logs);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn cfg(command: &str, args: &[&str]) -> StdioConfig {
+        StdioConfig { command: command.into(), args: args.iter().map(|a| a.to_string()).collect(), ..Default::default() }
+    }
+
+    /// Test fingerprints are stable and distinguish argument boundaries
+    #[test]
+    fn test_fingerprint() {
+        assert_eq!(fingerprint(&cfg("npx", &["-y", "pkg"])), fingerprint(&cfg("npx", &["-y", "pkg"])));
+        assert_ne!(fingerprint(&cfg("npx", &["-y", "pkg"])), fingerprint(&cfg("npx", &["-ypkg"])));
+        assert_eq!(fingerprint(&cfg("a", &[])).len(), 16);
+    }
+
+    /// Test the config hash covers env (order-independent) but not supervisor settings
+    #[test]
+    fn test_config_hash() {
+        let mut a = cfg("node", &["server.js"]);
+        a.env.insert("A".into(), "1".into());
+        a.env.insert("B".into(), "2".into());
+        let mut b = cfg("node", &["server.js"]);
+        b.env.insert("B".into(), "2".into());
+        b.env.insert("A".into(), "1".into());
+        b.stop_grace_ms = Some(10);
+        assert_eq!(config_hash(&a), config_hash(&b));
+        b.env.insert("A".into(), "changed".into());
+        assert_ne!(config_hash(&a), config_hash(&b));
+    }
+
+    /// Test records round-trip through the registry file
+    #[test]
+    fn test_records_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(REGISTRY_FILE);
+        assert!(load_records(&path).is_empty());
+
+        let record = RegistryRecord {
+            server_id: "s".into(),
+            pid: 42,
+            started_at: "2024-01-01T00:00:00Z".into(),
+            start_ticks: Some(7),
+            fingerprint: "abc".into(),
+            command: "node".into(),
+        };
+        save_records(&path, std::slice::from_ref(&record)).unwrap();
+        assert_eq!(load_records(&path), vec![record]);
+
+        fs::write(&path, "not json").unwrap();
+        assert!(load_records(&path).is_empty());
+    }
+
+    /// Test liveness requires a matching start time
+    #[cfg(any(target_os = "linux", target_os = "macos", windows))]
+    #[test]
+    fn test_is_alive_checks_start_time() {
+        let pid = std::process::id();
+        let ticks = start_ticks(pid);
+        assert!(ticks.is_some());
+        let mut record = RegistryRecord {
+            server_id: "self".into(),
+            pid,
+            started_at: String::new(),
+            start_ticks: ticks,
+            fingerprint: String::new(),
+            command: String::new(),
+        };
+        assert!(is_alive(&record));
+        record.start_ticks = ticks.map(|t| t + 1);
+        assert!(!is_alive(&record));
+        assert_eq!(serde_json::to_value(OrphanPolicy::Kill).unwrap(), "kill");
+    }
+}
@@ -0,0 +1,292 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    process::{Child, Command},
+    time::{Duration, Instant},
+};
+
+/// Grace period between SIGTERM and SIGKILL when none is configured
+pub const DEFAULT_STOP_GRACE_MS: u64 = 5_000;
+/// How long a stop waits for the tree to disappear after SIGKILL, on top of the grace period
+pub const KILL_WAIT: Duration = Duration::from_secs(1);
+const POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+/// Signal that finally brought a process tree down
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum StopSignal {
+    Sigterm,
+    Sigkill,
+}
+
+impl StopSignal {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StopSignal::Sigterm => "SIGTERM",
+            StopSignal::Sigkill => "SIGKILL",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StopOutcome {
+    /// `None` when the process had already exited before it was signalled
+    pub signal: Option<StopSignal>,
+    pub exit_code: Option<i32>,
+    pub elapsed_ms: u64,
+}
+
+/// Run the child in its own process group so the whole tree (npx/uvx grandchildren
+/// included) can be signalled at once
+pub fn isolate(cmd: &mut Command) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+        cmd.creation_flags(CREATE_NEW_PROCESS_GROUP);
+    }
+}
+
+#[cfg(unix)]
+fn signal_group(pgid: u32, signal: Option<nix::sys::signal::Signal>) -> bool {
+    nix::sys::signal::killpg(nix::unistd::Pid::from_raw(pgid as i32), signal).is_ok()
+}
+
+/// True while any process of the group is still alive
+#[cfg(unix)]
+fn group_alive(pgid: u32) -> bool {
+    signal_group(pgid, None) && has_live_member(pgid)
+}
+
+/// Killed grandchildren linger as zombies until init reaps them, which still
+/// makes the group signalable; only count members that are actually running
+#[cfg(target_os = "linux")]
+fn has_live_member(pgid: u32) -> bool {
+    let Ok(entries) = std::fs::read_dir("/proc") else { return true };
+    entries.flatten().filter(|e| e.file_name().to_str().is_some_and(|n| n.bytes().all(|b| b.is_ascii_digit()))).any(|e| {
+        let Ok(stat) = std::fs::read_to_string(e.path().join("stat")) else { return false };
+        let Some((_, rest)) = stat.rsplit_once(')') else { return false };
+        let fields: Vec<&str> = rest.split_whitespace().collect();
+        fields.first().is_some_and(|state| *state != "Z") && fields.get(2).and_then(|g| g.parse::<u32>().ok()) == Some(pgid)
+    })
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn has_live_member(_pgid: u32) -> bool {
+    true
+}
+
+/// Reap the leader and wait for the rest of its group, up to `deadline`.
+/// `reap` returns `Some(exit code)` once the leader is gone.
+#[cfg(unix)]
+fn wait_tree(pgid: u32, reap: &mut dyn FnMut() -> Option<Option<i32>>, deadline: Instant) -> (bool, Option<i32>) {
+    let mut exit_code = None;
+    let mut reaped = false;
+    loop {
+        if !reaped {
+            if let Some(code) = reap() {
+                reaped = true;
+                exit_code = code;
+            }
+        }
+        if reaped && !group_alive(pgid) {
+            return (true, exit_code);
+        }
+        if Instant::now() >= deadline {
+            return (false, exit_code);
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// SIGKILL the group led by `pgid` without waiting; the caller reaps the leader later.
+/// Only for a child not yet reaped, so the id can't have been reused.
+pub fn kill_group(pgid: u32) {
+    #[cfg(unix)]
+    {
+        use nix::sys::signal::{kill, Signal};
+        if !signal_group(pgid, Some(Signal::SIGKILL)) {
+            let _ = kill(nix::unistd::Pid::from_raw(pgid as i32), Signal::SIGKILL);
+        }
+    }
+    #[cfg(windows)]
+    {
+        let _ = Command::new("taskkill").args(["/PID", &pgid.to_string(), "/T", "/F"]).stdout(std::process::Stdio::null()).stderr(std::process::Stdio::null()).spawn();
+    }
+}
+
+/// Immediately SIGKILL the child's whole process group and reap the leader
+pub fn kill_tree(child: &mut Child) {
+    #[cfg(unix)]
+    {
+        use nix::sys::signal::Signal;
+        if !signal_group(child.id(), Some(Signal::SIGKILL)) {
+            let _ = child.kill();
+        }
+    }
+    #[cfg(windows)]
+    {
+        let _ = Command::new("taskkill").args(["/PID", &child.id().to_string(), "/T", "/F"]).output();
+        let _ = child.kill();
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = child.kill();
+    }
+    let _ = child.wait();
+}
+
+/// SIGTERM the group led by `pgid`, wait up to `grace`, then SIGKILL whatever is left and wait
+/// up to `KILL_WAIT`, so a stop never takes longer than `grace + KILL_WAIT`
+#[cfg(unix)]
+fn terminate_group(pgid: u32, reap: &mut dyn FnMut() -> Option<Option<i32>>, grace: Duration, force: bool) -> StopOutcome {
+    use nix::sys::signal::Signal;
+    let started = Instant::now();
+    let elapsed_ms = || started.elapsed().as_millis() as u64;
+
+    if let Some(exit_code) = reap() {
+        if !group_alive(pgid) {
+            return StopOutcome { signal: None, exit_code, elapsed_ms: elapsed_ms() };
+        }
+    }
+
+    if !force && signal_group(pgid, Some(Signal::SIGTERM)) {
+        let (done, exit_code) = wait_tree(pgid, reap, started + grace);
+        if done {
+            return StopOutcome { signal: Some(StopSignal::Sigterm), exit_code, elapsed_ms: elapsed_ms() };
+        }
+        log::warn!("Process group {pgid} ignored SIGTERM for {}ms; sending SIGKILL", grace.as_millis());
+    }
+
+    signal_group(pgid, Some(Signal::SIGKILL));
+    let (_, exit_code) = wait_tree(pgid, reap, Instant::now() + KILL_WAIT);
+    StopOutcome { signal: Some(StopSignal::Sigkill), exit_code, elapsed_ms: elapsed_ms() }
+}
+
+/// Stop a process tree: SIGTERM the group, wait up to `grace`, then SIGKILL whatever is left.
+/// With `force` the SIGTERM step is skipped.
+pub fn terminate(child: &mut Child, grace: Duration, force: bool) -> StopOutcome {
+    #[cfg(unix)]
+    {
+        let pgid = child.id();
+        // Make sure the leader dies even if it somehow left its group
+        let mut reap = || match child.try_wait() {
+            Ok(Some(status)) => Some(status.code()),
+            Ok(None) => None,
+            Err(_) => Some(None),
+        };
+        let outcome = terminate_group(pgid, &mut reap, grace, force);
+        if matches!(child.try_wait(), Ok(None)) {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        outcome
+    }
+
+    #[cfg(not(unix))]
+    {
+        let started = Instant::now();
+        let elapsed_ms = |started: Instant| started.elapsed().as_millis() as u64;
+        // No SIGTERM equivalent for console processes; terminate the tree outright
+        let _ = (grace, force);
+        if let Ok(Some(status)) = child.try_wait() {
+            return StopOutcome { signal: None, exit_code: status.code(), elapsed_ms: elapsed_ms(started) };
+        }
+        kill_tree(child);
+        let exit_code = child.try_wait().ok().flatten().and_then(|s| s.code());
+        StopOutcome { signal: Some(StopSignal::Sigkill), exit_code, elapsed_ms: elapsed_ms(started) }
+    }
+}
+
+/// Stop a process tree the hub did not spawn in this session (an adopted orphan)
+/// through the process group it leads
+#[cfg(unix)]
+pub fn terminate_pid(pid: u32, grace: Duration, force: bool) -> StopOutcome {
+    // Not our child, so it cannot be reaped; its exit code is unknown
+    terminate_group(pid, &mut || Some(None), grace, force)
+}
+
+#[cfg(not(unix))]
+pub fn terminate_pid(pid: u32, grace: Duration, force: bool) -> StopOutcome {
+    let started = Instant::now();
+    let _ = (grace, force);
+    let _ = Command::new("taskkill").args(["/PID", &pid.to_string(), "/T", "/F"]).output();
+    StopOutcome { signal: Some(StopSignal::Sigkill), exit_code: None, elapsed_ms: started.elapsed().as_millis() as u64 }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+
+    /// Spawn `script` in its own group and read the pid of its background grandchild
+    fn spawn_with_grandchild(script: &str) -> (Child, u32) {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", script]).stdout(Stdio::piped());
+        isolate(&mut cmd);
+        let mut child = cmd.spawn().unwrap();
+        let mut line = String::new();
+        BufReader::new(child.stdout.take().unwrap()).read_line(&mut line).unwrap();
+        (child, line.trim().parse().unwrap())
+    }
+
+    /// Reparented grandchildren may linger as zombies until init reaps them
+    fn is_gone(pid: u32) -> bool {
+        match std::fs::read_to_string(format!("/proc/{pid}/stat")) {
+            Ok(stat) => stat.rsplit_once(") ").is_some_and(|(_, rest)| rest.starts_with('Z')),
+            Err(_) => true,
+        }
+    }
+
+    /// Test SIGTERM reaches grandchildren through the process group
+    #[test]
+    fn test_terminate_kills_grandchildren_with_sigterm() {
+        let (mut child, grandchild) = spawn_with_grandchild("sleep 60 & echo $!; wait");
+        let outcome = terminate(&mut child, Duration::from_secs(5), false);
+        assert_eq!(outcome.signal, Some(StopSignal::Sigterm));
+        assert!(outcome.elapsed_ms < 5_000);
+        assert!(is_gone(grandchild));
+    }
+
+    /// Test escalation to SIGKILL when the tree ignores SIGTERM
+    #[test]
+    fn test_terminate_escalates_to_sigkill() {
+        let (mut child, grandchild) = spawn_with_grandchild("trap '' TERM; sleep 60 & echo $!; wait");
+        let outcome = terminate(&mut child, Duration::from_millis(300), false);
+        assert_eq!(outcome.signal, Some(StopSignal::Sigkill));
+        assert!(outcome.elapsed_ms >= 300);
+        assert!(is_gone(grandchild));
+    }
+
+    /// Test an already-exited child is reported without a signal
+    #[test]
+    fn test_terminate_already_exited() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "exit 3"]);
+        isolate(&mut cmd);
+        let mut child = cmd.spawn().unwrap();
+        child.wait().unwrap();
+        let outcome = terminate(&mut child, Duration::from_secs(1), false);
+        assert_eq!(outcome.signal, None);
+        assert_eq!(outcome.exit_code, Some(3));
+        assert_eq!(serde_json::to_value(StopSignal::Sigkill).unwrap(), "SIGKILL");
+    }
+
+    /// Test a group can be stopped knowing only its leader's pid
+    #[test]
+    fn test_terminate_pid() {
+        let (mut child, grandchild) = spawn_with_grandchild("sleep 60 & echo $!; wait");
+        let pid = child.id();
+        // Reap the leader from another thread the way init would for an orphan
+        let reaper = std::thread::spawn(move || child.wait());
+        let outcome = terminate_pid(pid, Duration::from_secs(5), false);
+        assert_eq!(outcome.signal, Some(StopSignal::Sigterm));
+        assert!(reaper.join().unwrap().is_ok());
+        assert!(is_gone(grandchild));
+    }
+}
@@ -0,0 +1,313 @@
+//! Prebuilt release archives: download with SHA-256 verification and extraction of
+//! tar.gz/zip files into an install directory.
+
+use sha2::{Digest, Sha256};
+use std::{
+    fs::File,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(600);
+const CHUNK_SIZE: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    TarGz,
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Format from an archive's file name
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else if name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+/// Rust-style target triple of the running build, as used in release asset names
+pub fn target_triple() -> String {
+    let arch = std::env::consts::ARCH;
+    let rest = match std::env::consts::OS {
+        "macos" => "apple-darwin",
+        "windows" if cfg!(target_env = "gnu") => "pc-windows-gnu",
+        "windows" => "pc-windows-msvc",
+        "linux" if cfg!(target_env = "musl") => "unknown-linux-musl",
+        "linux" => "unknown-linux-gnu",
+        other => other,
+    };
+    format!("{arch}-{rest}")
+}
+
+/// Substitute `{target}`, `{os}`, `{arch}` and `{version}` in a release URL
+pub fn expand_url(template: &str, version: Option<&str>) -> String {
+    let mut url = template
+        .replace("{target}", &target_triple())
+        .replace("{os}", std::env::consts::OS)
+        .replace("{arch}", std::env::consts::ARCH);
+    if let Some(v) = version {
+        url = url.replace("{version}", v);
+    }
+    url
+}
+
+/// Last path segment of a URL, without query or fragment
+pub fn file_name(url: &str) -> &str {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+pub fn is_sha256_hex(s: &str) -> bool {
+    s.len() == 64 && s.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn http_client() -> Result<reqwest::blocking::Client, String> {
+    reqwest::blocking::Client::builder().timeout(DOWNLOAD_TIMEOUT).build().map_err(|e| e.to_string())
+}
+
+/// Fetch a small text resource such as a checksums file
+pub fn fetch_text(url: &str) -> Result<String, String> {
+    let resp = http_client()?.get(url).send().map_err(|e| format!("Failed to fetch {url}: {e}"))?;
+    if !resp.status().is_success() {
+        return Err(format!("Failed to fetch {url}: HTTP {}", resp.status()));
+    }
+    resp.text().map_err(|e| e.to_string())
+}
+
+/// Checksum for `file` in a `sha256sum`-style listing ("<hex>  <name>" or "<hex> *<name>").
+/// A listing holding a single bare hash applies to any file.
+pub fn checksum_for(listing: &str, file: &str) -> Option<String> {
+    let mut bare = Vec::new();
+    for line in listing.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+        let mut parts = line.split_whitespace();
+        let Some(hash) = parts.next().filter(|h| is_sha256_hex(h)) else { continue };
+        match parts.next() {
+            Some(name) if name.trim_start_matches('*').rsplit('/').next() == Some(file) => return Some(hash.to_ascii_lowercase()),
+            Some(_) => {}
+            None => bare.push(hash.to_ascii_lowercase()),
+        }
+    }
+    (bare.len() == 1).then(|| bare.remove(0))
+}
+
+/// Stream `url` into `dest`, returning the SHA-256 of what was written.
+/// `on_progress(downloaded, total)` returns false to abort the download.
+pub fn download(url: &str, dest: &Path, mut on_progress: impl FnMut(u64, Option<u64>) -> bool) -> Result<String, String> {
+    let mut resp = http_client()?.get(url).send().map_err(|e| format!("Failed to download {url}: {e}"))?;
+    if !resp.status().is_success() {
+        return Err(format!("Failed to download {url}: HTTP {}", resp.status()));
+    }
+    let total = resp.content_length();
+    let mut file = File::create(dest).map_err(|e| e.to_string())?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    let mut downloaded = 0u64;
+    loop {
+        let n = resp.read(&mut buf).map_err(|e| format!("Download interrupted: {e}"))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        file.write_all(&buf[..n]).map_err(|e| e.to_string())?;
+        downloaded += n as u64;
+        if !on_progress(downloaded, total) {
+            return Err("Download aborted".into());
+        }
+    }
+    file.flush().map_err(|e| e.to_string())?;
+    Ok(hex(&hasher.finalize()))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Unpack `archive` into `dest`, refusing entries that would land outside it
+pub fn extract(archive: &Path, format: ArchiveFormat, dest: &Path) -> Result<(), String> {
+    let file = File::open(archive).map_err(|e| e.to_string())?;
+    match format {
+        ArchiveFormat::TarGz => {
+            let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(file));
+            tar.set_preserve_permissions(true);
+            for entry in tar.entries().map_err(|e| e.to_string())? {
+                let mut entry = entry.map_err(|e| e.to_string())?;
+                // unpack_in skips entries with `..` or absolute paths
+                if !entry.unpack_in(dest).map_err(|e| format!("Failed to extract archive: {e}"))? {
+                    log::warn!("Skipped unsafe archive entry {:?}", entry.path().unwrap_or_default());
+                }
+            }
+        }
+        ArchiveFormat::Zip => {
+            let mut zip = zip::ZipArchive::new(file).map_err(|e| format!("Invalid zip archive: {e}"))?;
+            for i in 0..zip.len() {
+                let mut entry = zip.by_index(i).map_err(|e| e.to_string())?;
+                let Some(rel) = entry.enclosed_name() else {
+                    log::warn!("Skipped unsafe archive entry {:?}", entry.name());
+                    continue;
+                };
+                let out = dest.join(rel);
+                if entry.is_dir() {
+                    std::fs::create_dir_all(&out).map_err(|e| e.to_string())?;
+                    continue;
+                }
+                if let Some(parent) = out.parent() {
+                    std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                }
+                let mut f = File::create(&out).map_err(|e| e.to_string())?;
+                io::copy(&mut entry, &mut f).map_err(|e| format!("Failed to extract archive: {e}"))?;
+                #[cfg(unix)]
+                if let Some(mode) = entry.unix_mode() {
+                    use std::os::unix::fs::PermissionsExt;
+                    let _ = std::fs::set_permissions(&out, std::fs::Permissions::from_mode(mode & 0o777));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn files_under(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        // Don't follow symlinked directories, which may point outside the install dir
+        if entry.file_type().is_ok_and(|t| t.is_dir()) {
+            files_under(&path, out);
+        } else {
+            out.push(path);
+        }
+    }
+}
+
+fn looks_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        path.metadata().is_ok_and(|m| m.permissions().mode() & 0o111 != 0)
+    }
+    #[cfg(not(unix))]
+    {
+        path.extension().is_some_and(|e| e.eq_ignore_ascii_case("exe"))
+    }
+}
+
+/// Locate the server binary in an extracted release: `hint` as a path relative to `dir` or a
+/// file name anywhere below it, otherwise a file named after `name`, otherwise the only executable.
+/// The result is canonical and always inside `dir`, even through `..` or symlinks.
+pub fn find_entrypoint(dir: &Path, hint: Option<&str>, name: &str) -> Result<PathBuf, String> {
+    let found = locate_entrypoint(dir, hint, name)?;
+    let root = dir.canonicalize().map_err(|e| e.to_string())?;
+    let entry = found.canonicalize().map_err(|e| format!("Entrypoint {}: {e}", found.display()))?;
+    if !entry.starts_with(&root) || !entry.is_file() {
+        return Err(format!("Entrypoint {} is outside the install directory", found.display()));
+    }
+    Ok(entry)
+}
+
+fn locate_entrypoint(dir: &Path, hint: Option<&str>, name: &str) -> Result<PathBuf, String> {
+    let mut files = Vec::new();
+    files_under(dir, &mut files);
+    let named = |wanted: &str| {
+        files.iter().find(|f| {
+            f.file_name().and_then(|n| n.to_str()).is_some_and(|n| n == wanted || n.strip_suffix(".exe") == Some(wanted))
+        })
+    };
+    if let Some(hint) = hint {
+        let direct = dir.join(hint);
+        if direct.is_file() {
+            return Ok(direct);
+        }
+        return named(hint).cloned().ok_or_else(|| format!("Entrypoint {hint} not found in the release archive"));
+    }
+    if let Some(found) = named(name) {
+        return Ok(found.clone());
+    }
+    let executables: Vec<_> = files.iter().filter(|f| looks_executable(f)).collect();
+    match executables.as_slice() {
+        [only] => Ok((*only).clone()),
+        [] => Err("No executable found in the release archive; set an entrypoint".into()),
+        _ => Err("Several executables found in the release archive; set an entrypoint".into()),
+    }
+}
+
+/// Add the executable bits, since zip archives and some tarballs drop them
+pub fn make_executable(path: &Path) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = path.metadata().map_err(|e| e.to_string())?.permissions().mode();
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode | 0o755)).map_err(|e| e.to_string())?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test checksum lookup in sha256sum listings
+    #[test]
+    fn test_checksum_for() {
+        let a = "a".repeat(64);
+        let b = "B".repeat(64);
+        let listing = format!("# checksums\n{a}  server-x86_64-unknown-linux-gnu.tar.gz\n{b} *dist/server-aarch64-apple-darwin.zip\n");
+        assert_eq!(checksum_for(&listing, "server-x86_64-unknown-linux-gnu.tar.gz"), Some(a.clone()));
+        assert_eq!(checksum_for(&listing, "server-aarch64-apple-darwin.zip"), Some(b.to_lowercase()));
+        assert_eq!(checksum_for(&listing, "other.zip"), None);
+        assert_eq!(checksum_for(&format!("{a}\n"), "anything.tar.gz"), Some(a));
+
+        assert_eq!(ArchiveFormat::from_name(file_name("https://x/y/server.TGZ?raw=1")), Some(ArchiveFormat::TarGz));
+        assert_eq!(ArchiveFormat::from_name("server.exe"), None);
+        assert!(expand_url("https://x/v{version}/server-{target}.zip", Some("1.0")).ends_with(&format!("v1.0/server-{}.zip", target_triple())));
+    }
+
+    /// Test zip extraction keeps entries inside the destination and finds the entrypoint
+    #[test]
+    fn test_extract_zip_and_find_entrypoint() {
+        let tmp = tempfile::tempdir().unwrap();
+        let archive = tmp.path().join("server.zip");
+        {
+            let mut zip = zip::ZipWriter::new(File::create(&archive).unwrap());
+            let opts = zip::write::SimpleFileOptions::default();
+            zip.start_file("server-1.0/README.md", opts).unwrap();
+            zip.write_all(b"docs").unwrap();
+            zip.start_file("server-1.0/bin/my-server", opts).unwrap();
+            zip.write_all(b"#!/bin/sh\necho hi\n").unwrap();
+            zip.start_file("../escape.txt", opts).unwrap();
+            zip.write_all(b"nope").unwrap();
+            zip.finish().unwrap();
+        }
+        let dest = tmp.path().join("out");
+        std::fs::create_dir_all(&dest).unwrap();
+        extract(&archive, ArchiveFormat::Zip, &dest).unwrap();
+        assert!(!tmp.path().join("escape.txt").exists());
+
+        let entry = find_entrypoint(&dest, None, "my-server").unwrap();
+        assert_eq!(entry, dest.join("server-1.0/bin/my-server"));
+        assert_eq!(find_entrypoint(&dest, Some("server-1.0/bin/my-server"), "x").unwrap(), entry);
+        assert!(find_entrypoint(&dest, Some("missing"), "x").is_err());
+
+        // Hints and symlinks may not reach outside the install dir
+        let outside = tmp.path().join("outside");
+        std::fs::write(&outside, "x").unwrap();
+        assert!(find_entrypoint(&dest, Some("../outside"), "x").unwrap_err().contains("outside the install directory"));
+        assert!(find_entrypoint(&dest, Some(&outside.to_string_lossy()), "x").is_err());
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&outside, dest.join("link")).unwrap();
+            assert!(find_entrypoint(&dest, Some("link"), "x").is_err());
+        }
+        make_executable(&entry).unwrap();
+        #[cfg(unix)]
+        assert!(looks_executable(&entry));
+    }
+}
@@ -1,7 +1,19 @@
 use keyring::Entry;
+use std::collections::HashMap;
 
 const SERVICE_NAME: &str = "com.tauri.mcp-hub";
 const CREDENTIAL_REGISTRY_KEY: &str = "_credential_registry";
+/// Opening marker of a secret reference in server env values, e.g. `${keyring:api_key_openai}`
+const SECRET_REF_PREFIX: &str = "${keyring:";
+/// Keys server env may reference: user-entered API keys and credentials. The hub's own
+/// secrets (`oauth_token_*`, `bridge_token_*`, `encrypted_*`, the registry) are never exposed.
+const SECRET_REF_ALLOWED_PREFIXES: &[&str] = &["api_key_", "credential_"];
+const SECRET_REF_ALLOWED_KEYS: &[&str] = &["credential"];
+
+fn secret_ref_allowed(key: &str) -> bool {
+    SECRET_REF_ALLOWED_KEYS.contains(&key)
+        || SECRET_REF_ALLOWED_PREFIXES.iter().any(|prefix| key.len() > prefix.len() && key.starts_with(prefix))
+}
 
 /// Get the list of all registered credential keys
 fn get_credential_registry() -> Result<Vec<String>, String> {
@@ -136,6 +148,17 @@ pub fn delete_oauth_token(server_id: String) -> Result<(), String> {
     delete_credential(key)
 }
 
+/// Get the bearer token guarding a server's local HTTP bridge, generating one on first use
+pub fn get_or_create_bridge_token(server_id: &str) -> Result<String, String> {
+    let key = format!("bridge_token_{}", server_id);
+    if let Some(token) = get_credential(key.clone())? {
+        return Ok(token);
+    }
+    let token = nanoid::nanoid!(32);
+    save_credential(key, token.clone())?;
+    Ok(token)
+}
+
 /// Save API key securely
 #[tauri::command]
 pub fn save_api_key(service: String, api_key: String) -> Result<(), String> {
@@ -178,6 +201,44 @@ pub fn delete_encrypted_data(key: String) -> Result<(), String> {
     delete_credential(storage_key)
 }
 
+/// Replace `${keyring:<key>}` references in env values with the stored credentials.
+/// `lookup` fetches a credential by its full key (e.g. `api_key_openai`); only `api_key_*`,
+/// `credential` and `credential_*` keys may be referenced.
+pub fn resolve_env_with(
+    env: &HashMap<String, String>,
+    lookup: impl Fn(&str) -> Result<Option<String>, String>,
+) -> Result<HashMap<String, String>, String> {
+    let mut resolved = HashMap::with_capacity(env.len());
+    for (name, value) in env {
+        let mut out = String::with_capacity(value.len());
+        let mut rest = value.as_str();
+        while let Some(start) = rest.find(SECRET_REF_PREFIX) {
+            out.push_str(&rest[..start]);
+            let after = &rest[start + SECRET_REF_PREFIX.len()..];
+            let end = after
+                .find('}')
+                .ok_or_else(|| format!("Unterminated secret reference in env {}", name))?;
+            let key = &after[..end];
+            if !secret_ref_allowed(key) {
+                return Err(format!("Secret reference '{}' in env {} is not allowed; only the credential key and api_key_* or credential_* keys can be referenced", key, name));
+            }
+            let secret = lookup(key)?
+                .ok_or_else(|| format!("Secret '{}' referenced by env {} is not in secure storage", key, name))?;
+            out.push_str(&secret);
+            rest = &after[end + 1..];
+        }
+        out.push_str(rest);
+        resolved.insert(name.clone(), out);
+    }
+    Ok(resolved)
+}
+
+/// Resolve secret references in a server's env from the system keyring. Only called when
+/// spawning, so the plaintext never reaches disk or the frontend.
+pub fn resolve_env(env: &HashMap<String, String>) -> Result<HashMap<String, String>, String> {
+    resolve_env_with(env, |key| get_credential(key.to_string()))
+}
+
 /// Clear all credentials for this application
 /// WARNING: This will delete all stored credentials
 #[tauri::command]
@@ -370,6 +431,38 @@ mod tests {
         assert!(registry.is_empty() || !registry.is_empty());
     }
 
+    /// Test secret references in env values are resolved through the lookup
+    #[test]
+    fn test_resolve_env_references() {
+        let lookup = |key: &str| Ok(match key {
+            "api_key_openai" => Some("sk-123".to_string()),
+            "credential" => Some("hunter2".to_string()),
+            _ => None,
+        });
+        let env = HashMap::from([
+            ("OPENAI_API_KEY".to_string(), "${keyring:api_key_openai}".to_string()),
+            ("AUTH".to_string(), "Bearer ${keyring:credential}; key=${keyring:api_key_openai}".to_string()),
+            ("PLAIN".to_string(), "${HOME}/x".to_string()),
+        ]);
+        let resolved = resolve_env_with(&env, lookup).unwrap();
+        assert_eq!(resolved["OPENAI_API_KEY"], "sk-123");
+        assert_eq!(resolved["AUTH"], "Bearer hunter2; key=sk-123");
+        assert_eq!(resolved["PLAIN"], "${HOME}/x");
+
+        let missing = HashMap::from([("K".to_string(), "${keyring:api_key_none}".to_string())]);
+        assert!(resolve_env_with(&missing, lookup).unwrap_err().contains("api_key_none"));
+        let unterminated = HashMap::from([("K".to_string(), "${keyring:api_key_openai".to_string())]);
+        assert!(resolve_env_with(&unterminated, lookup).is_err());
+        // The hub's own tokens can't be pulled into a server's env, even when stored
+        let any = |_: &str| Ok(Some("secret".to_string()));
+        for key in ["_credential_registry", "oauth_token_srv", "bridge_token_srv", "encrypted_x", "api_key_", "credentialx", ""] {
+            let env = HashMap::from([("K".to_string(), format!("${{keyring:{key}}}"))]);
+            assert!(resolve_env_with(&env, any).unwrap_err().contains("not allowed"), "{key}");
+        }
+        let env = HashMap::from([("K".to_string(), "${keyring:credential_github}".to_string())]);
+        assert_eq!(resolve_env_with(&env, any).unwrap()["K"], "secret");
+    }
+
     /// Test registry key format consistency
     #[test]
     fn test_registry_functions_exist() {
@@ -0,0 +1,112 @@
+use std::{
+    sync::{atomic::{AtomicBool, Ordering}, mpsc},
+    time::{Duration, Instant},
+};
+
+use crate::{mcp_lifecycle, next_server, process_tree};
+
+/// Total time allowed for stopping everything when the app exits
+pub const SHUTDOWN_DEADLINE: Duration = Duration::from_secs(8);
+/// Reserved at the end of the deadline for the SIGKILL step
+const KILL_MARGIN: Duration = process_tree::KILL_WAIT;
+
+static SHUT_DOWN: AtomicBool = AtomicBool::new(false);
+
+/// Stop the given servers in parallel; returns the ids that could not be stopped before `deadline`
+pub fn stop_servers(server_ids: Vec<String>, deadline: Duration) -> Vec<String> {
+    let started = Instant::now();
+    let grace = deadline.saturating_sub(KILL_MARGIN);
+    let (tx, rx) = mpsc::channel();
+    let mut pending = Vec::new();
+    for server_id in server_ids {
+        let tx = tx.clone();
+        let id = server_id.clone();
+        let spawned = std::thread::Builder::new().name(format!("mcp-{id}-shutdown")).spawn(move || {
+            let result = mcp_lifecycle::stop_server(&id, false, Some(grace));
+            let _ = tx.send((id, result));
+        });
+        match spawned {
+            Ok(_) => pending.push(server_id),
+            Err(e) => log::error!("Failed to stop server {server_id}: {e}"),
+        }
+    }
+    drop(tx);
+
+    let mut failed = Vec::new();
+    while !pending.is_empty() {
+        let remaining = deadline.saturating_sub(started.elapsed());
+        let Ok((server_id, result)) = rx.recv_timeout(remaining) else { break };
+        pending.retain(|id| *id != server_id);
+        match result {
+            Ok(outcome) => log::info!("Stopped server {server_id} on exit ({:?})", outcome.signal),
+            // Already gone from the registry; nothing left to stop
+            Err(e) if e.starts_with("No running process") => {}
+            Err(e) => {
+                log::error!("Failed to stop server {server_id} on exit: {e}");
+                failed.push(server_id);
+            }
+        }
+    }
+    for server_id in &pending {
+        log::error!("Server {server_id} did not stop within {}ms of app exit", deadline.as_millis());
+    }
+    failed.extend(pending);
+    failed
+}
+
+/// Stop every managed MCP server and the Next.js server, at most once per process
+pub fn shutdown_all() {
+    if SHUT_DOWN.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let started = Instant::now();
+    let node = std::thread::spawn(|| next_server::stop(SHUTDOWN_DEADLINE.saturating_sub(KILL_MARGIN)));
+    let failed = stop_servers(mcp_lifecycle::server_ids(), SHUTDOWN_DEADLINE);
+    match node.join() {
+        Ok(Some(outcome)) => log::info!("Stopped Next.js server ({:?})", outcome.signal),
+        Ok(None) => {}
+        Err(_) => log::error!("Failed to stop Next.js server: shutdown thread panicked"),
+    }
+    if failed.is_empty() {
+        log::info!("Shutdown completed in {}ms", started.elapsed().as_millis());
+    } else {
+        log::error!("Shutdown left {} server(s) running: {}", failed.len(), failed.join(", "));
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::mcp_lifecycle::{mcp_get_status, start_server, HealthCheckConfig, StdioConfig};
+
+    /// Test servers are stopped in parallel, escalating within the deadline
+    #[test]
+    fn test_stop_servers_in_parallel() {
+        let ids: Vec<String> = (0..3).map(|i| format!("shutdown-test-{i}")).collect();
+        for id in &ids {
+            let cfg = StdioConfig {
+                command: "sh".to_string(),
+                args: vec!["-c".to_string(), "trap '' TERM; sleep 60".to_string()],
+                health: HealthCheckConfig { handshake: false, ..Default::default() },
+                ..Default::default()
+            };
+            start_server(id.clone(), cfg).unwrap();
+        }
+        std::thread::sleep(Duration::from_millis(100));
+
+        let started = Instant::now();
+        let failed = stop_servers(ids.clone(), Duration::from_millis(3000));
+        assert!(failed.is_empty(), "{failed:?}");
+        // Each server uses its full 2s of grace; serial stops would take at least 6s
+        assert!(started.elapsed() < Duration::from_millis(4000), "{:?}", started.elapsed());
+        for id in ids {
+            assert!(mcp_get_status(id).is_err());
+        }
+    }
+
+    /// Test unknown ids are not reported as failures
+    #[test]
+    fn test_stop_servers_ignores_missing() {
+        assert!(stop_servers(vec!["shutdown-missing".to_string()], Duration::from_secs(1)).is_empty());
+    }
+}
@@ -1,7 +1,17 @@
+use sha2::{Digest, Sha256};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::{AppHandle, Manager};
 
+use crate::mcp_lifecycle::RunRecord;
+
+/// Directory under the app data dir holding one run history file per server
+const RUN_HISTORY_DIR: &str = "run_history";
+/// Runs kept per server; older ones are dropped
+pub const MAX_RUN_RECORDS: usize = 100;
+/// Must match `identifier` in tauri.conf.json, which names the app data directory
+const APP_IDENTIFIER: &str = "com.modelcontextprotocol.mcp-hub-next";
+
 /// Get the app data directory path
 fn get_app_data_dir(app: &AppHandle) -> Result<PathBuf, String> {
     app.path()
@@ -17,6 +27,13 @@ fn ensure_app_data_dir(app: &AppHandle) -> Result<PathBuf, String> {
     Ok(dir)
 }
 
+/// App data directory when running without a Tauri app (the `--mcp-gateway` CLI)
+pub fn default_app_data_dir() -> Result<PathBuf, String> {
+    dirs::data_dir()
+        .map(|dir| dir.join(APP_IDENTIFIER))
+        .ok_or_else(|| "Failed to get app data directory".to_string())
+}
+
 /// Get the app data directory path as a string
 #[tauri::command]
 pub fn get_app_data_path(app: AppHandle) -> Result<String, String> {
@@ -43,6 +60,11 @@ pub fn save_servers(app: AppHandle, servers: String) -> Result<(), String> {
 #[tauri::command]
 pub fn load_servers(app: AppHandle) -> Result<String, String> {
     let dir = get_app_data_dir(&app)?;
+    read_servers(&dir)
+}
+
+/// Read the servers configuration saved in `dir`
+pub fn read_servers(dir: &Path) -> Result<String, String> {
     let file_path = dir.join("servers.json");
     
     if !file_path.exists() {
@@ -208,18 +230,20 @@ pub fn list_backups(app: AppHandle) -> Result<Vec<String>, String> {
     Ok(backup_ids)
 }
 
-/// Clear all application data (for testing/reset purposes)
+/// Clear all application data (for testing/reset purposes), including server
+/// log files and run history
 #[tauri::command]
 pub fn clear_all_data(app: AppHandle) -> Result<(), String> {
     let dir = get_app_data_dir(&app)?;
-
-    if dir.exists() {
-        fs::remove_dir_all(&dir)
-            .map_err(|e| format!("Failed to clear data: {}", e))?;
-        log::info!("Cleared all application data");
-    }
-
-    Ok(())
+    // Log files are held open and written while servers run
+    crate::mcp_logs::with_writes_paused(|| {
+        if dir.exists() {
+            fs::remove_dir_all(&dir)
+                .map_err(|e| format!("Failed to clear data: {}", e))?;
+            log::info!("Cleared all application data");
+        }
+        Ok(())
+    })
 }
 
 /// Save installation metadata to persistent storage
@@ -250,6 +274,84 @@ pub fn load_installation_metadata(app: AppHandle) -> Result<String, String> {
         .map_err(|e| format!("Failed to read installation metadata file: {}", e))
 }
 
+/// Path-safe file name for a user-provided id such as a server id.
+/// Sanitizing alone maps ids like `a/b` and `a_b` to the same name, so a short hash of the
+/// original id is appended
+pub fn safe_file_name(id: &str) -> String {
+    let name: String = id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' { c } else { '_' })
+        .collect();
+    let digest = Sha256::digest(id.as_bytes());
+    let suffix: String = digest[..4].iter().map(|b| format!("{b:02x}")).collect();
+    format!("{name}-{suffix}")
+}
+
+/// File name for a server's run history
+fn run_history_file(dir: &Path, server_id: &str) -> PathBuf {
+    dir.join(format!("{}.json", safe_file_name(server_id)))
+}
+
+/// Runs recorded for a server, oldest first
+pub fn read_run_history(dir: &Path, server_id: &str) -> Result<Vec<RunRecord>, String> {
+    let path = run_history_file(dir, server_id);
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read run history: {}", e))?;
+    let records: Vec<RunRecord> = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse run history: {}", e))?;
+    // Only report this server's runs, even if the file was written under another id
+    Ok(records.into_iter().filter(|r| r.server_id == server_id).collect())
+}
+
+/// Append a run to its server's history, keeping the newest `max_records`
+pub fn append_run_record(dir: &Path, record: &RunRecord, max_records: usize) -> Result<(), String> {
+    fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create run history directory: {}", e))?;
+    let path = run_history_file(dir, &record.server_id);
+    let mut records: Vec<RunRecord> = fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<Vec<RunRecord>>(&content).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|r| r.server_id == record.server_id)
+        .collect();
+    records.push(record.clone());
+    let excess = records.len().saturating_sub(max_records);
+    records.drain(..excess);
+    let json = serde_json::to_string_pretty(&records)
+        .map_err(|e| format!("Failed to serialize run history: {}", e))?;
+    fs::write(&path, json)
+        .map_err(|e| format!("Failed to save run history: {}", e))
+}
+
+/// Record a finished run of a managed server
+pub fn save_run_record(app: &AppHandle, record: &RunRecord) -> Result<(), String> {
+    let dir = ensure_app_data_dir(app)?.join(RUN_HISTORY_DIR);
+    append_run_record(&dir, record, MAX_RUN_RECORDS)
+}
+
+/// Get the recorded runs of a server, newest first, optionally only the `limit` most recent
+/// and only those that crashed
+#[tauri::command]
+pub fn get_server_run_history(
+    app: AppHandle,
+    server_id: String,
+    limit: Option<usize>,
+    crashes_only: Option<bool>,
+) -> Result<Vec<RunRecord>, String> {
+    let dir = get_app_data_dir(&app)?.join(RUN_HISTORY_DIR);
+    let crashes_only = crashes_only.unwrap_or(false);
+    Ok(read_run_history(&dir, &server_id)?
+        .into_iter()
+        .rev()
+        .filter(|r| !crashes_only || r.crashed)
+        .take(limit.unwrap_or(MAX_RUN_RECORDS))
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
@@ -567,5 +669,45 @@ mod tests {
         let entries: Vec<_> = fs::read_dir(temp_dir.path()).unwrap().collect();
         assert_eq!(entries.len(), 4);
     }
+
+    /// Test run history is appended, capped and scoped to its server
+    #[test]
+    fn test_run_history_append_and_cap() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("run_history");
+        let run = |server_id: &str, exit_code: i32| crate::mcp_lifecycle::RunRecord {
+            server_id: server_id.to_string(),
+            exit_code: Some(exit_code),
+            crashed: exit_code != 0,
+            ..Default::default()
+        };
+
+        assert!(super::read_run_history(&dir, "a/b").unwrap().is_empty());
+        for code in 0..5 {
+            super::append_run_record(&dir, &run("a/b", code), 3).unwrap();
+        }
+        // Same sanitized name, separate history and cap
+        super::append_run_record(&dir, &run("a_b", 9), 3).unwrap();
+
+        let history = super::read_run_history(&dir, "a/b").unwrap();
+        let codes: Vec<_> = history.iter().map(|r| r.exit_code.unwrap()).collect();
+        assert_eq!(codes, vec![2, 3, 4]);
+        let other = super::read_run_history(&dir, "a_b").unwrap();
+        assert_eq!(other.iter().map(|r| r.exit_code.unwrap()).collect::<Vec<_>>(), vec![9]);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+    }
+
+    /// Test distinct ids never share a file name and names stay path-safe
+    #[test]
+    fn test_safe_file_name_distinct() {
+        let names: Vec<_> = ["a/b", "a_b", "a:b", "..", "."].iter().map(|id| super::safe_file_name(id)).collect();
+        for (i, name) in names.iter().enumerate() {
+            assert!(!name.contains('/') && !name.contains(':'));
+            assert!(name != "." && name != "..");
+            assert!(names[i + 1..].iter().all(|other| other != name));
+        }
+        assert_eq!(super::safe_file_name("a/b"), super::safe_file_name("a/b"));
+        assert!(super::safe_file_name("a/b").starts_with("a_b-"));
+    }
 }
 